@@ -0,0 +1,448 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ndarray::prelude::*;
+use num_rational::Rational32;
+use structopt::StructOpt;
+use wad::EntryId;
+use wad_gfx::{
+    validate_patch, Flip, ResourceIndex, Sprite, SpriteDef, MAX_SPRITE_FRAMES, SPRITE_NAMESPACES,
+};
+
+use crate::apng::{self, Frame, PngPixel, TICRATE};
+use crate::do_scale;
+use crate::format::{Format, MASK_PALETTE};
+use crate::sprite::{aspect_ratios, draw_sprite, hotspot_bounds};
+
+/// Parse a rotation number: 0 for the front of frames that do not rotate,
+/// 1-8, or ZDoom's 9-16
+pub fn parse_rotation(src: &str) -> Result<u8, &'static str> {
+    match src.parse() {
+        Ok(rotation) if rotation <= 16 => Ok(rotation),
+        _ => Err("rotation must be a number from 0 to 16"),
+    }
+}
+
+fn parse_frames(src: &str) -> Result<Vec<u8>, &'static str> {
+    const FORMAT_ERROR: &str = "frames must be frame letters or ranges, eg A-D or ABCB";
+
+    let frame_number = |c: char| -> Result<u8, &'static str> {
+        let c = c.to_ascii_uppercase();
+        if !c.is_ascii() {
+            return Err(FORMAT_ERROR);
+        }
+        match (c as u8).checked_sub(b'A') {
+            Some(frame) if (frame as usize) < MAX_SPRITE_FRAMES => Ok(frame),
+            _ => Err(FORMAT_ERROR),
+        }
+    };
+
+    let mut frames = vec![];
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        let first = frame_number(c)?;
+        if chars.peek() == Some(&'-') {
+            chars.next();
+            let last = frame_number(chars.next().ok_or(FORMAT_ERROR)?)?;
+            if last < first {
+                return Err(FORMAT_ERROR);
+            }
+            frames.extend(first..=last);
+        } else {
+            frames.push(first);
+        }
+    }
+
+    if frames.is_empty() {
+        return Err(FORMAT_ERROR);
+    }
+
+    Ok(frames)
+}
+
+/// A sequence of sprite frame numbers, where 0 is frame A
+#[derive(Debug, PartialEq)]
+pub struct Frames(pub Vec<u8>);
+
+impl FromStr for Frames {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Frames, &'static str> {
+        parse_frames(s).map(Frames)
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct AnimationOutputOpt {
+    /// Output format: full/f, indexed/i or mask/m. Full color uses the
+    /// alpha channel for transparency. Indexed color does not include
+    /// transparency unless --transparent is given.
+    #[structopt(short = "f", long = "format", default_value = "full")]
+    pub format: Format,
+
+    /// Color index to use for the background
+    #[structopt(short = "b", long = "background")]
+    pub background: Option<u8>,
+
+    /// Mark the background color index as transparent in indexed output
+    #[structopt(long = "transparent")]
+    pub transparent: bool,
+
+    /// Output anamorphic (non-square) pixels. Like the original assets,
+    /// the pixel aspect ratio will be 5:6.
+    #[structopt(short = "a", long = "anamorphic")]
+    pub anamorphic: bool,
+
+    /// Number of times to play the animation, or 0 to loop forever.
+    /// Defaults to looping forever, except for thing state sequences that
+    /// come to an end, which play once.
+    #[structopt(long = "loops")]
+    pub loops: Option<u32>,
+
+    /// Write a numbered sequence of PNG files instead of an animated PNG
+    #[structopt(long = "sequence")]
+    pub sequence: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct AnimateOpt {
+    /// The frames to include, as frame letters and ranges, eg A-D or ABCB
+    #[structopt(long = "frames")]
+    pub frames: Frames,
+
+    /// Which rotation to use, 1-8, or 1-16 for ZDoom-style sprites. Frames
+    /// that look the same from all directions are used as is.
+    #[structopt(
+        short = "r",
+        long = "rotation",
+        default_value = "1",
+        parse(try_from_str = "parse_rotation")
+    )]
+    pub rotation: u8,
+
+    /// Duration of each frame in tics, at 35 tics per second
+    #[structopt(short = "t", long = "tics", default_value = "8")]
+    pub tics: u16,
+
+    #[structopt(flatten)]
+    pub output: AnimationOutputOpt,
+}
+
+pub struct AnimationFrame<'a> {
+    pub sprite: Sprite<'a>,
+    pub flip: bool,
+    pub tics: i32,
+    /// Draw with the fullbright colormap, regardless of the light level
+    pub fullbright: bool,
+}
+
+/// The file name for frame `index` of a numbered sequence
+fn sequence_filename(output: &Path, index: usize) -> PathBuf {
+    let stem = output
+        .file_stem()
+        .map(|x| x.to_string_lossy())
+        .unwrap_or_default();
+    let extension = output
+        .extension()
+        .map(|x| x.to_string_lossy())
+        .unwrap_or_else(|| "png".into());
+    output.with_file_name(format!("{}-{:03}.{}", stem, index, extension))
+}
+
+fn render_frames<Px: Copy + Default>(
+    frames: &[AnimationFrame],
+    scale: usize,
+    scale_aspect: Rational32,
+    background: Px,
+    pixel_mapper: impl Fn(u8, bool) -> Px + Copy,
+) -> Vec<Array2<Px>> {
+    let (y_range, x_range) = hotspot_bounds(frames.iter().map(|frame| &frame.sprite));
+    let canvas_size = (
+        (y_range.end - y_range.start) as usize,
+        (x_range.end - x_range.start) as usize,
+    );
+    let hotspot = (-y_range.start, -x_range.start);
+
+    frames
+        .iter()
+        .map(|frame| {
+            let mut target = Array2::from_elem(canvas_size, background);
+            let flip = Flip {
+                x: frame.flip,
+                y: false,
+            };
+            draw_sprite(target.view_mut(), &frame.sprite, hotspot, flip, |x| {
+                pixel_mapper(x, frame.fullbright)
+            });
+            do_scale(
+                target.view(),
+                scale as u32,
+                Rational32::from(scale as i32) / scale_aspect,
+            )
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_frames<Px: PngPixel>(
+    output: &Path,
+    palette: Option<&[u8]>,
+    transparent: Option<u8>,
+    store_aspect: Rational32,
+    images: &[Array2<Px>],
+    frames: &[AnimationFrame],
+    num_plays: u32,
+    opt: &AnimationOutputOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if opt.sequence {
+        for (index, image) in images.iter().enumerate() {
+            apng::write_apng(
+                sequence_filename(output, index),
+                palette,
+                transparent,
+                store_aspect,
+                &[Frame::still(image.view())],
+                0,
+            )?;
+        }
+        return Ok(());
+    }
+
+    let apng_frames = images
+        .iter()
+        .zip(frames)
+        .map(|(image, frame)| Frame {
+            gfx: image.view(),
+            delay: Rational32::new(frame.tics, TICRATE),
+        })
+        .collect::<Vec<_>>();
+
+    apng::write_apng(
+        output,
+        palette,
+        transparent,
+        store_aspect,
+        &apng_frames,
+        num_plays,
+    )
+}
+
+/// Render the frames as an animation where all frames are registered on
+/// the shared hotspot, on a canvas covering all of them. Fullbright frames
+/// are drawn with `fullbright_colormap` instead of `colormap`.
+#[allow(clippy::too_many_arguments)]
+pub fn write_animation(
+    palette: &[u8],
+    colormap: &[u8],
+    fullbright_colormap: &[u8],
+    frames: &[AnimationFrame],
+    scale: usize,
+    output: &Path,
+    num_plays: u32,
+    opt: &AnimationOutputOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(palette.len(), 768);
+
+    let light = |x: u8, fullbright: bool| {
+        if fullbright {
+            fullbright_colormap[x as usize]
+        } else {
+            colormap[x as usize]
+        }
+    };
+
+    let (scale_aspect, store_aspect) = aspect_ratios(opt.anamorphic);
+
+    if opt.transparent && opt.background.is_none() {
+        return Err("--transparent requires --background".into());
+    }
+
+    match opt.format {
+        Format::Indexed => {
+            let background = opt
+                .background
+                .ok_or("--background must be specified for the indexed format")?;
+            let transparent = Some(background).filter(|_| opt.transparent);
+
+            let images = render_frames(frames, scale, scale_aspect, background, light);
+            write_frames(
+                output,
+                Some(palette),
+                transparent,
+                store_aspect,
+                &images,
+                frames,
+                num_plays,
+                opt,
+            )
+        }
+        Format::Mask => {
+            if opt.background.is_some() {
+                eprintln!("warning: --background has no effect for mask format");
+            }
+
+            let images = render_frames(frames, scale, scale_aspect, 0, |_, _| 1);
+            write_frames(
+                output,
+                Some(MASK_PALETTE),
+                None,
+                store_aspect,
+                &images,
+                frames,
+                num_plays,
+                opt,
+            )
+        }
+        Format::Full => {
+            let colormapper = |x, fullbright| -> [u8; 4] {
+                let i = light(x, fullbright) as usize;
+                let c = &palette[i * 3..i * 3 + 3];
+                [c[0], c[1], c[2], 255]
+            };
+
+            let background = opt
+                .background
+                .map(|x| colormapper(x, false))
+                .unwrap_or_default();
+
+            let images = render_frames(frames, scale, scale_aspect, background, colormapper);
+            write_frames(
+                output,
+                None,
+                None,
+                store_aspect,
+                &images,
+                frames,
+                num_plays,
+                opt,
+            )
+        }
+    }
+}
+
+/// Find the lump to draw for `rotation` of a sprite frame, and whether it
+/// should be drawn flipped. Frames that look the same from all directions
+/// are used for any rotation.
+pub fn find_rotation<'a>(
+    index: &ResourceIndex<'a>,
+    def: &SpriteDef,
+    frame: u8,
+    rotation: u8,
+) -> Result<(Sprite<'a>, bool), String> {
+    let sprite_name = String::from_utf8_lossy(&def.sprite()).into_owned();
+    let letter = (b'A' + frame) as char;
+    let sprite_frame = def
+        .frame(frame)
+        .ok_or_else(|| format!("Unable to find sprite frame {}{}", sprite_name, letter))?;
+    let found = if sprite_frame.rotates() {
+        sprite_frame.rotation(rotation)
+    } else {
+        sprite_frame.rotation(0)
+    };
+    let found = found.ok_or_else(|| {
+        format!(
+            "Sprite frame {}{} is missing rotation {}",
+            sprite_name, letter, rotation
+        )
+    })?;
+    let lump = index
+        .find(&found.lump, SPRITE_NAMESPACES)
+        .ok_or_else(|| format!("Cannot find {}", EntryId::from_bytes(&found.lump)))?;
+    if validate_patch(lump).is_err() {
+        return Err(format!(
+            "{} is not a valid patch",
+            EntryId::from_bytes(&found.lump)
+        ));
+    }
+
+    Ok((Sprite::new(lump), found.flip))
+}
+
+pub fn animate_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: AnimateOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sprite_name = name.to_ascii_uppercase();
+    if sprite_name.len() != 4 {
+        return Err(format!("Expected a four letter sprite name, eg TROO: {:?}", name).into());
+    }
+    let mut sprite = [0; 4];
+    sprite.copy_from_slice(sprite_name.as_bytes());
+
+    let def = SpriteDef::from_wad(index, sprite);
+
+    let frames = opt
+        .frames
+        .0
+        .iter()
+        .map(|&frame| {
+            let (sprite, flip) = find_rotation(index, &def, frame, opt.rotation)?;
+            Ok(AnimationFrame {
+                sprite,
+                flip,
+                tics: opt.tics as i32,
+                fullbright: false,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    write_animation(
+        palette,
+        colormap,
+        colormap,
+        &frames,
+        scale,
+        output.as_ref(),
+        opt.output.loops.unwrap_or(0),
+        &opt.output,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_frames_range() {
+        assert_eq!(parse_frames("A-D"), Ok(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn parse_frames_list() {
+        assert_eq!(parse_frames("abcb"), Ok(vec![0, 1, 2, 1]));
+    }
+
+    #[test]
+    fn parse_frames_mixed() {
+        assert_eq!(parse_frames("A-CE"), Ok(vec![0, 1, 2, 4]));
+    }
+
+    #[test]
+    fn parse_frames_invalid() {
+        assert!(parse_frames("").is_err());
+        assert!(parse_frames("D-A").is_err());
+        assert!(parse_frames("A-").is_err());
+        assert!(parse_frames("1").is_err());
+    }
+
+    #[test]
+    fn parse_rotation_range() {
+        assert_eq!(parse_rotation("0"), Ok(0));
+        assert_eq!(parse_rotation("16"), Ok(16));
+        assert!(parse_rotation("17").is_err());
+        assert!(parse_rotation("-1").is_err());
+    }
+
+    #[test]
+    fn sequence_filename_numbered() {
+        assert_eq!(
+            sequence_filename(Path::new("out/troo.png"), 3),
+            Path::new("out/troo-003.png")
+        );
+    }
+}
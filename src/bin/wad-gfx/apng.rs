@@ -0,0 +1,270 @@
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+use std::path::Path;
+
+use byteorder::{BigEndian, WriteBytesExt};
+use ndarray::prelude::*;
+use num_rational::Rational32;
+
+/// Doom runs its game logic at 35 tics per second
+pub const TICRATE: i32 = 35;
+
+struct PhysChunk {
+    x: i32,
+    y: i32,
+    unit: u8,
+}
+
+impl PhysChunk {
+    fn serialize(&self, w: &mut impl Write) -> std::io::Result<()> {
+        w.write_i32::<BigEndian>(self.x)?;
+        w.write_i32::<BigEndian>(self.y)?;
+        w.write_u8(self.unit)?;
+        Ok(())
+    }
+}
+
+struct AnimationControlChunk {
+    num_frames: u32,
+    num_plays: u32,
+}
+
+impl AnimationControlChunk {
+    fn serialize(&self, w: &mut impl Write) -> std::io::Result<()> {
+        w.write_u32::<BigEndian>(self.num_frames)?;
+        w.write_u32::<BigEndian>(self.num_plays)?;
+        Ok(())
+    }
+}
+
+struct FrameControlChunk {
+    sequence_number: u32,
+    width: u32,
+    height: u32,
+    delay_num: u16,
+    delay_den: u16,
+}
+
+impl FrameControlChunk {
+    fn serialize(&self, w: &mut impl Write) -> std::io::Result<()> {
+        const DISPOSE_OP_NONE: u8 = 0;
+        const BLEND_OP_SOURCE: u8 = 0;
+
+        w.write_u32::<BigEndian>(self.sequence_number)?;
+        w.write_u32::<BigEndian>(self.width)?;
+        w.write_u32::<BigEndian>(self.height)?;
+        w.write_u32::<BigEndian>(0)?; // x_offset
+        w.write_u32::<BigEndian>(0)?; // y_offset
+        w.write_u16::<BigEndian>(self.delay_num)?;
+        w.write_u16::<BigEndian>(self.delay_den)?;
+        w.write_u8(DISPOSE_OP_NONE)?;
+        w.write_u8(BLEND_OP_SOURCE)?;
+        Ok(())
+    }
+}
+
+/// Pixel types that can be written to a PNG file
+pub trait PngPixel: Copy {
+    const COLOR_TYPE: png::ColorType;
+
+    fn as_bytes(pixels: &[Self]) -> &[u8];
+}
+
+impl PngPixel for u8 {
+    const COLOR_TYPE: png::ColorType = png::ColorType::Indexed;
+
+    fn as_bytes(pixels: &[u8]) -> &[u8] {
+        pixels
+    }
+}
+
+impl PngPixel for [u8; 4] {
+    const COLOR_TYPE: png::ColorType = png::ColorType::RGBA;
+
+    fn as_bytes(pixels: &[[u8; 4]]) -> &[u8] {
+        // The following unsafe block is safe because:
+        //  * [u8; 4] has the same alignment as u8
+        //  * The resulting slice covers exactly the same memory
+        unsafe { std::slice::from_raw_parts(pixels.as_ptr() as *const u8, pixels.len() * 4) }
+    }
+}
+
+pub struct Frame<'a, Px> {
+    pub gfx: ArrayView2<'a, Px>,
+
+    /// How long to display this frame, in seconds. A duration in tics is
+    /// exactly representable as n/TICRATE
+    pub delay: Rational32,
+}
+
+impl<'a, Px> Frame<'a, Px> {
+    pub fn still(gfx: ArrayView2<'a, Px>) -> Frame<'a, Px> {
+        Frame {
+            gfx,
+            delay: Rational32::from(0),
+        }
+    }
+}
+
+fn delay_fraction(delay: Rational32) -> Result<(u16, u16), String> {
+    use std::convert::TryFrom;
+
+    // Rational32 is always kept in its reduced form, so if this does not
+    // fit, no equivalent fraction will
+    match (u16::try_from(*delay.numer()), u16::try_from(*delay.denom())) {
+        (Ok(num), Ok(den)) => Ok((num, den)),
+        _ => Err(format!(
+            "Frame delay {} is not representable in APNG",
+            delay
+        )),
+    }
+}
+
+fn compress_frame<Px: PngPixel>(gfx: ArrayView2<Px>) -> std::io::Result<Vec<u8>> {
+    let mut zlib = deflate::write::ZlibEncoder::new(Vec::new(), png::Compression::Best);
+    for row in gfx.genrows() {
+        zlib.write_all(&[0])?; // Filter type: None
+        zlib.write_all(Px::as_bytes(row.as_slice().unwrap()))?;
+    }
+    zlib.finish()
+}
+
+fn write_frame_control(
+    writer: &mut png::Writer<impl Write>,
+    sequence_number: &mut u32,
+    dim: (usize, usize),
+    (delay_num, delay_den): (u16, u16),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut buf = [0u8; 26];
+    let fctl = FrameControlChunk {
+        sequence_number: *sequence_number,
+        width: dim.1 as u32,
+        height: dim.0 as u32,
+        delay_num,
+        delay_den,
+    };
+    fctl.serialize(&mut Cursor::new(&mut buf as &mut [u8]))?;
+    writer.write_chunk(png::chunk::fcTL, &buf)?;
+    *sequence_number += 1;
+    Ok(())
+}
+
+/// Write a PNG file from one or more frames. A single frame gives a
+/// regular PNG file, while multiple frames give an Animated PNG which loops
+/// `num_plays` times, or forever if `num_plays` is 0.
+///
+/// For indexed images, `transparent` designates a palette index to be
+/// rendered fully transparent via the tRNS chunk.
+pub fn write_apng<Px: PngPixel>(
+    filename: impl AsRef<Path>,
+    palette: Option<&[u8]>,
+    transparent: Option<u8>,
+    pixel_aspect: Rational32,
+    frames: &[Frame<Px>],
+    num_plays: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use png::HasParameters;
+
+    let first = frames.first().ok_or("No frames to write")?;
+    let dim = first.gfx.dim();
+
+    assert!(dim.0 <= i32::MAX as usize);
+    assert!(dim.1 <= i32::MAX as usize);
+    for frame in frames {
+        assert_eq!(frame.gfx.dim(), dim, "All frames must have the same size");
+        assert_eq!(frame.gfx.stride_of(Axis(1)), 1);
+        assert_eq!(frame.gfx.stride_of(Axis(0)), dim.1 as isize);
+    }
+
+    if transparent.is_some() && Px::COLOR_TYPE != png::ColorType::Indexed {
+        return Err("Transparent palette index requires indexed color".into());
+    }
+
+    let delays = frames
+        .iter()
+        .map(|frame| delay_fraction(frame.delay))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let file = File::create(filename)?;
+    let w = &mut BufWriter::new(file);
+
+    let mut encoder = png::Encoder::new(w, dim.1 as u32, dim.0 as u32);
+    encoder.set(Px::COLOR_TYPE);
+    encoder.set(png::Compression::Best);
+    let mut writer = encoder.write_header()?;
+
+    if let Some(palette) = palette {
+        writer.write_chunk(png::chunk::PLTE, palette)?;
+    }
+
+    if let Some(transparent) = transparent {
+        let mut alpha = vec![255u8; transparent as usize + 1];
+        alpha[transparent as usize] = 0;
+        writer.write_chunk(png::chunk::tRNS, &alpha)?;
+    }
+
+    if pixel_aspect != Rational32::from(1) {
+        let mut buf = [0u8; 9];
+        let phys_chunk = PhysChunk {
+            x: *pixel_aspect.numer(),
+            y: *pixel_aspect.denom(),
+            unit: 0,
+        };
+        phys_chunk.serialize(&mut Cursor::new(&mut buf as &mut [u8]))?;
+        writer.write_chunk(png::chunk::pHYs, &buf)?;
+    }
+
+    // Sequence numbers are shared between fcTL and fdAT chunks
+    let mut sequence_number = 0;
+
+    if frames.len() > 1 {
+        let mut buf = [0u8; 8];
+        let actl = AnimationControlChunk {
+            num_frames: frames.len() as u32,
+            num_plays,
+        };
+        actl.serialize(&mut Cursor::new(&mut buf as &mut [u8]))?;
+        writer.write_chunk(png::chunk::acTL, &buf)?;
+
+        write_frame_control(&mut writer, &mut sequence_number, dim, delays[0])?;
+    }
+
+    writer.write_image_data(Px::as_bytes(first.gfx.into_slice().unwrap()))?;
+
+    for (frame, &delay) in frames.iter().zip(&delays).skip(1) {
+        write_frame_control(&mut writer, &mut sequence_number, dim, delay)?;
+
+        let mut fdat = vec![];
+        fdat.write_u32::<BigEndian>(sequence_number)?;
+        fdat.extend(compress_frame(frame.gfx)?);
+        sequence_number += 1;
+        writer.write_chunk(png::chunk::fdAT, &fdat)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tics_as_exact_fraction() {
+        let tics = |n| Rational32::new(n, TICRATE);
+        assert_eq!(delay_fraction(tics(1)), Ok((1, 35)));
+        assert_eq!(delay_fraction(tics(8)), Ok((8, 35)));
+        assert_eq!(delay_fraction(tics(35)), Ok((1, 1)));
+        assert_eq!(delay_fraction(tics(14)), Ok((2, 5)));
+    }
+
+    #[test]
+    fn unrepresentable_delay() {
+        assert!(delay_fraction(Rational32::new(1, 100_000)).is_err());
+    }
+
+    #[test]
+    fn rgba_as_bytes() {
+        let pixels = [[1, 2, 3, 4], [5, 6, 7, 8]];
+        assert_eq!(<[u8; 4]>::as_bytes(&pixels), &[1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,636 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ndarray::prelude::*;
+use ndarray::s;
+use num_rational::Rational32;
+use serde::Serialize;
+use structopt::StructOpt;
+use wad::EntryId;
+use wad_gfx::{
+    parse_pnames, render_texture_planes, validate_patch, Flat, Flip, LazyPatchProvider, Namespace,
+    PatchProvider, ResourceIndex, Sprite, SpriteLumpName, TextureSet, PATCH_NAMESPACES,
+};
+
+use crate::apng::{self, Frame};
+use crate::do_scale;
+use crate::format::Format;
+use crate::parallel::{default_threads, parallel_map};
+use crate::pattern::glob_match;
+use crate::sprite::draw_sprite;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Kind {
+    Sprites,
+    Patches,
+    Flats,
+    Textures,
+}
+
+impl FromStr for Kind {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Kind, &'static str> {
+        match s {
+            "sprites" => Ok(Kind::Sprites),
+            "s" => Ok(Kind::Sprites),
+            "patches" => Ok(Kind::Patches),
+            "p" => Ok(Kind::Patches),
+            "flats" => Ok(Kind::Flats),
+            "f" => Ok(Kind::Flats),
+            "textures" => Ok(Kind::Textures),
+            "t" => Ok(Kind::Textures),
+            _ => Err("kind must be 'sprites'/'s', 'patches'/'p', 'flats'/'f' or 'textures'/'t'"),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct AtlasOpt {
+    /// What to pack: sprites/s, patches/p, flats/f or textures/t
+    #[structopt(default_value = "sprites")]
+    pub kind: Kind,
+
+    /// Maximum width and height of each page. Must be a power of two
+    #[structopt(long = "max-size", default_value = "1024")]
+    pub max_size: u32,
+
+    /// Space between images, in output pixels
+    #[structopt(long = "padding", default_value = "1")]
+    pub padding: u32,
+
+    /// Output format: full/f or indexed/i. Full color uses the alpha
+    /// channel for transparency. Indexed color does not include
+    /// transparency unless --transparent is given.
+    #[structopt(short = "f", long = "format", default_value = "full")]
+    pub format: Format,
+
+    /// Color index to use for the background
+    #[structopt(short = "b", long = "background")]
+    pub background: Option<u8>,
+
+    /// Mark the background color index as transparent in indexed output
+    #[structopt(long = "transparent")]
+    pub transparent: bool,
+
+    /// Number of threads to render textures on. Defaults to the number of
+    /// CPU cores. The output is the same regardless.
+    #[structopt(short = "j", long = "jobs")]
+    pub jobs: Option<usize>,
+}
+
+/// One image to place in the atlas, as palette indices where None is
+/// transparent
+struct Image {
+    name: String,
+    pixels: Array2<Option<u8>>,
+    /// The left and top offsets of the graphic, as in the patch header
+    offset: (i32, i32),
+    /// Names of the sprite rotations that use this image drawn mirrored
+    mirrors: Vec<String>,
+}
+
+impl Image {
+    fn from_patch(name: String, sprite: &Sprite) -> Image {
+        let (top, left) = sprite.origin();
+        let mut pixels = Array2::from_elem(sprite.dim(), None);
+        draw_sprite(
+            pixels.view_mut(),
+            sprite,
+            (top as i32, left as i32),
+            Flip::default(),
+            Some,
+        );
+        Image {
+            name,
+            pixels,
+            offset: (left as i32, top as i32),
+            mirrors: vec![],
+        }
+    }
+}
+
+fn collect_images(
+    index: &ResourceIndex,
+    kind: Kind,
+    pattern: &str,
+    threads: usize,
+) -> Result<Vec<Image>, Box<dyn std::error::Error>> {
+    let pattern = pattern.as_bytes();
+    let mut images = vec![];
+
+    match kind {
+        Kind::Sprites => {
+            for i in index.indices(Namespace::Sprites) {
+                if index.is_overridden(i) {
+                    continue;
+                }
+                let wad::Entry { id, lump } = index.entry(i)?;
+                let parsed = match SpriteLumpName::parse(id.as_bytes()) {
+                    Some(parsed) => parsed,
+                    None => continue,
+                };
+                let name = id.to_string();
+                if !glob_match(pattern, name.as_bytes()) {
+                    continue;
+                }
+                if let Err(err) = validate_patch(lump) {
+                    eprintln!("warning: Skipping sprite {}: {}", name, err);
+                    continue;
+                }
+
+                let mut image = Image::from_patch(name.clone(), &Sprite::new(lump));
+                if parsed.mirror.is_some() {
+                    image.mirrors.push(format!("{}{}", &name[..4], &name[6..]));
+                }
+                images.push(image);
+            }
+        }
+        Kind::Patches => {
+            let pnames = parse_pnames(index.by_id(b"PNAMES").ok_or("Missing PNAMES")?);
+            let mut seen = std::collections::BTreeSet::new();
+            for pname in pnames {
+                let name = EntryId::from_bytes(pname).to_string();
+                if !glob_match(pattern, name.as_bytes()) || !seen.insert(name.clone()) {
+                    continue;
+                }
+                match index.find(pname, PATCH_NAMESPACES) {
+                    Some(lump) => match validate_patch(lump) {
+                        Ok(()) => images.push(Image::from_patch(name, &Sprite::new(lump))),
+                        Err(err) => eprintln!("warning: Skipping patch {}: {}", name, err),
+                    },
+                    None => eprintln!("warning: Cannot find patch {}", name),
+                }
+            }
+        }
+        Kind::Flats => {
+            for i in index.indices(Namespace::Flats) {
+                if index.is_overridden(i) {
+                    continue;
+                }
+                let wad::Entry { id, lump } = index.entry(i)?;
+                let name = id.to_string();
+                if !glob_match(pattern, name.as_bytes()) {
+                    continue;
+                }
+                // Skips anything in the namespace that is not a flat
+                let flat = match Flat::new(lump) {
+                    Ok(flat) => flat,
+                    Err(_) => continue,
+                };
+                images.push(Image {
+                    name,
+                    pixels: flat.view().map(|&x| Some(x)),
+                    offset: (0, 0),
+                    mirrors: vec![],
+                });
+            }
+        }
+        Kind::Textures => {
+            let pnames = parse_pnames(index.by_id(b"PNAMES").ok_or("Missing PNAMES")?);
+            let patch_provider = LazyPatchProvider::new(index, pnames);
+
+            let textures: Vec<_> = TextureSet::from_index(index)
+                .iter()
+                .map(|texture| (EntryId::from_bytes(&texture.name()).to_string(), texture))
+                .filter(|(name, _)| glob_match(pattern, name.as_bytes()))
+                .collect();
+
+            let rendered = parallel_map(&textures, threads, |(name, texture)| {
+                let texture = *texture;
+                let missing = (0..texture.len())
+                    .any(|p| patch_provider.patch(texture.patch(p).patch_id).is_none());
+                if missing {
+                    return None;
+                }
+                let (pixels, mask) = render_texture_planes(texture, &patch_provider);
+                Some(Image {
+                    name: name.clone(),
+                    pixels: Array2::from_shape_fn(pixels.dim(), |i| {
+                        Some(pixels[i]).filter(|_| mask[i])
+                    }),
+                    offset: (0, 0),
+                    mirrors: vec![],
+                })
+            });
+            for ((name, _), image) in textures.iter().zip(rendered) {
+                match image {
+                    Some(image) => images.push(image),
+                    None => eprintln!(
+                        "warning: Skipping texture {} with missing or malformed patches",
+                        name
+                    ),
+                }
+            }
+        }
+    }
+
+    Ok(images)
+}
+
+/// Skyline bottom-left bin packer for a single page. The skyline is a list
+/// of (x, y, width) segments covering the page from left to right, where y
+/// is the lowest free row above each segment.
+struct Skyline {
+    width: u32,
+    height: u32,
+    segments: Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    fn new(width: u32, height: u32) -> Skyline {
+        Skyline {
+            width,
+            height,
+            segments: vec![(0, 0, width)],
+        }
+    }
+
+    /// The y coordinate a rectangle starting at the given segment would be
+    /// placed at, if it fits
+    fn fit(&self, index: usize, w: u32, h: u32) -> Option<u32> {
+        let x = self.segments[index].0;
+        if x + w > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut remaining = w as i64;
+        for &(_, seg_y, seg_width) in &self.segments[index..] {
+            if remaining <= 0 {
+                break;
+            }
+            y = y.max(seg_y);
+            remaining -= seg_width as i64;
+        }
+
+        Some(y).filter(|y| y + h <= self.height)
+    }
+
+    /// Place a rectangle as low as possible, and then as far left as
+    /// possible, and return its (x, y) position
+    fn insert(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        let (index, x, y) = (0..self.segments.len())
+            .filter_map(|i| self.fit(i, w, h).map(|y| (i, self.segments[i].0, y)))
+            .min_by_key(|&(_, x, y)| (y + h, x))?;
+
+        self.segments.insert(index, (x, y + h, w));
+
+        // Shrink or remove the segments now covered by the new one
+        let right = x + w;
+        let i = index + 1;
+        while i < self.segments.len() && self.segments[i].0 < right {
+            let (seg_x, seg_y, seg_width) = self.segments[i];
+            let shrink = right - seg_x;
+            if shrink >= seg_width {
+                self.segments.remove(i);
+            } else {
+                self.segments[i] = (right, seg_y, seg_width - shrink);
+                break;
+            }
+        }
+
+        // Merge neighbouring segments at the same height
+        self.segments.dedup_by(|b, a| {
+            if a.1 == b.1 {
+                a.2 += b.2;
+                true
+            } else {
+                false
+            }
+        });
+
+        Some((x, y))
+    }
+}
+
+/// Pack rectangles of the given (width, height) into as many pages of at
+/// most `max_size` square as needed. Returns, for each page, the index and
+/// (x, y) position of each rectangle placed on it. Larger rectangles are
+/// placed first, for a tighter packing.
+fn pack(sizes: &[(u32, u32)], max_size: u32, padding: u32) -> Vec<Vec<(usize, (u32, u32))>> {
+    let mut remaining: Vec<usize> = (0..sizes.len()).collect();
+    remaining.sort_by_key(|&i| (std::cmp::Reverse(sizes[i].1), std::cmp::Reverse(sizes[i].0)));
+
+    let mut pages = vec![];
+    while !remaining.is_empty() {
+        // Padding is added after each rectangle, so the page is extended
+        // by the padding to let rectangles touch the far edges
+        let mut skyline = Skyline::new(max_size + padding, max_size + padding);
+        let mut page = vec![];
+        let mut left_over = vec![];
+
+        for i in remaining {
+            let (w, h) = sizes[i];
+            match skyline.insert(w + padding, h + padding) {
+                Some(pos) => page.push((i, pos)),
+                None => left_over.push(i),
+            }
+        }
+
+        assert!(!page.is_empty(), "Rectangle larger than the page");
+        pages.push(page);
+        remaining = left_over;
+    }
+
+    pages
+}
+
+#[derive(Serialize)]
+struct Rect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Serialize)]
+struct Size {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Serialize)]
+struct Point<T> {
+    x: T,
+    y: T,
+}
+
+/// One frame in TexturePacker's JSON hash format, extended with the offsets
+/// of the graphic and whether it is to be drawn mirrored
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AtlasFrame {
+    frame: Rect,
+    rotated: bool,
+    trimmed: bool,
+    sprite_source_size: Rect,
+    source_size: Size,
+    pivot: Point<f64>,
+    left_offset: i32,
+    top_offset: i32,
+    mirrored: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    app: &'static str,
+    version: &'static str,
+    image: String,
+    format: &'static str,
+    size: Size,
+    scale: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_multi_packs: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct AtlasJson {
+    frames: BTreeMap<String, AtlasFrame>,
+    meta: Meta,
+}
+
+/// The file names of each page, as (image, metadata). A single page is
+/// written to the output file name, while multiple pages are numbered.
+fn page_filenames(output: &Path, pages: usize) -> Vec<(PathBuf, PathBuf)> {
+    let stem = output
+        .file_stem()
+        .map(|x| x.to_string_lossy())
+        .unwrap_or_default();
+
+    (0..pages)
+        .map(|page| {
+            let image = if pages == 1 {
+                output.to_owned()
+            } else {
+                output.with_file_name(format!("{}-{}.png", stem, page))
+            };
+            let json = image.with_extension("json");
+            (image, json)
+        })
+        .collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|x| x.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+pub fn atlas_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: AtlasOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(palette.len(), 768);
+
+    if !opt.max_size.is_power_of_two() {
+        return Err("--max-size must be a power of two".into());
+    }
+    if opt.transparent && opt.background.is_none() {
+        return Err("--transparent requires --background".into());
+    }
+    let (format_name, background) = match opt.format {
+        Format::Full => ("RGBA8888", None),
+        Format::Indexed => (
+            "INDEXED",
+            Some(
+                opt.background
+                    .ok_or("--background must be specified for the indexed format")?,
+            ),
+        ),
+        Format::Mask => return Err("The mask format is not supported for atlases".into()),
+    };
+
+    let images = collect_images(
+        index,
+        opt.kind,
+        name,
+        opt.jobs.unwrap_or_else(default_threads),
+    )?;
+    if images.is_empty() {
+        return Err(format!("No images matching {:?}", name).into());
+    }
+
+    let scaled: Vec<Array2<Option<u8>>> = images
+        .iter()
+        .map(|image| {
+            do_scale(
+                image.pixels.view(),
+                scale as u32,
+                Rational32::from(scale as i32),
+            )
+        })
+        .collect();
+
+    let sizes: Vec<(u32, u32)> = scaled
+        .iter()
+        .map(|x| (x.dim().1 as u32, x.dim().0 as u32))
+        .collect();
+    if let Some((i, _)) = sizes
+        .iter()
+        .enumerate()
+        .find(|(_, &(w, h))| w > opt.max_size || h > opt.max_size)
+    {
+        return Err(format!(
+            "{} is {}x{}, which does not fit in --max-size {}",
+            images[i].name, sizes[i].0, sizes[i].1, opt.max_size
+        )
+        .into());
+    }
+
+    let pages = pack(&sizes, opt.max_size, opt.padding);
+    let filenames = page_filenames(output.as_ref(), pages.len());
+
+    for (page_index, page) in pages.iter().enumerate() {
+        let page_width = page
+            .iter()
+            .map(|&(i, (x, _))| x + sizes[i].0)
+            .max()
+            .unwrap_or(1)
+            .next_power_of_two();
+        let page_height = page
+            .iter()
+            .map(|&(i, (_, y))| y + sizes[i].1)
+            .max()
+            .unwrap_or(1)
+            .next_power_of_two();
+
+        let mut target: Array2<Option<u8>> =
+            Array2::from_elem((page_height as usize, page_width as usize), None);
+        let mut frames = BTreeMap::new();
+
+        for &(i, (x, y)) in page {
+            let (w, h) = sizes[i];
+            target
+                .slice_mut(s![
+                    y as usize..(y + h) as usize,
+                    x as usize..(x + w) as usize
+                ])
+                .assign(&scaled[i]);
+
+            let image = &images[i];
+            let (left, top) = (image.offset.0 * scale as i32, image.offset.1 * scale as i32);
+            let frame = |mirrored| AtlasFrame {
+                frame: Rect { x, y, w, h },
+                rotated: false,
+                trimmed: false,
+                sprite_source_size: Rect { x: 0, y: 0, w, h },
+                source_size: Size { w, h },
+                pivot: Point {
+                    x: left as f64 / w as f64,
+                    y: top as f64 / h as f64,
+                },
+                left_offset: left,
+                top_offset: top,
+                mirrored,
+            };
+
+            frames.insert(image.name.clone(), frame(false));
+            for mirror in &image.mirrors {
+                frames.insert(mirror.clone(), frame(true));
+            }
+        }
+
+        let (image_filename, json_filename) = &filenames[page_index];
+        let related_multi_packs = filenames
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != page_index)
+            .map(|(_, (_, json))| file_name(json))
+            .collect();
+
+        let json = AtlasJson {
+            frames,
+            meta: Meta {
+                app: "wad-gfx",
+                version: env!("CARGO_PKG_VERSION"),
+                image: file_name(image_filename),
+                format: format_name,
+                size: Size {
+                    w: page_width,
+                    h: page_height,
+                },
+                scale: scale.to_string(),
+                related_multi_packs,
+            },
+        };
+
+        match background {
+            Some(background) => {
+                let transparent = Some(background).filter(|_| opt.transparent);
+                let indexed = target.map(|x| x.map(|x| colormap[x as usize]).unwrap_or(background));
+                apng::write_apng(
+                    image_filename,
+                    Some(palette),
+                    transparent,
+                    Rational32::from(1),
+                    &[Frame::still(indexed.view())],
+                    0,
+                )?;
+            }
+            None => {
+                let rgba = target.map(|x| match x {
+                    Some(x) => {
+                        let i = colormap[*x as usize] as usize;
+                        [palette[i * 3], palette[i * 3 + 1], palette[i * 3 + 2], 255]
+                    }
+                    None => [0, 0, 0, 0],
+                });
+                apng::write_apng(
+                    image_filename,
+                    None,
+                    None,
+                    Rational32::from(1),
+                    &[Frame::still(rgba.view())],
+                    0,
+                )?;
+            }
+        }
+
+        std::fs::write(json_filename, serde_json::to_string_pretty(&json)?)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pack_no_overlap() {
+        let sizes = [(40, 30), (64, 64), (10, 50), (64, 64), (30, 30), (5, 5)];
+        let padding = 1;
+        let pages = pack(&sizes, 256, padding);
+        assert_eq!(pages.len(), 1);
+
+        let page = &pages[0];
+        assert_eq!(page.len(), sizes.len());
+        for (a, &(i, (ax, ay))) in page.iter().enumerate() {
+            let (aw, ah) = sizes[i];
+            assert!(ax + aw <= 256 && ay + ah <= 256);
+            for &(j, (bx, by)) in &page[a + 1..] {
+                let (bw, bh) = sizes[j];
+                let apart = ax + aw + padding <= bx
+                    || bx + bw + padding <= ax
+                    || ay + ah + padding <= by
+                    || by + bh + padding <= ay;
+                assert!(apart, "{} and {} overlap", i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn pack_overflows_to_more_pages() {
+        let sizes = [(64, 64); 5];
+        let pages = pack(&sizes, 128, 0);
+        assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), vec![4, 1]);
+    }
+}
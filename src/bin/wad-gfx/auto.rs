@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use ndarray::prelude::*;
+use num_rational::Rational32;
+use wad::EntryId;
+use wad_gfx::{detect_graphic, GraphicFormat, Namespace, ResourceIndex, TextureSet, SCREEN_DIM};
+
+use crate::flat::flat_cmd;
+use crate::format::Format;
+use crate::sprite::{aspect_ratios, sprite_cmd, SpriteOpt};
+use crate::texture::{composite_texture, write_planes};
+use crate::{do_scale, write_png};
+
+/// Where to look for a lump when its type is not known. Sprites come first,
+/// as the sprite namespace is the one most often shadowed by other lumps.
+const AUTO_NAMESPACES: &[Namespace] = &[
+    Namespace::Sprites,
+    Namespace::Global,
+    Namespace::Patches,
+    Namespace::Flats,
+];
+
+/// Find a lump by name in any namespace and detect its format
+pub fn find_graphic<'a>(
+    index: &ResourceIndex<'a>,
+    name: &str,
+) -> Result<(&'a [u8], GraphicFormat), String> {
+    let id = EntryId::from_str(name).ok_or_else(|| format!("Invalid ID: {:?}", name))?;
+    let lump_index = index
+        .find_index(id, AUTO_NAMESPACES)
+        .ok_or_else(|| format!("Cannot find {}", name))?;
+    let data = index.entry(lump_index).map_err(|x| x.to_string())?.lump;
+
+    let format = detect_graphic(data, index.namespace(lump_index))
+        .ok_or_else(|| format!("{} is not a graphic ({} bytes)", name, data.len()))?;
+
+    Ok((data, format))
+}
+
+fn is_lump(index: &ResourceIndex, name: &str) -> bool {
+    EntryId::from_str(name).is_some_and(|id| index.find_index(id, AUTO_NAMESPACES).is_some())
+}
+
+/// Check that a lump looked up for a subcommand is in the format the
+/// subcommand expects, to fail with a useful message rather than produce
+/// noise
+pub fn expect_format(
+    name: &str,
+    data: &[u8],
+    namespace: Option<Namespace>,
+    expected: GraphicFormat,
+) -> Result<(), String> {
+    match detect_graphic(data, namespace) {
+        Some(format) if format == expected => Ok(()),
+        Some(format) => Err(format!(
+            "{} is a {}, not a {}. Leave out the subcommand to extract it as a {}",
+            name,
+            format.name(),
+            expected.name(),
+            format.name()
+        )),
+        None => Err(format!("{} is not a {}", name, expected.name())),
+    }
+}
+
+pub fn screen_cmd(
+    palette: &[u8],
+    colormap: &[u8],
+    gfx: &[u8],
+    scale: usize,
+    output: impl AsRef<Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let screen = ArrayView2::from_shape(SCREEN_DIM, gfx)?;
+    let mapped = screen.map(|&x| colormap[x as usize]);
+
+    let (scale_aspect, store_aspect) = aspect_ratios(false);
+    let scaled = do_scale(
+        mapped.view(),
+        scale as u32,
+        Rational32::from(scale as i32) / scale_aspect,
+    );
+
+    write_png(output, Some(palette), store_aspect, scaled.view())
+}
+
+/// Extract a graphic as whatever format it is in
+pub fn auto_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (gfx, format) = match find_graphic(index, name) {
+        Ok(found) => found,
+        // Names that are not lumps may be textures
+        Err(err) => match TextureSet::from_index(index).find(name.as_bytes()) {
+            Some(texture) if !is_lump(index, name) => {
+                let (pixels, mask) = composite_texture(index, texture)?;
+                return write_planes(
+                    palette,
+                    colormap,
+                    pixels.view(),
+                    mask.view(),
+                    scale,
+                    output,
+                    Format::Full,
+                    None,
+                    false,
+                );
+            }
+            _ => return Err(err.into()),
+        },
+    };
+
+    match format {
+        GraphicFormat::Patch => sprite_cmd(
+            palette,
+            colormap,
+            gfx,
+            scale,
+            output,
+            SpriteOpt {
+                canvas_size: None,
+                pos: None,
+                info: false,
+                format: Format::Full,
+                background: None,
+                anamorphic: false,
+                flip: false,
+            },
+        ),
+        GraphicFormat::Flat => flat_cmd(palette, colormap, gfx, scale, output),
+        GraphicFormat::RawScreen => screen_cmd(palette, colormap, gfx, scale, output),
+        GraphicFormat::Png => {
+            eprintln!("{} is a PNG, which is written as is", name);
+            std::fs::write(output, gfx)?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::Serialize;
+use structopt::StructOpt;
+use wad_gfx::{
+    check_vanilla, check_wad, map_names, parse_pnames, validate_pnames, LazyPatchProvider, Map,
+    Problem, ResourceIndex, Severity, TextureSet,
+};
+
+use crate::pattern::glob_match;
+
+#[derive(Debug, StructOpt)]
+pub struct CheckOpt {
+    /// Output the problems as JSON
+    #[structopt(long = "json")]
+    pub json: bool,
+
+    /// Also report the quirks of the vanilla renderer that affect textures:
+    /// tutti-frutti, medusa, negative-y-clamp and non-power-of-two-width.
+    /// Medusa is a warning only for textures used as masked mid-textures in
+    /// the maps
+    #[structopt(long = "vanilla")]
+    pub vanilla: bool,
+
+    /// The least severe problems to report: info/i, warning/w or error/e
+    #[structopt(long = "severity", default_value = "info")]
+    pub severity: Severity,
+}
+
+#[derive(Serialize)]
+struct ProblemJson<'a> {
+    severity: &'static str,
+    check: &'static str,
+    subject: &'a str,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct CheckJson<'a> {
+    problems: Vec<ProblemJson<'a>>,
+    errors: usize,
+    warnings: usize,
+    infos: usize,
+}
+
+fn count(problems: &[&Problem], severity: Severity) -> usize {
+    problems.iter().filter(|x| x.severity == severity).count()
+}
+
+/// Check the graphics of the WADs for problems that the engines choke on.
+/// Problems with subjects not matching the pattern are left out. The report
+/// goes to the output file if one is given, and to stdout otherwise. Fails
+/// if any errors are reported.
+pub fn check_cmd(
+    index: &ResourceIndex,
+    pattern: &str,
+    output: Option<impl AsRef<Path>>,
+    opt: CheckOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pattern = pattern.to_ascii_uppercase();
+    let mut all = check_wad(index);
+    if opt.vanilla {
+        if let Some(pnames) = index
+            .by_id(b"PNAMES")
+            .filter(|x| validate_pnames(x).is_ok())
+        {
+            let patch_provider = LazyPatchProvider::new(index, parse_pnames(pnames));
+            let mut maps = vec![];
+            for name in map_names(index) {
+                match Map::from_index(index, &name) {
+                    Ok(map) => maps.push(map),
+                    Err(err) => eprintln!("warning: Skipping map {}: {}", name, err),
+                }
+            }
+            all.extend(check_vanilla(
+                &TextureSet::from_index(index),
+                &patch_provider,
+                &maps,
+            ));
+        }
+    }
+    let problems: Vec<&Problem> = all
+        .iter()
+        .filter(|x| x.severity >= opt.severity)
+        .filter(|x| {
+            glob_match(
+                pattern.as_bytes(),
+                x.subject.to_ascii_uppercase().as_bytes(),
+            )
+        })
+        .collect();
+
+    let errors = count(&problems, Severity::Error);
+    let warnings = count(&problems, Severity::Warning);
+    let infos = count(&problems, Severity::Info);
+
+    let mut out = if opt.json {
+        let json = CheckJson {
+            problems: problems
+                .iter()
+                .map(|x| ProblemJson {
+                    severity: x.severity.name(),
+                    check: x.check.name(),
+                    subject: &x.subject,
+                    message: &x.message,
+                })
+                .collect(),
+            errors,
+            warnings,
+            infos,
+        };
+        serde_json::to_string_pretty(&json)?
+    } else {
+        let mut out = String::new();
+        for problem in &problems {
+            writeln!(
+                out,
+                "{:7} {:8} {}: {}",
+                problem.severity.name(),
+                problem.subject,
+                problem.check.name(),
+                problem.message
+            )?;
+        }
+        write!(
+            out,
+            "{} errors, {} warnings, {} infos",
+            errors, warnings, infos
+        )?;
+        out
+    };
+    out.push('\n');
+
+    match output {
+        Some(output) => std::fs::write(output, out)?,
+        None => print!("{}", out),
+    }
+
+    if errors > 0 {
+        return Err(format!("{} errors found", errors).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,377 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use ndarray::prelude::*;
+use num_rational::Rational32;
+use structopt::StructOpt;
+use wad::EntryId;
+use wad_gfx::{
+    detect_graphic, parse_pnames, render_texture_planes, GraphicFormat, LazyPatchProvider,
+    Namespace, PatchProvider, ResourceIndex, Sprite, Texture, TextureSet,
+};
+
+use crate::auto::screen_cmd;
+use crate::flat::flat_cmd;
+use crate::format::Format;
+use crate::parallel::{default_threads, parallel_map};
+use crate::pattern::glob_match;
+use crate::sprite::{sprite_cmd, SpriteOpt};
+use crate::texture::write_planes;
+use crate::{do_scale, write_png_32};
+
+#[derive(Debug, StructOpt)]
+pub struct ExtractAllOpt {
+    /// Output anamorphic (non-square) pixels for sprites, patches, textures
+    /// and other graphics. Like the original assets, the pixel aspect ratio
+    /// will be 5:6.
+    #[structopt(short = "a", long = "anamorphic")]
+    pub anamorphic: bool,
+
+    /// Number of threads to render and write graphics on. Defaults to the
+    /// number of CPU cores. The output is the same regardless.
+    #[structopt(short = "j", long = "jobs")]
+    pub jobs: Option<usize>,
+}
+
+/// The directory of each kind of graphic in the output, after DeuTex
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum Category {
+    Sprites,
+    Flats,
+    Patches,
+    Textures,
+    Graphics,
+}
+
+impl Category {
+    const ALL: [Category; 5] = [
+        Category::Sprites,
+        Category::Flats,
+        Category::Patches,
+        Category::Textures,
+        Category::Graphics,
+    ];
+
+    fn dir(self) -> &'static str {
+        match self {
+            Category::Sprites => "sprites",
+            Category::Flats => "flats",
+            Category::Patches => "patches",
+            Category::Textures => "textures",
+            Category::Graphics => "graphics",
+        }
+    }
+}
+
+enum Source<'a> {
+    Patch(&'a [u8]),
+    /// A texture, to be composited from its patches
+    Texture(Texture<'a>),
+    Flat(&'a [u8]),
+    Screen(&'a [u8]),
+    Png(&'a [u8]),
+}
+
+/// One graphic to write
+struct Job<'a> {
+    category: Category,
+    name: String,
+    source: Source<'a>,
+}
+
+/// The file name to use for a lump name. Like DeuTex, the backslash, which
+/// is used in the names of some sprites, is replaced with ^
+fn file_stem(name: &str) -> String {
+    name.to_ascii_lowercase().replace('\\', "^")
+}
+
+/// Decide what to do with each lump of the WAD, and each texture. Returns
+/// the graphics to write and the reasons for skipping the rest, with the
+/// names of the lumps skipped for each reason.
+fn plan<'a>(
+    index: &ResourceIndex<'a>,
+    pnames: &[[u8; 8]],
+    patch_provider: &impl PatchProvider<'a>,
+    pattern: &str,
+) -> (Vec<Job<'a>>, BTreeMap<&'static str, Vec<String>>) {
+    let pattern = pattern.as_bytes();
+
+    let pnames_set: BTreeSet<&[u8; 8]> = pnames.iter().collect();
+    let has_flat_namespace = index.has_namespace(Namespace::Flats);
+
+    let mut jobs = vec![];
+    let mut skipped: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+    let mut written = BTreeSet::new();
+
+    let mut add = |category: Category, name: String, source: Result<Source<'a>, &'static str>| {
+        let result = source.and_then(|source| {
+            if written.insert((category, file_stem(&name))) {
+                Ok(source)
+            } else {
+                Err("duplicate name")
+            }
+        });
+        match result {
+            Ok(source) => jobs.push(Job {
+                category,
+                name,
+                source,
+            }),
+            Err(reason) => skipped.entry(reason).or_default().push(name),
+        }
+    };
+
+    for i in 0..index.len() {
+        let namespace = match index.namespace(i) {
+            Some(namespace) => namespace,
+            None => continue,
+        };
+        if index.is_overridden(i) {
+            continue;
+        }
+        let entry = match index.entry(i) {
+            Ok(entry) => entry,
+            Err(_) => {
+                let name = index.id(i).to_string();
+                add(Category::Graphics, name, Err("invalid directory entry"));
+                continue;
+            }
+        };
+        let name = entry.id.to_string();
+        if !glob_match(pattern, name.as_bytes()) || entry.id.as_bytes() == b"PLAYPAL\0" {
+            continue;
+        }
+
+        let data = entry.lump;
+        let format = detect_graphic(data, Some(namespace));
+        let (category, source) = match (namespace, format) {
+            (_, Some(GraphicFormat::Png)) => {
+                let category = match namespace {
+                    Namespace::Sprites => Category::Sprites,
+                    Namespace::Flats => Category::Flats,
+                    Namespace::Patches => Category::Patches,
+                    Namespace::Global => Category::Graphics,
+                };
+                (category, Ok(Source::Png(data)))
+            }
+            (Namespace::Sprites, Some(GraphicFormat::Patch)) => {
+                (Category::Sprites, Ok(Source::Patch(data)))
+            }
+            (Namespace::Sprites, _) => (Category::Sprites, Err("invalid sprite")),
+            (Namespace::Flats, Some(GraphicFormat::Flat)) => {
+                (Category::Flats, Ok(Source::Flat(data)))
+            }
+            (Namespace::Flats, _) => (Category::Flats, Err("not a flat")),
+            (Namespace::Patches, Some(GraphicFormat::Patch)) => {
+                (Category::Patches, Ok(Source::Patch(data)))
+            }
+            (Namespace::Patches, _) => (Category::Patches, Err("invalid patch")),
+            (Namespace::Global, Some(GraphicFormat::Patch)) => {
+                if pnames_set.contains(entry.id.as_bytes()) {
+                    (Category::Patches, Ok(Source::Patch(data)))
+                } else {
+                    (Category::Graphics, Ok(Source::Patch(data)))
+                }
+            }
+            (Namespace::Global, Some(GraphicFormat::Flat)) if !has_flat_namespace => {
+                (Category::Flats, Ok(Source::Flat(data)))
+            }
+            (Namespace::Global, Some(GraphicFormat::Flat)) => (
+                Category::Graphics,
+                Err("64x64 raw data outside of the flat namespace"),
+            ),
+            (Namespace::Global, Some(GraphicFormat::RawScreen)) => {
+                (Category::Graphics, Ok(Source::Screen(data)))
+            }
+            (Namespace::Global, None) => (Category::Graphics, Err("not a graphic")),
+        };
+        add(category, name, source);
+    }
+
+    for texture in TextureSet::from_index(index).iter() {
+        let name = EntryId::from_bytes(&texture.name()).to_string();
+        if !glob_match(pattern, name.as_bytes()) {
+            continue;
+        }
+        let missing =
+            (0..texture.len()).any(|p| patch_provider.patch(texture.patch(p).patch_id).is_none());
+        let source = if missing {
+            Err("texture with missing or malformed patches")
+        } else {
+            Ok(Source::Texture(texture))
+        };
+        add(Category::Textures, name, source);
+    }
+
+    (jobs, skipped)
+}
+
+/// Write all palettes as one image, with one row of 256 colors per palette
+fn write_palettes(
+    playpal: &[u8],
+    scale: usize,
+    output: impl AsRef<Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let palettes = playpal.len() / 768;
+    let image = Array2::from_shape_fn((palettes, 256), |(p, i)| {
+        let c = &playpal[p * 768 + i * 3..p * 768 + i * 3 + 3];
+        [c[0], c[1], c[2], 255]
+    });
+    let scaled = do_scale(image.view(), scale as u32, Rational32::from(scale as i32));
+    write_png_32(output, None, Rational32::from(1), scaled.view())
+}
+
+fn write_job<'a>(
+    job: &Job<'a>,
+    patch_provider: &impl PatchProvider<'a>,
+    palette: &[u8],
+    colormap: &[u8],
+    scale: usize,
+    path: PathBuf,
+    anamorphic: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sprite_opt = SpriteOpt {
+        canvas_size: None,
+        pos: None,
+        info: false,
+        format: Format::Full,
+        background: None,
+        anamorphic,
+        flip: false,
+    };
+
+    match &job.source {
+        Source::Patch(data) => sprite_cmd(palette, colormap, data, scale, path, sprite_opt),
+        Source::Texture(texture) => {
+            let (pixels, mask) = render_texture_planes(*texture, patch_provider);
+            write_planes(
+                palette,
+                colormap,
+                pixels.view(),
+                mask.view(),
+                scale,
+                path,
+                Format::Full,
+                None,
+                anamorphic,
+            )
+        }
+        Source::Flat(data) => flat_cmd(palette, colormap, data, scale, path),
+        Source::Screen(data) => screen_cmd(palette, colormap, data, scale, path),
+        Source::Png(data) => Ok(std::fs::write(path, data)?),
+    }
+}
+
+/// A DeuTex-style listing of what was extracted, with the offsets of the
+/// graphics in patch format
+fn wadinfo(jobs: &[Job]) -> String {
+    let mut out = String::new();
+    for &category in &Category::ALL {
+        let mut jobs = jobs
+            .iter()
+            .filter(|job| job.category == category)
+            .peekable();
+        if jobs.peek().is_none() {
+            continue;
+        }
+
+        writeln!(out, "[{}]", category.dir()).unwrap();
+        for job in jobs {
+            match &job.source {
+                Source::Patch(data) => {
+                    let sprite = Sprite::new(data);
+                    writeln!(out, "{} {} {}", job.name, sprite.left(), sprite.top()).unwrap();
+                }
+                _ => writeln!(out, "{}", job.name).unwrap(),
+            }
+        }
+        writeln!(out).unwrap();
+    }
+    out
+}
+
+pub fn extract_all_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    pattern: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: ExtractAllOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output = output.as_ref();
+    let pnames = index.by_id(b"PNAMES").map(parse_pnames).unwrap_or_default();
+    let patch_provider = LazyPatchProvider::new(index, pnames);
+    let (jobs, skipped) = plan(index, pnames, &patch_provider, pattern);
+
+    for &category in &Category::ALL {
+        if jobs.iter().any(|job| job.category == category) {
+            std::fs::create_dir_all(output.join(category.dir()))?;
+        }
+    }
+    if jobs.is_empty() {
+        std::fs::create_dir_all(output)?;
+    }
+
+    let threads = opt.jobs.unwrap_or_else(default_threads);
+    let results = parallel_map(&jobs, threads, |job| {
+        let path = output
+            .join(job.category.dir())
+            .join(format!("{}.png", file_stem(&job.name)));
+        write_job(
+            job,
+            &patch_provider,
+            palette,
+            colormap,
+            scale,
+            path,
+            opt.anamorphic,
+        )
+        .map_err(|err| format!("{}: {}", job.name, err))
+    });
+    for result in results {
+        result?;
+    }
+
+    if glob_match(pattern.as_bytes(), b"PLAYPAL") {
+        if let Some(playpal) = index.by_id(b"PLAYPAL") {
+            write_palettes(playpal, scale, output.join("playpal.png"))?;
+        }
+    }
+
+    std::fs::write(output.join("wadinfo.txt"), wadinfo(&jobs))?;
+
+    for &category in &Category::ALL {
+        let count = jobs.iter().filter(|job| job.category == category).count();
+        if count > 0 {
+            println!("{:>6} {}", count, category.dir());
+        }
+    }
+
+    if !skipped.is_empty() {
+        let mut list = String::new();
+        for (reason, names) in &skipped {
+            println!("{:>6} skipped: {}", names.len(), reason);
+            writeln!(list, "[{}]", reason)?;
+            for name in names {
+                writeln!(list, "{}", name)?;
+            }
+            writeln!(list)?;
+        }
+        std::fs::write(output.join("skipped.txt"), list)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn file_stem_escapes() {
+        assert_eq!(file_stem("TROOA1"), "trooa1");
+        assert_eq!(file_stem("VILE\\1"), "vile^1");
+    }
+}
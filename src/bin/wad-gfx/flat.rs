@@ -12,6 +12,7 @@ pub fn flat_cmd(
     scale: usize,
     output: impl AsRef<Path>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    #[allow(clippy::needless_borrow)]
     let gfx = Flat::new(&gfx)?;
     let mut mapped = [0u8; 64 * 64];
 
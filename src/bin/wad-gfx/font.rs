@@ -0,0 +1,95 @@
+use ndarray::prelude::*;
+use wad::EntryId;
+use wad_gfx::{validate_patch, Flip, ResourceIndex, Sprite};
+
+use crate::sprite::draw_sprite;
+
+const FONT_START: u8 = b'!';
+const FONT_END: u8 = b'_';
+
+/// The advance for characters that are not in the font, as in hu_lib.c
+const SPACE_WIDTH: i32 = 4;
+
+/// The small HUD font, STCFN033 to STCFN095, as used for the messages in
+/// the game
+pub struct Font<'a> {
+    glyphs: Vec<Option<Sprite<'a>>>,
+}
+
+impl<'a> Font<'a> {
+    pub fn from_wad(index: &ResourceIndex<'a>) -> Option<Font<'a>> {
+        let glyphs: Vec<_> = (FONT_START..=FONT_END)
+            .map(|c| {
+                let name = format!("STCFN{:03}", c);
+                let lump = index.by_id(EntryId::from_str(&name).unwrap())?;
+                if let Err(err) = validate_patch(lump) {
+                    eprintln!("warning: Skipping font character {}: {}", name, err);
+                    return None;
+                }
+                Some(Sprite::new(lump))
+            })
+            .collect();
+
+        if glyphs.iter().all(Option::is_none) {
+            return None;
+        }
+
+        Some(Font { glyphs })
+    }
+
+    fn glyph(&self, c: char) -> Option<&Sprite<'a>> {
+        let c = c.to_ascii_uppercase();
+        if (FONT_START as char..=FONT_END as char).contains(&c) {
+            self.glyphs[(c as u8 - FONT_START) as usize].as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn height(&self) -> i32 {
+        self.glyphs
+            .iter()
+            .flatten()
+            .map(|glyph| glyph.height() as i32)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn text_width(&self, text: &str) -> i32 {
+        text.chars()
+            .map(|c| {
+                self.glyph(c)
+                    .map(|glyph| glyph.width() as i32)
+                    .unwrap_or(SPACE_WIDTH)
+            })
+            .sum()
+    }
+
+    /// Draw text with its top left corner at `pos`, given as (y, x)
+    pub fn draw<Px>(
+        &self,
+        mut target: ArrayViewMut2<Px>,
+        text: &str,
+        pos: (i32, i32),
+        pixel_mapper: impl Fn(u8) -> Px,
+    ) {
+        let (y, mut x) = pos;
+        for c in text.chars() {
+            match self.glyph(c) {
+                Some(glyph) => {
+                    let (o_y, o_x) = glyph.origin();
+                    let glyph_pos = (y + o_y as i32, x + o_x as i32);
+                    draw_sprite(
+                        target.view_mut(),
+                        glyph,
+                        glyph_pos,
+                        Flip::default(),
+                        &pixel_mapper,
+                    );
+                    x += glyph.width() as i32;
+                }
+                None => x += SPACE_WIDTH,
+            }
+        }
+    }
+}
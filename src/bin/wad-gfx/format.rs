@@ -1,5 +1,8 @@
 use std::str::FromStr;
 
+/// Black for transparent pixels and white for opaque ones
+pub const MASK_PALETTE: &[u8] = &[0, 0, 0, 255, 255, 255];
+
 #[derive(Debug)]
 pub enum Format {
     Indexed,
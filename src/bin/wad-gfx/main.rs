@@ -1,9 +1,27 @@
 extern crate wad_gfx;
 
+mod animate;
+mod apng;
+mod atlas;
+mod auto;
+mod check;
+mod extract_all;
 mod flat;
+mod font;
 mod format;
+mod map;
+mod parallel;
+mod patches;
+mod pattern;
+mod rotations;
 mod sprite;
 mod texture;
+mod thing;
+mod wall;
+
+#[cfg(test)]
+#[path = "../../test_wad.rs"]
+mod test_wad;
 
 #[path = "../../rangetools.rs"]
 mod rangetools;
@@ -14,9 +32,39 @@ use ndarray::prelude::*;
 use num_rational::Rational32;
 use structopt::StructOpt;
 use wad::EntryId;
+use wad_gfx::{GraphicFormat, Namespace, ResourceIndex, FLAT_NAMESPACES};
+
+use apng::Frame;
+
+/// Where the sprite subcommand looks for graphics in patch format: sprites
+/// first, then other graphics like TITLEPIC, and last texture patches
+const SPRITE_CMD_NAMESPACES: &[Namespace] =
+    &[Namespace::Sprites, Namespace::Global, Namespace::Patches];
 
 #[derive(Debug, StructOpt)]
 enum Graphics {
+    /// Extract a graphic as whatever it is: a sprite or other patch, a
+    /// flat, a raw full screen graphic, a PNG or a texture. This is the
+    /// default
+    #[structopt(name = "auto")]
+    Auto,
+
+    /// Check the graphics for problems that the engines choke on, like
+    /// malformed patches, textures with missing patches and sprite frames
+    /// with missing rotations. The name is a pattern for the lumps,
+    /// textures and sprite frames to report on, eg '*'. The report is
+    /// written to stdout unless an output file is given
+    #[structopt(name = "check")]
+    Check(check::CheckOpt),
+
+    /// Extract all graphics and the palette into a directory tree with
+    /// sprites, flats, patches, textures and graphics directories. The name
+    /// is a pattern, where * matches any sequence of characters and ? any
+    /// single character. The output is the directory, which defaults to
+    /// the name of the WAD file
+    #[structopt(name = "extract-all")]
+    ExtractAll(extract_all::ExtractAllOpt),
+
     /// Extract a flat
     #[structopt(name = "flat")]
     Flat,
@@ -25,9 +73,51 @@ enum Graphics {
     #[structopt(name = "sprite")]
     Sprite(sprite::SpriteOpt),
 
-    /// Extract a texture
+    /// Export a sequence of sprite frames as an animation, aligned on the
+    /// hotspot. The name is the sprite name, eg TROO
+    #[structopt(name = "animate")]
+    Animate(animate::AnimateOpt),
+
+    /// Pack sprites, patches, flats or textures into power-of-two pages
+    /// with JSON metadata. The name is a pattern, where * matches any
+    /// sequence of characters and ? any single character, eg TROO*
+    #[structopt(name = "atlas")]
+    Atlas(atlas::AtlasOpt),
+
+    /// Render a map the way the automap shows it with all of the map
+    /// revealed. The name is the map, eg E1M1 or MAP01. The output is SVG
+    /// when the output file name ends in .svg, and PNG otherwise
+    #[structopt(name = "map")]
+    Map(map::MapOpt),
+
+    /// Report which textures use each patch, and which patches are unused,
+    /// missing or not in PNAMES. The name is a pattern for the patch names,
+    /// where * matches any sequence of characters. The report is written to
+    /// stdout unless an output file is given
+    #[structopt(name = "patches")]
+    Patches(patches::PatchesOpt),
+
+    /// Render all rotations of a sprite frame into one image. The name is
+    /// the sprite name followed by the frame letter, eg TROOA
+    #[structopt(name = "rotations")]
+    Rotations(rotations::RotationsOpt),
+
+    /// Extract a texture, composited from its patches. The textures of
+    /// TEXTURE1 and TEXTURE2 are looked up by name, eg BIGDOOR2
     #[structopt(name = "texture")]
     Texture(texture::TextureOpt),
+
+    /// Export a state sequence of a thing type as an animation, with the
+    /// durations and fullbright frames of the game. The name is the thing
+    /// number, eg 3001, or the thing type, eg MT_TROOP
+    #[structopt(name = "thing")]
+    Thing(thing::ThingOpt),
+
+    /// Render a side of a linedef as it appears in game, with the upper,
+    /// middle and lower textures pegged and offset like the engine does,
+    /// and lit by the sector. The name is the map, eg E1M1 or MAP01
+    #[structopt(name = "wall")]
+    Wall(wall::WallOpt),
 }
 
 #[derive(Debug, StructOpt)]
@@ -37,15 +127,23 @@ struct Opt {
     #[structopt(parse(from_os_str))]
     input: PathBuf,
 
-    /// The lump name of the graphic to extract
+    /// The lump name of the graphic to extract, the texture name for
+    /// texture, the sprite name for animate and rotations, the thing number or type for thing, the
+    /// map name for map and wall, or a name pattern for atlas
     name: String,
 
+    /// PWAD to load on top of the input WAD, like -file for the engines.
+    /// May be given multiple times, and later WADs override earlier ones
+    #[structopt(long = "file", raw(number_of_values = "1"), parse(from_os_str))]
+    files: Vec<PathBuf>,
+
     /// Output filename. If absent, will default to <name>.png
     #[structopt(short = "o", long = "output", parse(from_os_str))]
     output: Option<PathBuf>,
 
+    /// What to extract. If absent, the type of the graphic is detected
     #[structopt(subcommand)]
-    gfx: Graphics,
+    gfx: Option<Graphics>,
 
     /// Which palette to use (0-13)
     #[structopt(short = "p", long = "palette", default_value = "0")]
@@ -60,65 +158,20 @@ struct Opt {
     scale: usize,
 }
 
-struct PhysChunk {
-    x: i32,
-    y: i32,
-    unit: u8,
-}
-
-use std::io::Write;
-
-impl PhysChunk {
-    fn serialize(&self, w: &mut impl Write) -> std::io::Result<()> {
-        use byteorder::{BigEndian, WriteBytesExt};
-        w.write_i32::<BigEndian>(self.x)?;
-        w.write_i32::<BigEndian>(self.y)?;
-        w.write_u8(self.unit)?;
-        Ok(())
-    }
-}
-
 fn write_png(
     filename: impl AsRef<Path>,
     palette: Option<&[u8]>,
     pixel_aspect: Rational32,
     gfx: ArrayView2<u8>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use png::HasParameters;
-    use std::fs::File;
-    use std::io::{BufWriter, Cursor};
-
-    assert!(gfx.dim().0 <= i32::max_value() as usize);
-    assert!(gfx.dim().1 <= i32::max_value() as usize);
-    assert_eq!(gfx.stride_of(Axis(1)), 1);
-    assert_eq!(gfx.stride_of(Axis(0)), gfx.dim().1 as isize);
-
-    let file = File::create(filename)?;
-    let ref mut w = BufWriter::new(file);
-
-    let mut encoder = png::Encoder::new(w, gfx.dim().1 as u32, gfx.dim().0 as u32);
-    encoder.set(png::ColorType::Indexed);
-    encoder.set(png::Compression::Best);
-    let mut writer = encoder.write_header()?;
-
-    if let Some(palette) = palette {
-        writer.write_chunk(png::chunk::PLTE, palette)?;
-    }
-
-    if pixel_aspect != Rational32::from(1) {
-        let mut buf = [0u8; 9];
-        let phys_chunk = PhysChunk {
-            x: *pixel_aspect.numer(),
-            y: *pixel_aspect.denom(),
-            unit: 0,
-        };
-        phys_chunk.serialize(&mut Cursor::new(&mut buf as &mut [u8]))?;
-        writer.write_chunk(png::chunk::pHYs, &buf)?;
-    }
-
-    writer.write_image_data(gfx.into_slice().unwrap())?;
-
-    Ok(())
+    apng::write_apng(
+        filename,
+        palette,
+        None,
+        pixel_aspect,
+        &[Frame::still(gfx)],
+        0,
+    )
 }
 
 fn write_png_32(
@@ -127,44 +180,14 @@ fn write_png_32(
     pixel_aspect: Rational32,
     gfx: ArrayView2<[u8; 4]>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use png::HasParameters;
-    use std::fs::File;
-    use std::io::{BufWriter, Cursor};
-
-    assert!(gfx.dim().0 <= i32::max_value() as usize);
-    assert!(gfx.dim().1 <= i32::max_value() as usize);
-    assert_eq!(gfx.stride_of(Axis(1)), 1);
-    assert_eq!(gfx.stride_of(Axis(0)), gfx.dim().1 as isize);
-
-    let file = File::create(filename)?;
-    let ref mut w = BufWriter::new(file);
-
-    let mut encoder = png::Encoder::new(w, gfx.dim().1 as u32, gfx.dim().0 as u32);
-    encoder.set(png::ColorType::RGBA);
-    encoder.set(png::Compression::Best);
-    let mut writer = encoder.write_header()?;
-
-    if let Some(palette) = palette {
-        writer.write_chunk(png::chunk::PLTE, palette)?;
-    }
-
-    if pixel_aspect != Rational32::from(1) {
-        let mut buf = [0u8; 9];
-        let phys_chunk = PhysChunk {
-            x: *pixel_aspect.numer(),
-            y: *pixel_aspect.denom(),
-            unit: 0,
-        };
-        phys_chunk.serialize(&mut Cursor::new(&mut buf as &mut [u8]))?;
-        writer.write_chunk(png::chunk::pHYs, &buf)?;
-    }
-
-    let raw_data = gfx.into_slice().unwrap();
-    writer.write_image_data(unsafe {
-        std::slice::from_raw_parts(raw_data.as_ptr() as *const u8, raw_data.len() * 4)
-    })?;
-
-    Ok(())
+    apng::write_apng(
+        filename,
+        palette,
+        None,
+        pixel_aspect,
+        &[Frame::still(gfx)],
+        0,
+    )
 }
 
 fn do_scale<Px: Default + Copy>(input: ArrayView2<Px>, sx: u32, sy: Rational32) -> Array2<Px> {
@@ -184,37 +207,217 @@ fn do_scale<Px: Default + Copy>(input: ArrayView2<Px>, sx: u32, sy: Rational32)
     target
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let opt = Opt::from_args();
-
-    let wad = wad::load_wad_file(&opt.input)?;
-
-    let palettes = wad.by_id(b"PLAYPAL").ok_or("Missing PLAYPAL")?;
-    let palette_index = opt.palette.checked_mul(768).ok_or("Overflow")?;
-    let palette = &palettes[palette_index..palette_index + 768];
+/// The palette and colormaps chosen on the command line. Loaded only by the
+/// subcommands that draw, so the reports work on WADs without a PLAYPAL
+struct Colors<'a> {
+    palette: &'a [u8],
+    colormaps: &'a [u8],
+    colormap: &'a [u8],
+}
 
-    let colormaps = wad.by_id(b"COLORMAP").ok_or("Missing COLORMAP")?;
-    let colormap_index = opt.colormap.checked_mul(256).ok_or("Overflow")?;
-    let colormap = &colormaps[colormap_index..colormap_index + 256];
+impl<'a> Colors<'a> {
+    fn load(
+        index: &ResourceIndex<'a>,
+        palette: usize,
+        colormap: usize,
+    ) -> Result<Colors<'a>, Box<dyn std::error::Error>> {
+        let palettes = index.by_id(b"PLAYPAL").ok_or("Missing PLAYPAL")?;
+        let palette_index = palette.checked_mul(768).ok_or("Overflow")?;
+        let palette = &palettes[palette_index..palette_index + 768];
+
+        let colormaps = index.by_id(b"COLORMAP").ok_or("Missing COLORMAP")?;
+        let colormap_index = colormap.checked_mul(256).ok_or("Overflow")?;
+        let colormap = &colormaps[colormap_index..colormap_index + 256];
+
+        Ok(Colors {
+            palette,
+            colormaps,
+            colormap,
+        })
+    }
 
-    let gfx_id =
-        EntryId::from_str(&opt.name).ok_or_else(|| format!("Invalid ID: {:?}", opt.name))?;
-    let gfx = wad
-        .by_id(gfx_id)
-        .ok_or_else(|| format!("Cannot find {}", opt.name))?;
+    fn fullbright_colormap(&self) -> &'a [u8] {
+        &self.colormaps[0..256]
+    }
+}
 
-    let output = opt
-        .output
-        .clone()
-        .unwrap_or_else(|| format!("{}.png", opt.name.to_ascii_lowercase()).into());
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut opt = Opt::from_args();
 
-    match opt.gfx {
-        Graphics::Flat => flat::flat_cmd(palette, colormap, gfx, opt.scale, output),
+    let mut wads = vec![wad::load_wad_file(&opt.input)?];
+    for file in &opt.files {
+        wads.push(wad::load_wad_file(file).map_err(|err| format!("{}: {}", file.display(), err))?);
+    }
+    let index = ResourceIndex::from_wads(wads.iter().map(|wad| wad.as_slice()).collect());
+
+    let gfx = opt.gfx.take().unwrap_or(Graphics::Auto);
+    let name = &opt.name;
+    let colors = || Colors::load(&index, opt.palette, opt.colormap);
+
+    let find_lump = |namespaces: &[Namespace]| -> Result<(&[u8], Option<Namespace>), String> {
+        let gfx_id = EntryId::from_str(name).ok_or_else(|| format!("Invalid ID: {:?}", name))?;
+        let lump_index = index.find_index(gfx_id, namespaces).ok_or_else(|| {
+            let namespaces: Vec<_> = namespaces.iter().map(|x| x.name()).collect();
+            format!(
+                "Cannot find {} in the {} namespaces",
+                name,
+                namespaces.join(", ")
+            )
+        })?;
+        let lump = index.entry(lump_index).map_err(|x| x.to_string())?.lump;
+        Ok((lump, index.namespace(lump_index)))
+    };
+    let find_gfx = |namespaces: &[Namespace], expected| -> Result<&[u8], String> {
+        let (lump, namespace) = find_lump(namespaces)?;
+        auto::expect_format(name, lump, namespace, expected)?;
+        Ok(lump)
+    };
+
+    let output = opt.output.clone().unwrap_or_else(|| {
+        let stem: String = opt.name.chars().filter(|&c| c != '*' && c != '?').collect();
+        let stem = if stem.is_empty() { "atlas" } else { &stem };
+        format!("{}.png", stem.to_ascii_lowercase()).into()
+    });
+
+    match gfx {
+        Graphics::Auto => {
+            let colors = colors()?;
+            auto::auto_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                name,
+                opt.scale,
+                output,
+            )
+        }
+        Graphics::ExtractAll(opts) => {
+            let colors = colors()?;
+            let input = &opt.input;
+            let dir = opt.output.clone().unwrap_or_else(|| {
+                input
+                    .file_stem()
+                    .map(|x| x.to_ascii_lowercase().into())
+                    .unwrap_or_else(|| "wad".into())
+            });
+            extract_all::extract_all_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                name,
+                opt.scale,
+                dir,
+                opts,
+            )
+        }
+        Graphics::Flat => {
+            let colors = colors()?;
+            flat::flat_cmd(
+                colors.palette,
+                colors.colormap,
+                find_gfx(FLAT_NAMESPACES, GraphicFormat::Flat)?,
+                opt.scale,
+                output,
+            )
+        }
         Graphics::Sprite(opts) => {
-            sprite::sprite_cmd(palette, colormap, gfx, opt.scale, output, opts)
+            let colors = colors()?;
+            sprite::sprite_cmd(
+                colors.palette,
+                colors.colormap,
+                find_gfx(SPRITE_CMD_NAMESPACES, GraphicFormat::Patch)?,
+                opt.scale,
+                output,
+                opts,
+            )
+        }
+        Graphics::Animate(opts) => {
+            let colors = colors()?;
+            animate::animate_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                name,
+                opt.scale,
+                output,
+                opts,
+            )
+        }
+        Graphics::Atlas(opts) => {
+            let colors = colors()?;
+            atlas::atlas_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                name,
+                opt.scale,
+                output,
+                opts,
+            )
+        }
+        Graphics::Check(opts) => check::check_cmd(&index, name, opt.output.as_ref(), opts),
+        Graphics::Map(opts) => {
+            let colors = colors()?;
+            map::map_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                colors.colormaps,
+                name,
+                opt.scale,
+                output,
+                opts,
+            )
+        }
+        Graphics::Patches(opts) => patches::patches_cmd(&index, name, opt.output.as_ref(), opts),
+        Graphics::Rotations(opts) => {
+            let colors = colors()?;
+            rotations::rotations_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                &opt.name,
+                opt.scale,
+                output,
+                opts,
+            )
         }
         Graphics::Texture(opts) => {
-            texture::texture_cmd(&wad, palette, colormap, gfx, opt.scale, output, opts)
+            let colors = colors()?;
+            texture::texture_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                name,
+                opt.scale,
+                output,
+                opts,
+            )
+        }
+        Graphics::Thing(opts) => {
+            let colors = colors()?;
+            thing::thing_cmd(
+                &index,
+                colors.palette,
+                colors.colormap,
+                colors.fullbright_colormap(),
+                name,
+                opt.scale,
+                output,
+                opts,
+            )
+        }
+        Graphics::Wall(opts) => {
+            let colors = colors()?;
+            wall::wall_cmd(
+                &index,
+                colors.palette,
+                colors.colormaps,
+                name,
+                opt.scale,
+                output,
+                opts,
+            )
         }
     }
 }
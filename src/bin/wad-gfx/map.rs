@@ -0,0 +1,536 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryInto;
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use ndarray::prelude::*;
+use num_rational::Rational32;
+use structopt::StructOpt;
+use wad::EntryId;
+use wad_gfx::{
+    degrees_to_bam, sprite_defs, validate_patch, Flat, Flip, GameMode, Map, ResourceIndex,
+    Sequence, Sprite, ThingInfo, DEATHMATCH_START, FLAT_NAMESPACES, SPRITE_NAMESPACES, S_NULL,
+};
+
+use crate::sprite::draw_sprite;
+use crate::thing::load_thing_info;
+use crate::{do_scale, write_png};
+
+/// The palette index of the automap background
+const BACKGROUND: u8 = 0;
+
+/// Room around the map, in output pixels before scaling
+const MARGIN: i32 = 8;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Surface {
+    Floor,
+    Ceiling,
+}
+
+impl FromStr for Surface {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Surface, &'static str> {
+        match s {
+            "floor" | "f" => Ok(Surface::Floor),
+            "ceiling" | "c" => Ok(Surface::Ceiling),
+            _ => Err("surface must be 'floor'/'f' or 'ceiling'/'c'"),
+        }
+    }
+}
+
+/// The sprite and frame to draw for a thing number, given as eg
+/// 3001=TROOA, or 3001=TROO for frame A
+#[derive(Debug, Clone, Copy)]
+pub struct ThingSprite {
+    doomednum: u16,
+    sprite: [u8; 4],
+    frame: u8,
+}
+
+impl FromStr for ThingSprite {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<ThingSprite, &'static str> {
+        const ERR: &str = "thing sprite must be a thing number and a sprite name with an \
+                           optional frame letter, eg 3001=TROOA";
+        let mut parts = s.splitn(2, '=');
+        let doomednum = parts.next().unwrap().parse().map_err(|_| ERR)?;
+        let name = parts.next().ok_or(ERR)?.to_ascii_uppercase();
+        let name = name.as_bytes();
+        let frame = match name.len() {
+            4 => 0,
+            5 if (b'A'..=b']').contains(&name[4]) => name[4] - b'A',
+            _ => return Err(ERR),
+        };
+        Ok(ThingSprite {
+            doomednum,
+            sprite: name[..4].try_into().unwrap(),
+            frame,
+        })
+    }
+}
+
+fn parse_skill(src: &str) -> Result<u8, &'static str> {
+    match src.parse() {
+        Ok(skill) if (1..=5).contains(&skill) => Ok(skill),
+        _ => Err("skill must be a number from 1 to 5"),
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct MapOpt {
+    /// How many map units make up one pixel, before scaling with --scale
+    #[structopt(short = "u", long = "units-per-pixel", default_value = "8")]
+    pub units_per_pixel: u16,
+
+    /// Fill the sectors with their floor/f or ceiling/c flats, shaded by the
+    /// sector light level, instead of drawing the lines. Only for PNG output
+    #[structopt(long = "flats")]
+    pub flats: Option<Surface>,
+
+    /// With --flats, draw the lines on top of the flats
+    #[structopt(long = "lines")]
+    pub lines: bool,
+
+    /// Draw the things with their sprites. Only for PNG output
+    #[structopt(long = "things")]
+    pub things: bool,
+
+    /// Only draw the things that appear on this skill level, 1-5
+    #[structopt(long = "skill", parse(try_from_str = "parse_skill"))]
+    pub skill: Option<u8>,
+
+    /// Only draw the things that appear in this kind of game: single/s,
+    /// coop/c or deathmatch/dm
+    #[structopt(long = "mode")]
+    pub mode: Option<GameMode>,
+
+    /// The sprite to draw for a thing number, eg 3001=TROOA, for things
+    /// that are not in the thing tables. May be given multiple times
+    #[structopt(long = "thing-sprite")]
+    pub thing_sprites: Vec<ThingSprite>,
+
+    /// DeHackEd patch to apply to the thing tables, after the DEHACKED
+    /// lump of the WAD, if any. May be given multiple times
+    #[structopt(long = "deh", parse(from_os_str))]
+    pub deh: Vec<PathBuf>,
+}
+
+/// Maps map coordinates to pixel coordinates, with y pointing down
+struct Projection {
+    left: i32,
+    top: i32,
+    units_per_pixel: i32,
+}
+
+impl Projection {
+    fn new(map: &Map, units_per_pixel: u16) -> Result<(Projection, (usize, usize)), String> {
+        let ((x0, y0), (x1, y1)) = map.bounds().ok_or("The map has no vertexes")?;
+        let units_per_pixel = units_per_pixel.max(1) as i32;
+        let projection = Projection {
+            left: x0 as i32,
+            top: y1 as i32,
+            units_per_pixel,
+        };
+        let (width, height) = projection.project(x1, y0);
+        let dim = (
+            (height + MARGIN + 1) as usize,
+            (width + MARGIN + 1) as usize,
+        );
+        Ok((projection, dim))
+    }
+
+    /// The map coordinates of the center of a pixel
+    fn unproject(&self, x: i32, y: i32) -> (f64, f64) {
+        let unit = self.units_per_pixel as f64;
+        (
+            self.left as f64 + ((x - MARGIN) as f64 + 0.5) * unit,
+            self.top as f64 - ((y - MARGIN) as f64 + 0.5) * unit,
+        )
+    }
+
+    /// The pixel columns with centers in the range of map x coordinates
+    fn columns(&self, x0: f64, x1: f64) -> Range<i32> {
+        let unit = self.units_per_pixel as f64;
+        let column = |x: f64| ((x - self.left as f64) / unit - 0.5).ceil() as i32 + MARGIN;
+        column(x0)..column(x1)
+    }
+
+    fn project(&self, x: i16, y: i16) -> (i32, i32) {
+        (
+            (x as i32 - self.left).div_euclid(self.units_per_pixel) + MARGIN,
+            (self.top - y as i32).div_euclid(self.units_per_pixel) + MARGIN,
+        )
+    }
+}
+
+/// Draw a line with Bresenham's algorithm, like AM_drawFline. Pixels
+/// outside of the target are skipped.
+fn draw_line(mut target: ArrayViewMut2<u8>, (x0, y0): (i32, i32), (x1, y1): (i32, i32), color: u8) {
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let (mut x, mut y) = (x0, y0);
+    let mut error = dx + dy;
+    loop {
+        if x >= 0 && y >= 0 {
+            if let Some(pixel) = target.get_mut((y as usize, x as usize)) {
+                *pixel = color;
+            }
+        }
+        if (x, y) == (x1, y1) {
+            break;
+        }
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+/// Fill each sector with its flat, tiled in world coordinates like the
+/// span drawer does, and shaded with the colormap of the sector light
+/// level. The pixels whose centers are inside the sector by the even-odd
+/// rule are filled. Sectors with missing flats are left empty.
+fn draw_flats(
+    mut target: ArrayViewMut2<u8>,
+    projection: &Projection,
+    index: &ResourceIndex,
+    colormaps: &[u8],
+    map: &Map,
+    surface: Surface,
+) {
+    let width = target.dim().1 as i32;
+    let mut missing = BTreeSet::new();
+    for (sector, polygons) in map.sectors.iter().zip(map.sector_polygons()) {
+        let flat_name = match surface {
+            Surface::Floor => sector.floor_flat,
+            Surface::Ceiling => sector.ceiling_flat,
+        };
+        let flat = index
+            .find(EntryId::from_bytes(&flat_name), FLAT_NAMESPACES)
+            .and_then(|x| Flat::new(x).ok());
+        let flat = match flat {
+            Some(flat) => flat.view(),
+            None => {
+                if missing.insert(flat_name) {
+                    eprintln!("warning: Missing flat {}", EntryId::from_bytes(&flat_name));
+                }
+                continue;
+            }
+        };
+        let colormap = &colormaps[sector.colormap() * 256..][..256];
+
+        let edges: Vec<_> = polygons
+            .iter()
+            .flat_map(|vertexes| vertexes.windows(2))
+            .map(|edge| {
+                let (a, b) = (map.vertex(edge[0]), map.vertex(edge[1]));
+                ((a.x as f64, a.y as f64), (b.x as f64, b.y as f64))
+            })
+            .collect();
+
+        for (y, mut row) in target.outer_iter_mut().enumerate() {
+            let (_, world_y) = projection.unproject(0, y as i32);
+            let mut crossings: Vec<f64> = edges
+                .iter()
+                .filter(|((_, y0), (_, y1))| (*y0 > world_y) != (*y1 > world_y))
+                .map(|((x0, y0), (x1, y1))| x0 + (world_y - y0) * (x1 - x0) / (y1 - y0))
+                .collect();
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            let flat_y = ((-world_y).floor() as i32).rem_euclid(64) as usize;
+            for span in crossings.chunks_exact(2) {
+                let columns = projection.columns(span[0], span[1]);
+                for x in columns.start.max(0)..columns.end.min(width) {
+                    let (world_x, _) = projection.unproject(x, y as i32);
+                    let flat_x = (world_x.floor() as i32).rem_euclid(64) as usize;
+                    row[x as usize] = colormap[flat[(flat_y, flat_x)] as usize];
+                }
+            }
+        }
+    }
+}
+
+fn draw_lines(mut target: ArrayViewMut2<u8>, projection: &Projection, map: &Map) {
+    for linedef in &map.linedefs {
+        let v1 = map.vertex(linedef.v1);
+        let v2 = map.vertex(linedef.v2);
+        draw_line(
+            target.view_mut(),
+            projection.project(v1.x, v1.y),
+            projection.project(v2.x, v2.y),
+            map.automap_line(linedef).color(),
+        );
+    }
+}
+
+/// The sprite and frame to draw for each thing number: the first frame of
+/// the spawn state of the thing type, which is frame A for most. Player
+/// starts and deathmatch starts are drawn as the player.
+fn thing_sprites(info: &ThingInfo, overrides: &[ThingSprite]) -> BTreeMap<u16, ([u8; 4], u8)> {
+    let spawn_frame = |thing: usize| {
+        let state = info.mobjinfo[thing].state(Sequence::Spawn);
+        Some(state).filter(|&x| x != S_NULL).map(|x| {
+            let state = &info.states[x];
+            (info.sprite_names[state.sprite], state.frame_number() as u8)
+        })
+    };
+
+    let mut sprites = BTreeMap::new();
+    for (thing, mobjinfo) in info.mobjinfo.iter().enumerate() {
+        if let (Ok(doomednum), Some(frame)) = (mobjinfo.doomednum.try_into(), spawn_frame(thing)) {
+            sprites.insert(doomednum, frame);
+        }
+    }
+    if let Some(player) = info.by_name("MT_PLAYER").and_then(spawn_frame) {
+        for doomednum in (1..=4).chain(std::iter::once(DEATHMATCH_START)) {
+            sprites.insert(doomednum, player);
+        }
+    }
+    for x in overrides {
+        sprites.insert(x.doomednum, (x.sprite, x.frame));
+    }
+    sprites
+}
+
+/// Draw the things that appear with the given skill level and game mode
+/// with their sprites, centered on their positions. The rotation is the
+/// one seen from the bottom of the image, and the pixels of the sprites
+/// are not scaled with the map.
+fn draw_things(
+    mut target: ArrayViewMut2<u8>,
+    projection: &Projection,
+    index: &ResourceIndex,
+    colormap: &[u8],
+    map: &Map,
+    opt: &MapOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let info = load_thing_info(index, &opt.deh)?;
+    let sprites = thing_sprites(&info, &opt.thing_sprites);
+    let defs = sprite_defs(index);
+
+    let mut warned = BTreeSet::new();
+    let mut warn = |doomednum: u16, message: String| {
+        if warned.insert(doomednum) {
+            eprintln!("warning: Thing {}: {}", doomednum, message);
+        }
+    };
+
+    for thing in &map.things {
+        if !thing.appears(opt.skill, opt.mode, &info) {
+            continue;
+        }
+        let (sprite_name, frame) = match sprites.get(&thing.doomednum) {
+            Some(&found) => found,
+            None => {
+                warn(thing.doomednum, "Unknown thing number".into());
+                continue;
+            }
+        };
+        let sprite_name_str = String::from_utf8_lossy(&sprite_name);
+
+        // Seen from the south, looking north
+        let angle = degrees_to_bam(90. - thing.angle as f64);
+        let rotation = defs
+            .get(&sprite_name)
+            .and_then(|def| def.resolve(frame, angle));
+        let rotation = match rotation {
+            Some(rotation) => rotation,
+            None => {
+                let letter = (b'A' + frame) as char;
+                warn(
+                    thing.doomednum,
+                    format!("Unable to find sprite {}{}", sprite_name_str, letter),
+                );
+                continue;
+            }
+        };
+        let lump = index
+            .find(&rotation.lump, SPRITE_NAMESPACES)
+            .filter(|lump| validate_patch(lump).is_ok());
+        let sprite = match lump {
+            Some(lump) => Sprite::new(lump),
+            None => {
+                let lump_name = EntryId::from_bytes(&rotation.lump);
+                warn(thing.doomednum, format!("Invalid sprite {}", lump_name));
+                continue;
+            }
+        };
+
+        let (x, y) = projection.project(thing.x, thing.y);
+        let (top, left) = sprite.origin();
+        let (height, width) = sprite.dim();
+        let pos = (
+            y - height as i32 / 2 + top as i32,
+            x - width as i32 / 2 + left as i32,
+        );
+        let flip = if rotation.flip {
+            Flip::horizontal()
+        } else {
+            Flip::default()
+        };
+        draw_sprite(target.view_mut(), &sprite, pos, flip, |x| {
+            colormap[x as usize]
+        });
+    }
+
+    Ok(())
+}
+
+fn hex_color(palette: &[u8], index: u8) -> String {
+    let c = &palette[index as usize * 3..index as usize * 3 + 3];
+    format!("#{:02x}{:02x}{:02x}", c[0], c[1], c[2])
+}
+
+/// Render the map as SVG in map units, with y flipped to point down
+fn render_svg(
+    map: &Map,
+    palette: &[u8],
+    units_per_pixel: u16,
+    scale: usize,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let (_, (height, width)) = Projection::new(map, units_per_pixel)?;
+    let ((x0, _), (_, y1)) = map.bounds().ok_or("The map has no vertexes")?;
+    let unit = units_per_pixel.max(1) as i32;
+    let margin = MARGIN * unit;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        width * scale,
+        height * scale,
+        x0 as i32 - margin,
+        -(y1 as i32) - margin,
+        width as i32 * unit,
+        height as i32 * unit,
+    )?;
+    writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="100%" height="100%" fill="{}"/>"#,
+        x0 as i32 - margin,
+        -(y1 as i32) - margin,
+        hex_color(palette, BACKGROUND)
+    )?;
+    writeln!(
+        svg,
+        r#"<g stroke-width="{}" stroke-linecap="square">"#,
+        unit
+    )?;
+    for linedef in &map.linedefs {
+        let v1 = map.vertex(linedef.v1);
+        let v2 = map.vertex(linedef.v2);
+        writeln!(
+            svg,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}"/>"#,
+            v1.x,
+            -(v1.y as i32),
+            v2.x,
+            -(v2.y as i32),
+            hex_color(palette, map.automap_line(linedef).color())
+        )?;
+    }
+    svg.push_str("</g>\n</svg>\n");
+    Ok(svg)
+}
+
+/// Render a map the way the automap shows it with all of the map revealed.
+/// The output is SVG when the file name ends in .svg, and PNG otherwise.
+#[allow(clippy::too_many_arguments)]
+pub fn map_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    colormaps: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: MapOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let map = Map::from_index(index, name).map_err(|err| format!("{}: {}", name, err))?;
+
+    let is_svg = output
+        .as_ref()
+        .extension()
+        .is_some_and(|x| x.eq_ignore_ascii_case("svg"));
+    if is_svg && (opt.flats.is_some() || opt.things) {
+        return Err("--flats and --things are only supported for PNG output".into());
+    }
+    if is_svg {
+        std::fs::write(
+            output,
+            render_svg(&map, palette, opt.units_per_pixel, scale)?,
+        )?;
+        return Ok(());
+    }
+
+    let (projection, dim) = Projection::new(&map, opt.units_per_pixel)?;
+    let mut target = Array2::from_elem(dim, BACKGROUND);
+    if let Some(surface) = opt.flats {
+        draw_flats(
+            target.view_mut(),
+            &projection,
+            index,
+            colormaps,
+            &map,
+            surface,
+        );
+    }
+    if opt.flats.is_none() || opt.lines {
+        draw_lines(target.view_mut(), &projection, &map);
+    }
+    if opt.things {
+        draw_things(target.view_mut(), &projection, index, colormap, &map, &opt)?;
+    }
+    let scaled = do_scale(target.view(), scale as u32, Rational32::from(scale as i32));
+    write_png(output, Some(palette), Rational32::from(1), scaled.view())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn draw_line_covers_endpoints() {
+        let mut target = Array2::zeros((4, 8));
+        draw_line(target.view_mut(), (7, 0), (0, 3), 1);
+        assert_eq!(target[(0, 7)], 1);
+        assert_eq!(target[(3, 0)], 1);
+        assert_eq!(target.iter().filter(|&&x| x == 1).count(), 8);
+
+        // Clipped at the edges
+        draw_line(target.view_mut(), (-4, 1), (12, 1), 2);
+        assert_eq!(target.row(1).iter().filter(|&&x| x == 2).count(), 8);
+    }
+
+    #[test]
+    fn thing_sprite_table() {
+        let overrides = ["3001=SKULB".parse().unwrap(), "9001=trite".parse().unwrap()];
+        let sprites = thing_sprites(&ThingInfo::doom(), &overrides);
+        assert_eq!(sprites[&3004], (*b"POSS", 0));
+        assert_eq!(sprites[&1], (*b"PLAY", 0));
+        assert_eq!(sprites[&DEATHMATCH_START], (*b"PLAY", 0));
+        assert_eq!(sprites[&3001], (*b"SKUL", 1));
+        assert_eq!(sprites[&9001], (*b"TRIT", 4));
+        // Teleport destinations are invisible
+        assert_eq!(sprites.get(&14), None);
+        assert!("3001=TROOA1".parse::<ThingSprite>().is_err());
+    }
+
+    #[test]
+    fn parse_skill_range() {
+        assert_eq!(parse_skill("1"), Ok(1));
+        assert_eq!(parse_skill("5"), Ok(5));
+        assert!(parse_skill("0").is_err());
+        assert!(parse_skill("6").is_err());
+    }
+}
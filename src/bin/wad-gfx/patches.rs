@@ -0,0 +1,205 @@
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::Serialize;
+use structopt::StructOpt;
+use wad::EntryId;
+use wad_gfx::{parse_pnames, Namespace, ResourceIndex, TextureSet, PATCH_NAMESPACES};
+
+use crate::pattern::glob_match;
+
+#[derive(Debug, StructOpt)]
+pub struct PatchesOpt {
+    /// Output the report as JSON
+    #[structopt(long = "json")]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PatchUsage {
+    name: String,
+    /// The index of the patch in PNAMES
+    index: usize,
+    /// Whether there is a lump for the patch
+    found: bool,
+    /// The textures using the patch, in texture order
+    textures: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PatchesReport {
+    patches: Vec<PatchUsage>,
+    /// PNAMES entries that are not used by any texture
+    unused: Vec<String>,
+    /// PNAMES entries without a lump
+    missing: Vec<String>,
+    /// Lumps between P_START and P_END that are not in PNAMES
+    not_in_pnames: Vec<String>,
+}
+
+fn name_of(id: &[u8; 8]) -> String {
+    EntryId::from_bytes(id).to_string().to_ascii_uppercase()
+}
+
+fn patches_report(index: &ResourceIndex, pattern: &str) -> Result<PatchesReport, String> {
+    let pattern = pattern.to_ascii_uppercase();
+    let matches = |name: &str| glob_match(pattern.as_bytes(), name.as_bytes());
+
+    let pnames = parse_pnames(index.by_id(b"PNAMES").ok_or("Missing PNAMES")?);
+    let mut users: Vec<Vec<String>> = vec![vec![]; pnames.len()];
+    for texture in TextureSet::from_index(index).iter() {
+        let texture_name = name_of(&texture.name());
+        let used: BTreeSet<_> = (0..texture.len())
+            .map(|p| texture.patch(p).patch_id as usize)
+            .collect();
+        for patch_id in used {
+            match users.get_mut(patch_id) {
+                Some(users) => users.push(texture_name.clone()),
+                None => eprintln!(
+                    "warning: {} uses patch number {}, which is not in PNAMES",
+                    texture_name, patch_id
+                ),
+            }
+        }
+    }
+
+    let mut report = PatchesReport {
+        patches: vec![],
+        unused: vec![],
+        missing: vec![],
+        not_in_pnames: vec![],
+    };
+
+    for (i, (pname, textures)) in pnames.iter().zip(users).enumerate() {
+        let name = name_of(pname);
+        if !matches(&name) {
+            continue;
+        }
+        let found = index.find_index(pname, PATCH_NAMESPACES).is_some();
+        if textures.is_empty() {
+            report.unused.push(name.clone());
+        }
+        if !found {
+            report.missing.push(name.clone());
+        }
+        report.patches.push(PatchUsage {
+            name,
+            index: i,
+            found,
+            textures,
+        });
+    }
+
+    let listed: BTreeSet<String> = pnames.iter().map(name_of).collect();
+    for i in index.indices(Namespace::Patches) {
+        let name = name_of(index.id(i).as_bytes());
+        if !index.is_overridden(i) && matches(&name) && !listed.contains(&name) {
+            report.not_in_pnames.push(name);
+        }
+    }
+
+    Ok(report)
+}
+
+fn format_text(report: &PatchesReport) -> String {
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "{} patches, {} unused, {} missing, {} not in PNAMES",
+        report.patches.len(),
+        report.unused.len(),
+        report.missing.len(),
+        report.not_in_pnames.len()
+    )
+    .unwrap();
+
+    writeln!(out, "\n[usage]").unwrap();
+    for patch in &report.patches {
+        writeln!(out, "{:8} {}", patch.name, patch.textures.join(" ")).unwrap();
+    }
+
+    let sections = [
+        ("unused", &report.unused),
+        ("missing", &report.missing),
+        ("not in pnames", &report.not_in_pnames),
+    ];
+    for (section, names) in &sections {
+        if names.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n[{}]", section).unwrap();
+        for name in names.iter() {
+            writeln!(out, "{}", name).unwrap();
+        }
+    }
+
+    out
+}
+
+/// Report which textures use each patch, and the patches that are unused,
+/// missing or not in PNAMES. The report goes to the output file if one is
+/// given, and to stdout otherwise.
+pub fn patches_cmd(
+    index: &ResourceIndex,
+    pattern: &str,
+    output: Option<impl AsRef<Path>>,
+    opt: PatchesOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let report = patches_report(index, pattern)?;
+
+    let mut out = if opt.json {
+        serde_json::to_string_pretty(&report)?
+    } else {
+        format_text(&report)
+    };
+    if !out.ends_with('\n') {
+        out.push('\n');
+    }
+
+    match output {
+        Some(output) => std::fs::write(output, out)?,
+        None => print!("{}", out),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_wad::{pnames, test_wad, texture_dir};
+
+    #[test]
+    fn cross_reference() {
+        let pnames = pnames(&["WALL", "DOOR", "GONE"]);
+        let texture1 = texture_dir(&[
+            ("BIGWALL", 128, 128, &[(0, 0, 0), (64, 0, 0), (0, 64, 2)]),
+            ("SMALL", 64, 64, &[(0, 0, 0)]),
+        ]);
+        let wad = test_wad(&[
+            ("PNAMES", &pnames),
+            ("TEXTURE1", &texture1),
+            ("P_START", b""),
+            ("WALL", b"patch"),
+            ("DOOR", b"patch"),
+            ("EXTRA", b"patch"),
+            ("P_END", b""),
+        ]);
+        let index = ResourceIndex::new(wad.as_slice());
+
+        let report = patches_report(&index, "*").unwrap();
+        assert_eq!(report.patches.len(), 3);
+        assert_eq!(report.patches[0].textures, ["BIGWALL", "SMALL"]);
+        assert_eq!(report.unused, ["DOOR"]);
+        assert_eq!(report.missing, ["GONE"]);
+        assert_eq!(report.not_in_pnames, ["EXTRA"]);
+
+        let report = patches_report(&index, "w*").unwrap();
+        assert_eq!(report.patches.len(), 1);
+        assert!(report.not_in_pnames.is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+/// Match a lump or texture name against a pattern where * matches any
+/// sequence of characters and ? matches any single character, ignoring case
+pub fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|i| glob_match(rest, &name[i..])),
+        Some((&p, rest)) => match name.split_first() {
+            Some((&n, name_rest)) if p == b'?' || p.eq_ignore_ascii_case(&n) => {
+                glob_match(rest, name_rest)
+            }
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn glob() {
+        assert!(glob_match(b"troo*", b"TROOA1"));
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"T?OOA*", b"TROOA2A8"));
+        assert!(!glob_match(b"troo?", b"TROOA1"));
+        assert!(!glob_match(b"SKUL*", b"TROOA1"));
+    }
+}
@@ -0,0 +1,286 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use ndarray::prelude::*;
+use num_rational::Rational32;
+use structopt::StructOpt;
+use wad::EntryId;
+use wad_gfx::{
+    validate_patch, Flip, ResourceIndex, Sprite, SpriteDef, MAX_SPRITE_FRAMES, SPRITE_NAMESPACES,
+};
+
+use crate::font::Font;
+use crate::sprite::{aspect_ratios, draw_sprite, hotspot_bounds};
+use crate::{do_scale, write_png_32};
+
+#[derive(Debug)]
+pub enum Layout {
+    Row,
+    Compass,
+}
+
+impl FromStr for Layout {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Layout, &'static str> {
+        match s {
+            "row" => Ok(Layout::Row),
+            "r" => Ok(Layout::Row),
+            "compass" => Ok(Layout::Compass),
+            "c" => Ok(Layout::Compass),
+            _ => Err("layout must be 'row'/'r' or 'compass'/'c'"),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct RotationsOpt {
+    /// Layout of the rotations: row/r or compass/c. The row starts with
+    /// the front view and goes counterclockwise around the thing. The
+    /// compass places each rotation at the direction it is seen from, for
+    /// a thing facing down.
+    #[structopt(short = "l", long = "layout", default_value = "row")]
+    pub layout: Layout,
+
+    /// Label each rotation with the name of the lump used, in the font
+    /// from the WAD
+    #[structopt(short = "L", long = "labels")]
+    pub labels: bool,
+
+    /// Space between rotations, in unscaled pixels
+    #[structopt(long = "padding", default_value = "4")]
+    pub padding: u16,
+
+    /// Color index to use for the background. Defaults to transparent
+    #[structopt(short = "b", long = "background")]
+    pub background: Option<u8>,
+
+    /// Output anamorphic (non-square) pixels. Like the original assets,
+    /// the pixel aspect ratio will be 5:6.
+    #[structopt(short = "a", long = "anamorphic")]
+    pub anamorphic: bool,
+}
+
+fn parse_sprite_frame(name: &str) -> Option<([u8; 4], u8)> {
+    let name = name.to_ascii_uppercase();
+    let name = name.as_bytes();
+    if name.len() != 5 {
+        return None;
+    }
+
+    let mut sprite = [0; 4];
+    sprite.copy_from_slice(&name[0..4]);
+
+    let frame = name[4].checked_sub(b'A')?;
+    if frame as usize >= MAX_SPRITE_FRAMES {
+        return None;
+    }
+
+    Some((sprite, frame))
+}
+
+/// Grid cell (row, column) for each of `n` rotations in the given layout,
+/// along with the grid dimensions (rows, columns)
+fn layout_cells(layout: &Layout, n: usize) -> (Vec<(usize, usize)>, (usize, usize)) {
+    match layout {
+        Layout::Row => ((0..n).map(|i| (0, i)).collect(), (1, n)),
+        Layout::Compass if n == 1 => (vec![(0, 0)], (1, 1)),
+        Layout::Compass => {
+            // Place the rotations on the border of a square grid. For a
+            // thing facing down, rotation 1 is seen from below, and the
+            // following rotations go counterclockwise.
+            let side = n / 4 + 1;
+            let cells = (0..n)
+                .map(|i| {
+                    let angle = (270. + 360. * i as f64 / n as f64).to_radians();
+                    let (y, x) = angle.sin_cos();
+                    let r = x.abs().max(y.abs());
+                    let to_cell = |v: f64| ((v / r + 1.) / 2. * (side - 1) as f64).round();
+                    (to_cell(-y) as usize, to_cell(x) as usize)
+                })
+                .collect();
+            (cells, (side, side))
+        }
+    }
+}
+
+pub fn rotations_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: RotationsOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (sprite_name, frame) = parse_sprite_frame(name).ok_or_else(|| {
+        format!(
+            "Expected sprite name and frame letter, eg TROOA: {:?}",
+            name
+        )
+    })?;
+
+    let def = SpriteDef::from_wad(index, sprite_name);
+    let frame = def
+        .frame(frame)
+        .ok_or_else(|| format!("Unable to find sprite frame {}", name))?;
+
+    let rotations = frame
+        .rotations()
+        .enumerate()
+        .map(|(i, rotation)| {
+            let rotation = rotation.and_then(|rotation| {
+                let lump = index.find(&rotation.lump, SPRITE_NAMESPACES)?;
+                if let Err(err) = validate_patch(lump) {
+                    let lump_name = EntryId::from_bytes(&rotation.lump);
+                    eprintln!("warning: Skipping sprite {}: {}", lump_name, err);
+                    return None;
+                }
+                Some((Sprite::new(lump), rotation))
+            });
+            if rotation.is_none() {
+                eprintln!(
+                    "warning: {} is missing rotation {}",
+                    name,
+                    frame.rotation_number(i)
+                );
+            }
+            rotation
+        })
+        .collect::<Vec<_>>();
+
+    let font = if opt.labels {
+        let font = Font::from_wad(index);
+        if font.is_none() {
+            eprintln!("warning: Missing STCFN font lumps, unable to draw labels");
+        }
+        font
+    } else {
+        None
+    };
+
+    let labels = rotations
+        .iter()
+        .map(|x| match x {
+            Some((_, rotation)) => EntryId::from_bytes(&rotation.lump).to_string(),
+            None => String::new(),
+        })
+        .collect::<Vec<_>>();
+
+    let (y_range, x_range) = hotspot_bounds(rotations.iter().flatten().map(|(sprite, _)| sprite));
+
+    let label_height = font.as_ref().map(|font| font.height()).unwrap_or(0);
+    let label_width = font
+        .as_ref()
+        .map(|font| labels.iter().map(|x| font.text_width(x)).max().unwrap_or(0))
+        .unwrap_or(0);
+
+    let padding = opt.padding as i32;
+    let sprite_size = (y_range.end - y_range.start, x_range.end - x_range.start);
+    let label_space = font.as_ref().map(|_| padding + label_height).unwrap_or(0);
+    let cell_size = (
+        sprite_size.0 + label_space + padding,
+        sprite_size.1.max(label_width) + padding,
+    );
+
+    let (cells, grid) = layout_cells(&opt.layout, rotations.len());
+
+    let colormapper = |x| -> [u8; 4] {
+        let i = colormap[x as usize] as usize;
+        let c = &palette[i * 3..i * 3 + 3];
+        [c[0], c[1], c[2], 255]
+    };
+
+    let background = opt.background.map(colormapper).unwrap_or_default();
+    let canvas_size = (
+        (grid.0 as i32 * cell_size.0 + padding) as usize,
+        (grid.1 as i32 * cell_size.1 + padding) as usize,
+    );
+    let mut target: Array2<[u8; 4]> = Array2::from_elem(canvas_size, background);
+
+    for ((rotation, label), cell) in rotations.iter().zip(&labels).zip(cells) {
+        let cell_pos = (
+            padding + cell.0 as i32 * cell_size.0,
+            padding + cell.1 as i32 * cell_size.1,
+        );
+        let cell_width = cell_size.1 - padding;
+
+        if let Some((sprite, rotation)) = rotation {
+            let hotspot = (
+                cell_pos.0 - y_range.start,
+                cell_pos.1 + (cell_width - sprite_size.1) / 2 - x_range.start,
+            );
+            let flip = Flip {
+                x: rotation.flip,
+                y: false,
+            };
+            draw_sprite(target.view_mut(), sprite, hotspot, flip, colormapper);
+        }
+
+        if let Some(font) = &font {
+            let label_pos = (
+                cell_pos.0 + sprite_size.0 + padding,
+                cell_pos.1 + (cell_width - font.text_width(label)) / 2,
+            );
+            font.draw(target.view_mut(), label, label_pos, colormapper);
+        }
+    }
+
+    let (scale_aspect, store_aspect) = aspect_ratios(opt.anamorphic);
+
+    let scaled = do_scale(
+        target.view(),
+        scale as u32,
+        Rational32::from(scale as i32) / scale_aspect,
+    );
+
+    write_png_32(output, None, store_aspect, scaled.view())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_sprite_frame_ok() {
+        assert_eq!(parse_sprite_frame("trooa"), Some((*b"TROO", 0)));
+        assert_eq!(parse_sprite_frame("TROOC"), Some((*b"TROO", 2)));
+    }
+
+    #[test]
+    fn parse_sprite_frame_invalid() {
+        assert_eq!(parse_sprite_frame("TROO"), None);
+        assert_eq!(parse_sprite_frame("TROOA1"), None);
+        assert_eq!(parse_sprite_frame("TROO1"), None);
+    }
+
+    #[test]
+    fn compass_8() {
+        let (cells, grid) = layout_cells(&Layout::Compass, 8);
+        assert_eq!(grid, (3, 3));
+        assert_eq!(
+            cells,
+            vec![
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 1),
+                (0, 0),
+                (1, 0),
+                (2, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn compass_16_uses_distinct_cells() {
+        let (mut cells, grid) = layout_cells(&Layout::Compass, 16);
+        assert_eq!(grid, (5, 5));
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), 16);
+    }
+}
@@ -1,11 +1,12 @@
+use std::ops::Range;
 use std::path::Path;
 
 use ndarray::prelude::*;
 use num_rational::Rational32;
 use structopt::StructOpt;
-use wad_gfx::Sprite;
+use wad_gfx::{Flip, Sprite};
 
-use crate::format::Format;
+use crate::format::{Format, MASK_PALETTE};
 use crate::rangetools::{add, intersect};
 use crate::{do_scale, write_png, write_png_32};
 
@@ -13,6 +14,7 @@ fn parse_pair<T: std::str::FromStr>(src: &str) -> Result<(T, T), &'static str> {
     const FORMAT_ERROR: &str =
         "format must be two integers separated by `x` or `,`, eg 320x200 or 100,200";
 
+    #[allow(clippy::manual_pattern_char_comparison)]
     let mut split = src
         .splitn(2, |x| x == 'x' || x == ',')
         .map(|x| x.parse().map_err(|_| FORMAT_ERROR));
@@ -57,13 +59,60 @@ pub struct SpriteOpt {
     /// the pixel aspect ratio will be 5:6.
     #[structopt(short = "a", long = "anamorphic")]
     pub anamorphic: bool,
+
+    /// Mirror the sprite horizontally, like the engine does for mirrored
+    /// rotations. The sprite covers the same area as when unflipped.
+    #[structopt(long = "flip")]
+    pub flip: bool,
+}
+
+/// The pixel aspect ratios to scale by and to store in the output,
+/// respectively, for correcting the 5:6 aspect ratio of the original pixels
+/// either during rendering or in the viewer
+pub fn aspect_ratios(anamorphic: bool) -> (Rational32, Rational32) {
+    if anamorphic {
+        (
+            Rational32::new(1, 1),
+            Rational32::new(4, 3) / Rational32::new(320, 200),
+        )
+    } else {
+        (
+            Rational32::new(4, 3) / Rational32::new(320, 200),
+            Rational32::new(1, 1),
+        )
+    }
+}
+
+/// The bounding box of the given sprites when drawn with their hotspots at
+/// the same point, relative to the hotspot, as (y range, x range)
+pub fn hotspot_bounds<'a, 'b: 'a>(
+    sprites: impl IntoIterator<Item = &'a Sprite<'b>>,
+) -> (Range<i32>, Range<i32>) {
+    let mut bounds: Option<(Range<i32>, Range<i32>)> = None;
+
+    for sprite in sprites {
+        let (top, left) = sprite.origin();
+        let y_range = -top as i32..sprite.height() as i32 - top as i32;
+        let x_range = -left as i32..sprite.width() as i32 - left as i32;
+
+        bounds = Some(match bounds {
+            None => (y_range, x_range),
+            Some((y, x)) => (
+                y.start.min(y_range.start)..y.end.max(y_range.end),
+                x.start.min(x_range.start)..x.end.max(x_range.end),
+            ),
+        });
+    }
+
+    bounds.unwrap_or((0..0, 0..0))
 }
 
 // TODO Refactor to avoid or reduce duplication with SpriteCanvas
-fn draw_sprite<Px>(
+pub fn draw_sprite<Px>(
     mut target: ArrayViewMut2<Px>,
     sprite: &Sprite,
     pos: (i32, i32),
+    flip: Flip,
     pixel_mapper: impl Fn(u8) -> Px,
 ) {
     let (o_y, o_x) = sprite.origin();
@@ -77,16 +126,17 @@ fn draw_sprite<Px>(
     let x_range = intersect(x_range, 0..target.dim().1 as i32); // Clip to canvas
 
     for x in x_range {
-        for span in sprite.col((x - offset.1) as _) {
-            let y_offset = offset.0 + span.top as i32;
+        let src_x = flip.map_x(x - offset.1, sprite.width());
+        for span in sprite.col(src_x as _) {
+            let y_offset = offset.0 + flip.map_span_top(&span, sprite.height());
 
             let span_range = 0..span.pixels.len() as i32;
             let span_range = add(span_range, y_offset);
             let span_range = intersect(span_range, 0..target.dim().0 as i32);
 
             for y in span_range {
-                target[[y as usize, x as usize]] =
-                    pixel_mapper(span.pixels[(y - y_offset) as usize]);
+                let src_y = flip.map_span_pixel(y - y_offset, &span);
+                target[[y as usize, x as usize]] = pixel_mapper(span.pixels[src_y]);
             }
         }
     }
@@ -105,6 +155,7 @@ pub fn sprite_cmd(
         format,
         background,
         anamorphic,
+        flip,
     }: SpriteOpt,
 ) -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(palette.len(), 768);
@@ -123,17 +174,7 @@ pub fn sprite_cmd(
         return Ok(());
     }
 
-    let (scale_aspect, store_aspect) = if anamorphic {
-        (
-            Rational32::new(1, 1),
-            Rational32::new(4, 3) / Rational32::new(320, 200),
-        )
-    } else {
-        (
-            Rational32::new(4, 3) / Rational32::new(320, 200),
-            Rational32::new(1, 1),
-        )
-    };
+    let (scale_aspect, store_aspect) = aspect_ratios(anamorphic);
 
     let canvas_size = canvas_size
         .map(|(y, x)| (y as usize, x as usize))
@@ -144,6 +185,8 @@ pub fn sprite_cmd(
         (y as _, x as _)
     });
 
+    let flip = Flip { x: flip, y: false };
+
     // PNG can store the pixel aspect ratio in the pHYs chunk. So, I can
     // envision two modes: correcting the pixel aspect ratio by scaling
     // during rendering or storing anamorphic pixels, but specifying the
@@ -157,7 +200,9 @@ pub fn sprite_cmd(
 
             let mut target: Array2<u8> = Array2::from_elem(canvas_size, background);
 
-            draw_sprite(target.view_mut(), &sprite, pos, |x| colormap[x as usize]);
+            draw_sprite(target.view_mut(), &sprite, pos, flip, |x| {
+                colormap[x as usize]
+            });
 
             let scaled = do_scale(
                 target.view(),
@@ -176,7 +221,7 @@ pub fn sprite_cmd(
 
             let mut target: Array2<u8> = Array2::zeros(canvas_size);
 
-            draw_sprite(target.view_mut(), &sprite, pos, |_| 1);
+            draw_sprite(target.view_mut(), &sprite, pos, flip, |_| 1);
 
             let scaled = do_scale(
                 target.view(),
@@ -184,7 +229,6 @@ pub fn sprite_cmd(
                 Rational32::from(scale as i32) / scale_aspect,
             );
 
-            const MASK_PALETTE: &[u8] = &[0, 0, 0, 255, 255, 255];
             write_png(output, Some(MASK_PALETTE), store_aspect, scaled.view())?;
 
             Ok(())
@@ -200,7 +244,7 @@ pub fn sprite_cmd(
 
             let mut target: Array2<[u8; 4]> = Array2::from_elem(canvas_size, background);
 
-            draw_sprite(target.view_mut(), &sprite, pos, colormapper);
+            draw_sprite(target.view_mut(), &sprite, pos, flip, colormapper);
 
             let scaled = do_scale(
                 target.view(),
@@ -244,6 +288,16 @@ mod test {
         assert_eq!(parse_pair("10,10"), Ok((10i16, 10i16)));
     }
 
+    #[test]
+    fn hotspot_bounds_of_single_sprite() {
+        let sprite = Sprite::new(include_bytes!("../../trooa1.sprite"));
+        let (top, left) = (sprite.top() as i32, sprite.left() as i32);
+        assert_eq!(
+            hotspot_bounds(&[sprite]),
+            (-top..57 - top, -left..41 - left)
+        );
+    }
+
     #[test]
     fn parse_pair_result_as_y_x() {
         assert_eq!(parse_pair("320x200"), Ok((200, 320)));
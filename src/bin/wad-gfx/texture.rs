@@ -1,15 +1,26 @@
 use std::path::Path;
+
+use ndarray::prelude::*;
+use num_rational::Rational32;
 use structopt::StructOpt;
 use wad::EntryId;
-use wad_gfx::{parse_pnames, render_texture, LazyPatchProvider, TextureDirectory};
+use wad_gfx::{
+    parse_pnames, render_texture_planes, render_texture_vanilla, LazyPatchProvider, PatchProvider,
+    ResourceIndex, Texture, TextureSet, TEXTURE_LUMPS,
+};
 
-use crate::format::Format;
-use crate::sprite::{sprite_cmd, SpriteOpt};
+use crate::format::{Format, MASK_PALETTE};
+use crate::pattern::glob_match;
+use crate::sprite::aspect_ratios;
+use crate::{do_scale, write_png, write_png_32};
 
 #[derive(Debug, StructOpt)]
-pub struct ExtractOpt {
-    /// The name of the texture to extract
-    name: String,
+pub struct TextureOpt {
+    /// List the textures matching the name, which is a pattern, with their
+    /// size, number of patches, the lump they are defined in and any header
+    /// flags, instead of generating an output image
+    #[structopt(short = "l", long = "list")]
+    list: bool,
 
     /// Print information about the texture in DeuTex format to stdout instead
     /// of generating an output image
@@ -31,93 +42,215 @@ pub struct ExtractOpt {
     /// the pixel aspect ratio will be 5:6.
     #[structopt(short = "a", long = "anamorphic")]
     anamorphic: bool,
-}
 
-#[derive(Debug, StructOpt)]
-pub enum TextureOpt {
-    /// List textures in directory
-    #[structopt(name = "list")]
-    List,
-
-    /// Extract a texture
-    #[structopt(name = "extract")]
-    Extract(ExtractOpt),
+    /// Composite the texture the way the vanilla renderer does, bugs and
+    /// all, rather than the way it was meant to look. Shows the
+    /// tutti-frutti effect of short patches and patches with holes, and
+    /// the clamping of negative patch Y offsets.
+    #[structopt(long = "vanilla")]
+    vanilla: bool,
+
+    /// With --vanilla, draw the texture as a masked mid-texture, with
+    /// transparency and the Medusa effect of columns covered by several
+    /// patches
+    #[structopt(long = "masked")]
+    masked: bool,
 }
 
-pub fn texture_cmd(
-    wad: &wad::Wad,
+/// Write a texture given as pixel and mask planes
+#[allow(clippy::too_many_arguments)]
+pub fn write_planes(
     palette: &[u8],
     colormap: &[u8],
-    texture_dir: &[u8],
+    pixels: ArrayView2<u8>,
+    mask: ArrayView2<bool>,
     scale: usize,
     output: impl AsRef<Path>,
-    opt: TextureOpt,
+    format: Format,
+    background: Option<u8>,
+    anamorphic: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let texture_dir = TextureDirectory::new(texture_dir);
+    let (scale_aspect, store_aspect) = aspect_ratios(anamorphic);
+    let scale_y = Rational32::from(scale as i32) / scale_aspect;
 
-    match opt {
-        TextureOpt::List => {
-            for i in 0..texture_dir.len() {
-                let texture = texture_dir.texture(i);
-                println!("{}", EntryId::from_bytes(&texture.name()));
+    match format {
+        Format::Indexed => {
+            let background =
+                background.ok_or("--background must be specified for the indexed format")?;
+            let mut target = pixels.map(|&x| colormap[x as usize]);
+            target.zip_mut_with(&mask, |x, &m| {
+                if !m {
+                    *x = background
+                }
+            });
+            let scaled = do_scale(target.view(), scale as u32, scale_y);
+            write_png(output, Some(palette), store_aspect, scaled.view())
+        }
+        Format::Mask => {
+            if background.is_some() {
+                eprintln!("warning: --background has no effect for mask format");
             }
+            let target = mask.map(|&m| m as u8);
+            let scaled = do_scale(target.view(), scale as u32, scale_y);
+            write_png(output, Some(MASK_PALETTE), store_aspect, scaled.view())
+        }
+        Format::Full => {
+            let colormapper = |x: u8| -> [u8; 4] {
+                let i = colormap[x as usize] as usize;
+                let c = &palette[i * 3..i * 3 + 3];
+                [c[0], c[1], c[2], 255]
+            };
+            let background = background.map(colormapper).unwrap_or_default();
+            let mut target = pixels.map(|&x| colormapper(x));
+            target.zip_mut_with(&mask, |x, &m| {
+                if !m {
+                    *x = background
+                }
+            });
+            let scaled = do_scale(target.view(), scale as u32, scale_y);
+            write_png_32(output, None, store_aspect, scaled.view())
+        }
+    }
+}
+
+/// Describe the fields of the texture header that are usually 0: the masked
+/// flag, and the flags and scale of ZDoom. Each is preceded by a space.
+fn header_flags(texture: Texture) -> String {
+    let mut flags = String::new();
+    if texture.masked() {
+        flags.push_str(" masked");
+    }
+    if texture.world_panning() {
+        flags.push_str(" world-panning");
+    }
+    if texture.scale_x() != 0 || texture.scale_y() != 0 {
+        flags.push_str(&format!(
+            " scale={}/8,{}/8",
+            texture.scale_x(),
+            texture.scale_y()
+        ));
+    }
+    flags
+}
+
+/// Composite a texture from its patches into pixel and mask planes
+pub fn composite_texture(
+    index: &ResourceIndex,
+    texture: Texture,
+) -> Result<(Array2<u8>, Array2<bool>), String> {
+    let pnames = parse_pnames(index.by_id(b"PNAMES").ok_or("Missing PNAMES")?);
+    let patch_provider = LazyPatchProvider::new(index, pnames);
 
-            Ok(())
+    for i in 0..texture.len() {
+        let patch_id = texture.patch(i).patch_id;
+        if patch_provider.patch(patch_id).is_none() {
+            let patch_name = pnames
+                .get(patch_id as usize)
+                .map(|x| EntryId::from_bytes(x).to_string())
+                .unwrap_or_else(|| format!("number {}", patch_id));
+            return Err(format!(
+                "{} uses missing or malformed patch {}",
+                EntryId::from_bytes(&texture.name()),
+                patch_name
+            ));
         }
-        TextureOpt::Extract(opt) => {
-            let pnames = parse_pnames(wad.by_id(b"PNAMES").ok_or("Missing PNAMES")?);
+    }
 
-            let texture_id = EntryId::from_str(&opt.name)
-                .ok_or_else(|| format!("Invalid ID: {:?}", opt.name))?;
+    Ok(render_texture_planes(texture, &patch_provider))
+}
 
-            let mut texture = None;
+pub fn texture_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: TextureOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let textures = TextureSet::from_index(index);
+    if textures.is_empty() {
+        return Err("Missing TEXTURE1 and TEXTURE2".into());
+    }
 
-            for i in 0..texture_dir.len() {
-                let t = texture_dir.texture(i);
-                if EntryId::from_bytes(&t.name()) == texture_id {
-                    texture = Some(t);
-                    break;
-                }
+    if opt.list {
+        let pattern = name.to_ascii_uppercase();
+        for i in 0..textures.len() {
+            let texture = textures.texture(i);
+            let texture_name = EntryId::from_bytes(&texture.name()).to_string();
+            if !glob_match(pattern.as_bytes(), texture_name.as_bytes()) {
+                continue;
             }
+            println!(
+                "{:8} {:>4}x{:<4} {:>3} {}{}{}",
+                texture_name,
+                texture.width(),
+                texture.height(),
+                texture.len(),
+                EntryId::from_bytes(TEXTURE_LUMPS[textures.directory(i)]),
+                header_flags(texture),
+                if textures.is_overridden(i) {
+                    " (overridden)"
+                } else {
+                    ""
+                },
+            );
+        }
 
-            let texture = texture.ok_or_else(|| format!("Unable to find texture {}", opt.name))?;
-
-            if opt.info {
-                println!("; TextureName Width Height");
-                println!("{} {} {}", texture_id, texture.width(), texture.height());
-                println!("; PatchName Xoffset Yoffset");
-                for i in 0..texture.len() {
-                    let patch = texture.patch(i);
-                    println!(
-                        "* {} {} {}",
-                        EntryId::from_bytes(&pnames[patch.patch_id as usize]),
-                        patch.origin_x,
-                        patch.origin_y
-                    );
-                }
-                return Ok(());
-            }
+        return Ok(());
+    }
 
-            let patch_provider = LazyPatchProvider::new(wad.as_slice(), pnames);
-
-            let texture_sprite = render_texture(texture, &patch_provider);
-
-            // TODO Refactor to avoid reusing top-level entrypoint
-            sprite_cmd(
-                palette,
-                colormap,
-                &texture_sprite,
-                scale,
-                output,
-                SpriteOpt {
-                    canvas_size: None,
-                    pos: None,
-                    info: false,
-                    format: opt.format,
-                    background: opt.background,
-                    anamorphic: opt.anamorphic,
-                },
-            )
+    let texture = textures
+        .find(name.as_bytes())
+        .ok_or_else(|| format!("Unable to find texture {}", name))?;
+
+    if opt.info {
+        let pnames = parse_pnames(index.by_id(b"PNAMES").ok_or("Missing PNAMES")?);
+        println!("; TextureName Width Height");
+        println!(
+            "{} {} {}",
+            EntryId::from_bytes(&texture.name()),
+            texture.width(),
+            texture.height()
+        );
+        println!("; PatchName Xoffset Yoffset");
+        for i in 0..texture.len() {
+            let patch = texture.patch(i);
+            match pnames.get(patch.patch_id as usize) {
+                Some(patch_name) => println!(
+                    "* {} {} {}",
+                    EntryId::from_bytes(patch_name),
+                    patch.origin_x,
+                    patch.origin_y
+                ),
+                None => println!(
+                    "; missing patch number {} at {} {}",
+                    patch.patch_id, patch.origin_x, patch.origin_y
+                ),
+            }
         }
+        return Ok(());
+    }
+
+    if opt.masked && !opt.vanilla {
+        return Err("--masked requires --vanilla".into());
     }
+    let (pixels, mask) = if opt.vanilla {
+        let pnames = parse_pnames(index.by_id(b"PNAMES").ok_or("Missing PNAMES")?);
+        let patch_provider = LazyPatchProvider::new(index, pnames);
+        render_texture_vanilla(texture, &patch_provider, opt.masked)
+    } else {
+        composite_texture(index, texture)?
+    };
+    write_planes(
+        palette,
+        colormap,
+        pixels.view(),
+        mask.view(),
+        scale,
+        output,
+        opt.format,
+        opt.background,
+        opt.anamorphic,
+    )
 }
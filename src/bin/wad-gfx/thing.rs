@@ -0,0 +1,198 @@
+use std::path::{Path, PathBuf};
+
+use structopt::StructOpt;
+use wad_gfx::{sprite_defs, ResourceIndex, Sequence, ThingInfo};
+
+use crate::animate::{
+    find_rotation, parse_rotation, write_animation, AnimationFrame, AnimationOutputOpt,
+};
+use crate::apng::TICRATE;
+
+#[derive(Debug, StructOpt)]
+pub struct ThingOpt {
+    /// Which state sequence to animate: spawn, see, pain, melee,
+    /// missile/attack, death, xdeath or raise
+    #[structopt(default_value = "spawn")]
+    pub state_sequence: Sequence,
+
+    /// Which rotation to use, 1-8, or 1-16 for ZDoom-style sprites. Frames
+    /// that look the same from all directions are used as is.
+    #[structopt(
+        short = "r",
+        long = "rotation",
+        default_value = "1",
+        parse(try_from_str = "parse_rotation")
+    )]
+    pub rotation: u8,
+
+    /// Print the states of the sequence instead of writing an animation
+    #[structopt(long = "list")]
+    pub list: bool,
+
+    /// DeHackEd patch to apply to the state tables, after the DEHACKED
+    /// lump of the WAD, if any. May be given multiple times
+    #[structopt(long = "deh", parse(from_os_str))]
+    pub deh: Vec<PathBuf>,
+
+    #[structopt(flatten)]
+    pub output: AnimationOutputOpt,
+}
+
+/// Find a thing type by its map thing number or by its name, eg MT_TROOP
+fn find_thing(info: &ThingInfo, name: &str) -> Option<usize> {
+    match name.parse::<i32>() {
+        Ok(doomednum) => info.by_doomednum(doomednum),
+        Err(_) => info.by_name(name),
+    }
+}
+
+/// The state tables of Doom II, as modified by the DEHACKED lumps of the
+/// WADs, in load order, and the given DeHackEd patch files
+pub fn load_thing_info(
+    index: &ResourceIndex,
+    deh: &[PathBuf],
+) -> Result<ThingInfo, Box<dyn std::error::Error>> {
+    let mut info = ThingInfo::doom();
+
+    let mut apply = |source: &str, patch: &[u8]| {
+        for warning in info.apply_dehacked(patch) {
+            eprintln!("warning: {} {}", source, warning);
+        }
+    };
+
+    for i in 0..index.len() {
+        if index.id(i).as_bytes() == b"DEHACKED" {
+            apply("DEHACKED", index.entry(i)?.lump);
+        }
+    }
+    for path in deh {
+        apply(&path.display().to_string(), &std::fs::read(path)?);
+    }
+
+    Ok(info)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn thing_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormap: &[u8],
+    fullbright_colormap: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: ThingOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let info = load_thing_info(index, &opt.deh)?;
+    let thing = find_thing(&info, name)
+        .ok_or_else(|| format!("Unknown thing number or type: {:?}", name))?;
+    let thing_name = info.mobjinfo[thing].name;
+
+    let sequence = info.sequence(thing, opt.state_sequence);
+    if sequence.frames.is_empty() {
+        return Err(format!(
+            "{} has no {} sequence",
+            thing_name,
+            opt.state_sequence.name()
+        )
+        .into());
+    }
+
+    if opt.list {
+        for (index, frame) in sequence.frames.iter().enumerate() {
+            let state = &info.states[frame.state];
+            let loop_marker = if Some(index) == sequence.loop_start {
+                '>'
+            } else {
+                ' '
+            };
+            println!(
+                "{}{:>4} {}{} {:>3} {:6} {}",
+                loop_marker,
+                frame.state,
+                String::from_utf8_lossy(&frame.sprite),
+                (b'A' + frame.frame) as char,
+                frame.tics,
+                if frame.fullbright { "bright" } else { "" },
+                state.action.unwrap_or(""),
+            );
+        }
+        return Ok(());
+    }
+
+    let defs = sprite_defs(index);
+
+    // States lasting 0 tics are never drawn. A state that lasts forever
+    // is held for a second, which is only visible when the animation loops.
+    let frames = sequence
+        .frames
+        .iter()
+        .filter(|frame| frame.tics != 0)
+        .map(|frame| {
+            let def = defs.get(&frame.sprite).ok_or_else(|| {
+                format!(
+                    "Unable to find sprite {}",
+                    String::from_utf8_lossy(&frame.sprite)
+                )
+            })?;
+            let (sprite, flip) = find_rotation(index, def, frame.frame, opt.rotation)?;
+            let tics = match frame.tics {
+                -1 => TICRATE,
+                tics => tics,
+            };
+            Ok(AnimationFrame {
+                sprite,
+                flip,
+                tics,
+                fullbright: frame.fullbright,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let num_plays = match sequence.loop_start {
+        Some(_) => 0,
+        None => 1,
+    };
+    let num_plays = opt.output.loops.unwrap_or(num_plays);
+
+    // Animated PNGs can only loop all of their frames, so the frames before
+    // the state the sequence loops back to are repeated too
+    if let Some(loop_start) = sequence.loop_start {
+        let intro = sequence.frames[..loop_start]
+            .iter()
+            .any(|frame| frame.tics != 0);
+        if intro && num_plays != 1 {
+            eprintln!(
+                "warning: {} loops back to state {}, but the animation repeats all \
+                 frames, so the states before it play again on every loop",
+                thing_name, sequence.frames[loop_start].state
+            );
+        }
+    }
+
+    write_animation(
+        palette,
+        colormap,
+        fullbright_colormap,
+        &frames,
+        scale,
+        output.as_ref(),
+        num_plays,
+        &opt.output,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_thing_by_number_or_name() {
+        let info = ThingInfo::doom();
+        let imp = find_thing(&info, "3001");
+        assert!(imp.is_some());
+        assert_eq!(find_thing(&info, "troop"), imp);
+        assert_eq!(find_thing(&info, "MT_TROOP"), imp);
+        assert_eq!(find_thing(&info, "12345"), None);
+    }
+}
@@ -0,0 +1,277 @@
+use std::path::Path;
+
+use ndarray::prelude::*;
+use structopt::StructOpt;
+use wad::EntryId;
+use wad_gfx::{
+    parse_pnames, render_texture_vanilla, LazyPatchProvider, Map, ResourceIndex, Side, TextureSet,
+    Wall, WallSection,
+};
+
+use crate::format::Format;
+use crate::texture::{composite_texture, write_planes};
+
+#[derive(Debug, StructOpt)]
+pub struct WallOpt {
+    /// The number of the linedef, as shown in map editors
+    linedef: usize,
+
+    /// The side of the linedef to draw: front/f or back/b
+    #[structopt(long = "side", default_value = "front")]
+    side: Side,
+
+    /// Output format: full/f, indexed/i or mask/m. Full color uses the
+    /// alpha channel for transparency. Indexed color does not include
+    /// transparency, but can be combined with the mask.
+    #[structopt(short = "f", long = "format", default_value = "full")]
+    format: Format,
+
+    /// Color index to use for the background
+    #[structopt(short = "b", long = "background")]
+    background: Option<u8>,
+
+    /// Composite the textures the way the vanilla renderer does, with the
+    /// middle texture of a two-sided line drawn as a masked texture
+    #[structopt(long = "vanilla")]
+    vanilla: bool,
+}
+
+/// Draw the texture of a section of a wall, given as pixel and mask planes,
+/// onto the planes of the whole wall. Row 0 of the target is at the top of
+/// the wall, and each pixel is one map unit. The texture must not be empty.
+///
+/// With `vanilla`, the rows and columns wrap like in the vanilla wall
+/// drawer: rows with a mask of 127, and columns with a mask of the largest
+/// power of two that fits in the width. The garbage that vanilla shows
+/// below the bottom of textures that are less than 128 tall is left
+/// transparent.
+fn draw_section(
+    wall: &Wall,
+    section: &WallSection,
+    texture: (ArrayView2<u8>, ArrayView2<bool>),
+    mut pixels: ArrayViewMut2<u8>,
+    mut mask: ArrayViewMut2<bool>,
+    vanilla: bool,
+) {
+    let (texture_pixels, texture_mask) = texture;
+    let (texture_height, texture_width) = texture_pixels.dim();
+    let (texture_height, texture_width) = (texture_height as i32, texture_width as i32);
+    let width_mask = (1 << (31 - texture_width.leading_zeros())) - 1;
+
+    for z in section.bottom + 1..=section.top {
+        let y = (wall.top - z) as usize;
+        let row = section.texture_row(z, texture_height, wall.y_offset);
+        let row = if section.tiled && vanilla {
+            row & 127
+        } else if section.tiled {
+            row.rem_euclid(texture_height)
+        } else {
+            row
+        };
+        if !(0..texture_height).contains(&row) {
+            continue;
+        }
+
+        for x in 0..pixels.dim().1 {
+            let column = x as i32 + wall.x_offset as i32;
+            let column = if vanilla {
+                column & width_mask
+            } else {
+                column.rem_euclid(texture_width)
+            };
+            let src = (row as usize, column as usize);
+            if texture_mask[src] {
+                pixels[(y, x)] = texture_pixels[src];
+                mask[(y, x)] = true;
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn wall_cmd(
+    index: &ResourceIndex,
+    palette: &[u8],
+    colormaps: &[u8],
+    name: &str,
+    scale: usize,
+    output: impl AsRef<Path>,
+    opt: WallOpt,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let map = Map::from_index(index, name).map_err(|err| format!("{}: {}", name, err))?;
+    let wall = map
+        .wall(opt.linedef, opt.side)
+        .map_err(|err| format!("{}: linedef {}: {}", name, opt.linedef, err))?;
+    if wall.length <= 0 || wall.top <= wall.bottom {
+        return Err(format!("{}: linedef {}: the side has no wall", name, opt.linedef).into());
+    }
+
+    let textures = TextureSet::from_index(index);
+    let pnames = index.by_id(b"PNAMES").map(parse_pnames);
+
+    let dim = ((wall.top - wall.bottom) as usize, wall.length as usize);
+    let mut pixels = Array2::zeros(dim);
+    let mut mask = Array2::from_elem(dim, false);
+
+    for section in &wall.sections {
+        let texture_name = EntryId::from_bytes(&section.texture);
+        let texture = match textures.find(texture_name.to_string().as_bytes()) {
+            Some(texture) => texture,
+            None => {
+                eprintln!("warning: Unable to find texture {}", texture_name);
+                continue;
+            }
+        };
+        if texture.width() == 0 || texture.height() == 0 {
+            eprintln!("warning: Skipping empty texture {}", texture_name);
+            continue;
+        }
+
+        let planes = if opt.vanilla {
+            let pnames = pnames.ok_or("Missing PNAMES")?;
+            let patch_provider = LazyPatchProvider::new(index, pnames);
+            Ok(render_texture_vanilla(
+                texture,
+                &patch_provider,
+                !section.tiled,
+            ))
+        } else {
+            composite_texture(index, texture)
+        };
+        let (texture_pixels, texture_mask) = match planes {
+            Ok(planes) => planes,
+            Err(err) => {
+                eprintln!("warning: {}", err);
+                continue;
+            }
+        };
+
+        draw_section(
+            &wall,
+            section,
+            (texture_pixels.view(), texture_mask.view()),
+            pixels.view_mut(),
+            mask.view_mut(),
+            opt.vanilla,
+        );
+    }
+
+    // Lit by the sector, like the flats of the map subcommand
+    let colormap = &colormaps[wall.colormap() * 256..][..256];
+    write_planes(
+        palette,
+        colormap,
+        pixels.view(),
+        mask.view(),
+        scale,
+        output,
+        opt.format,
+        opt.background,
+        false,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wad_gfx::{Peg, WallPart};
+
+    #[test]
+    fn draw_sections() {
+        let wall = Wall {
+            length: 6,
+            bottom: 0,
+            top: 8,
+            x_offset: 1,
+            y_offset: 0,
+            light: 255,
+            sections: vec![],
+        };
+        // A 4x4 texture where each texel is 10 * row + column
+        let texture = Array2::from_shape_fn((4, 4), |(y, x)| (10 * y + x) as u8);
+        let texture_mask = Array2::from_elem((4, 4), true);
+        let mut pixels = Array2::zeros((8, 6));
+        let mut mask = Array2::from_elem((8, 6), false);
+
+        let upper = WallSection {
+            part: WallPart::Upper,
+            texture: *b"UPPER\0\0\0",
+            bottom: 5,
+            top: 8,
+            peg: Peg::Bottom(5),
+            tiled: true,
+        };
+        let middle = WallSection {
+            part: WallPart::Middle,
+            texture: *b"MIDDLE\0\0",
+            bottom: 0,
+            top: 5,
+            peg: Peg::Top(3),
+            tiled: false,
+        };
+        for section in &[upper, middle] {
+            draw_section(
+                &wall,
+                section,
+                (texture.view(), texture_mask.view()),
+                pixels.view_mut(),
+                mask.view_mut(),
+                false,
+            );
+        }
+
+        // The bottom of the upper texture is at its bottom, and the
+        // columns are shifted by the X offset
+        assert_eq!(pixels.row(2).to_vec(), [31, 32, 33, 30, 31, 32]);
+        assert_eq!(pixels.row(0).to_vec(), [11, 12, 13, 10, 11, 12]);
+        // The middle texture is not tiled: the rows above its top at 3 and
+        // below its bottom at -1 are left transparent
+        assert!(!mask.row(3).iter().any(|&x| x));
+        assert!(!mask.row(4).iter().any(|&x| x));
+        assert_eq!(pixels.row(5)[0], 1);
+        assert_eq!(pixels.row(7)[0], 21);
+        assert!(mask.row(7).iter().all(|&x| x));
+    }
+
+    #[test]
+    fn draw_sections_vanilla() {
+        let wall = Wall {
+            length: 8,
+            bottom: 0,
+            top: 130,
+            x_offset: -1,
+            y_offset: 0,
+            light: 255,
+            sections: vec![],
+        };
+        // A 6x4 texture, which wraps after 4 columns in vanilla
+        let texture = Array2::from_shape_fn((4, 6), |(y, x)| (10 * y + x) as u8);
+        let texture_mask = Array2::from_elem((4, 6), true);
+        let mut pixels = Array2::zeros((130, 8));
+        let mut mask = Array2::from_elem((130, 8), false);
+
+        let middle = WallSection {
+            part: WallPart::Middle,
+            texture: *b"MIDDLE\0\0",
+            bottom: 0,
+            top: 130,
+            peg: Peg::Top(130),
+            tiled: true,
+        };
+        draw_section(
+            &wall,
+            &middle,
+            (texture.view(), texture_mask.view()),
+            pixels.view_mut(),
+            mask.view_mut(),
+            true,
+        );
+
+        assert_eq!(pixels.row(0).to_vec(), [3, 0, 1, 2, 3, 0, 1, 2]);
+        // The rows wrap at 128 rather than at the height of the texture
+        assert!(mask.row(3).iter().all(|&x| x));
+        assert!(!mask.row(4).iter().any(|&x| x));
+        assert!(!mask.row(127).iter().any(|&x| x));
+        assert_eq!(pixels.row(129)[1], 10);
+    }
+}
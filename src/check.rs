@@ -0,0 +1,542 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use byteorder::{ByteOrder, LittleEndian};
+use wad::EntryId;
+
+use crate::{
+    parse_pnames, sprite_defs, validate_pnames, validate_texture_directory, Namespace,
+    ResourceIndex, SpriteDef, TextureSet, FLAT_SIZE, PATCH_NAMESPACES, PNG_SIGNATURE,
+    TEXTURE_LUMPS,
+};
+
+/// How bad a problem is
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    /// Unusual, but harmless
+    Info,
+    /// Drawn wrongly, or only works in some engines
+    Warning,
+    /// Makes the engine quit with an error or crash
+    Error,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" | "i" => Ok(Severity::Info),
+            "warning" | "w" => Ok(Severity::Warning),
+            "error" | "e" => Ok(Severity::Error),
+            _ => Err("Unrecognized severity. Valid values are info/i, warning/w and error/e"),
+        }
+    }
+}
+
+/// The kinds of problems that are checked for
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Check {
+    /// A directory entry pointing outside of the WAD
+    InvalidEntry,
+    /// A patch header that is too short or gives an empty patch
+    MalformedPatch,
+    /// Column offsets pointing outside of the column data
+    ColumnOffset,
+    /// Posts running past the end of the lump or the height of the patch
+    PostOverrun,
+    /// A PNAMES or TEXTUREx lump too short for the counts and offsets in it
+    MalformedTextureLump,
+    /// A texture without patches
+    EmptyTexture,
+    /// A texture using a patch that is not in PNAMES or has no lump
+    MissingPatch,
+    /// The unused stepdir and colormap fields of a texture patch set
+    PatchFields,
+    /// Several textures with the same name
+    DuplicateTexture,
+    /// A flat that is not 64x64
+    FlatSize,
+    /// A sprite with a gap in its frames
+    MissingFrame,
+    /// A rotating sprite frame without all rotations
+    MissingRotation,
+    /// A texture that is not a power of two tall, see `texture_quirks`
+    TuttiFrutti,
+    /// A texture with columns covered by more than one patch
+    Medusa,
+    /// A texture patch with a negative Y offset
+    NegativeYClamp,
+    /// A texture that is not a power of two wide
+    NonPowerOfTwoWidth,
+}
+
+impl Check {
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::InvalidEntry => "invalid-entry",
+            Check::MalformedPatch => "malformed-patch",
+            Check::ColumnOffset => "column-offset",
+            Check::PostOverrun => "post-overrun",
+            Check::MalformedTextureLump => "malformed-texture-lump",
+            Check::EmptyTexture => "empty-texture",
+            Check::MissingPatch => "missing-patch",
+            Check::PatchFields => "patch-fields",
+            Check::DuplicateTexture => "duplicate-texture",
+            Check::FlatSize => "flat-size",
+            Check::MissingFrame => "missing-frame",
+            Check::MissingRotation => "missing-rotation",
+            Check::TuttiFrutti => "tutti-frutti",
+            Check::Medusa => "medusa",
+            Check::NegativeYClamp => "negative-y-clamp",
+            Check::NonPowerOfTwoWidth => "non-power-of-two-width",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Problem {
+    pub severity: Severity,
+    pub check: Check,
+    /// The lump, texture or sprite frame with the problem
+    pub subject: String,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(severity: Severity, check: Check, subject: &str, message: String) -> Problem {
+        Problem {
+            severity,
+            check,
+            subject: subject.to_string(),
+            message,
+        }
+    }
+}
+
+fn columns(list: &[usize]) -> String {
+    match list {
+        [x] => format!("column {}", x),
+        _ => format!("{} columns, starting with column {}", list.len(), list[0]),
+    }
+}
+
+/// Check the structure of a lump in patch format, like `validate_patch`,
+/// but find all the problems and describe them
+pub fn check_patch(name: &str, data: &[u8]) -> Vec<Problem> {
+    use Severity::*;
+
+    let mut problems = vec![];
+    let mut report =
+        |severity, check, message| problems.push(Problem::new(severity, check, name, message));
+
+    if data.len() < 8 {
+        let message = format!("{} bytes is too short for a patch header", data.len());
+        report(Error, Check::MalformedPatch, message);
+        return problems;
+    }
+    let width = LittleEndian::read_u16(&data[0..2]) as usize;
+    let height = LittleEndian::read_u16(&data[2..4]) as usize;
+    if width == 0 || height == 0 {
+        let message = format!("the patch is {}x{}", width, height);
+        report(Error, Check::MalformedPatch, message);
+        return problems;
+    }
+
+    let column_array_end = 8 + width * 4;
+    if data.len() < column_array_end {
+        let message = format!(
+            "the offsets of {} columns do not fit in {} bytes",
+            width,
+            data.len()
+        );
+        report(Error, Check::MalformedPatch, message);
+        return problems;
+    }
+
+    let mut bad_offsets = vec![];
+    let mut past_lump = vec![];
+    let mut past_height = vec![];
+    for (x, column) in data[8..column_array_end].chunks(4).enumerate() {
+        let mut pos = LittleEndian::read_u32(column) as usize;
+        if pos < column_array_end || pos >= data.len() {
+            bad_offsets.push(x);
+            continue;
+        }
+
+        let mut too_tall = false;
+        loop {
+            let (top, length) = match (data.get(pos), data.get(pos + 1)) {
+                (Some(255), _) => break,
+                (Some(&top), Some(&length)) => (top as usize, length as usize),
+                _ => {
+                    past_lump.push(x);
+                    break;
+                }
+            };
+            too_tall |= top + length > height;
+            pos += 4 + length;
+        }
+        if too_tall {
+            past_height.push(x);
+        }
+    }
+
+    if !bad_offsets.is_empty() {
+        let message = format!("column offsets out of bounds in {}", columns(&bad_offsets));
+        report(Error, Check::ColumnOffset, message);
+    }
+    if !past_lump.is_empty() {
+        let message = format!(
+            "posts run past the end of the lump in {}",
+            columns(&past_lump)
+        );
+        report(Error, Check::PostOverrun, message);
+    }
+    if !past_height.is_empty() {
+        let message = format!(
+            "posts run past the height of {} in {}",
+            height,
+            columns(&past_height)
+        );
+        report(Warning, Check::PostOverrun, message);
+    }
+
+    problems
+}
+
+/// Check that a flat is 64x64. The engine reads 4096 bytes regardless, so
+/// smaller flats read garbage, and larger flats are only supported by
+/// source ports.
+pub fn check_flat(name: &str, data: &[u8]) -> Option<Problem> {
+    let severity = match data.len() {
+        FLAT_SIZE => return None,
+        len if len < FLAT_SIZE => Severity::Error,
+        _ => Severity::Warning,
+    };
+    let message = format!("{} bytes instead of {}", data.len(), FLAT_SIZE);
+    Some(Problem::new(severity, Check::FlatSize, name, message))
+}
+
+/// Check the textures for duplicate names, missing patches and patch
+/// fields that are set
+pub fn check_textures(index: &ResourceIndex, textures: &TextureSet) -> Vec<Problem> {
+    use Severity::*;
+
+    let mut problems = vec![];
+    if textures.is_empty() {
+        return problems;
+    }
+    let pnames = match index.by_id(b"PNAMES") {
+        Some(pnames) => match validate_pnames(pnames) {
+            Ok(()) => parse_pnames(pnames),
+            Err(err) => {
+                let message = format!("{}, so no textures can be drawn", err);
+                let check = Check::MalformedTextureLump;
+                problems.push(Problem::new(Error, check, "PNAMES", message));
+                return problems;
+            }
+        },
+        None => {
+            let message = "missing, so no textures can be drawn".to_string();
+            problems.push(Problem::new(Error, Check::MissingPatch, "PNAMES", message));
+            return problems;
+        }
+    };
+
+    let mut definitions: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for i in 0..textures.len() {
+        let name = EntryId::from_bytes(&textures.texture(i).name())
+            .to_string()
+            .to_ascii_uppercase();
+        definitions.entry(name).or_default().push(i);
+    }
+
+    for i in 0..textures.len() {
+        let texture = textures.texture(i);
+        let name = EntryId::from_bytes(&texture.name()).to_string();
+        let mut report =
+            |severity, check, message| problems.push(Problem::new(severity, check, &name, message));
+
+        let duplicates = &definitions[&name.to_ascii_uppercase()];
+        if duplicates.len() > 1 && !textures.is_overridden(i) {
+            let lumps: Vec<String> = duplicates
+                .iter()
+                .map(|&d| EntryId::from_bytes(TEXTURE_LUMPS[textures.directory(d)]).to_string())
+                .collect();
+            let message = format!(
+                "defined {} times, in {}. Vanilla and Boom use the first, ZDoom the last",
+                duplicates.len(),
+                lumps.join(", ")
+            );
+            report(Warning, Check::DuplicateTexture, message);
+        }
+
+        if texture.len() == 0 {
+            report(Error, Check::EmptyTexture, "has no patches".to_string());
+        }
+
+        let mut missing = BTreeSet::new();
+        let mut fields_set = None;
+        for p in 0..texture.len() {
+            let patch = texture.patch(p);
+            match pnames.get(patch.patch_id as usize) {
+                None => {
+                    let message = format!(
+                        "uses patch number {}, but PNAMES has {} entries",
+                        patch.patch_id,
+                        pnames.len()
+                    );
+                    report(Error, Check::MissingPatch, message);
+                }
+                Some(pname) => {
+                    if index.find_index(pname, PATCH_NAMESPACES).is_none() {
+                        missing.insert(EntryId::from_bytes(pname).to_string());
+                    }
+                }
+            }
+            if (patch.step_dir != 1 || patch.colormap != 0) && fields_set.is_none() {
+                fields_set = Some((p, patch.step_dir, patch.colormap));
+            }
+        }
+
+        for patch_name in missing {
+            let message = format!("uses the patch {}, which has no lump", patch_name);
+            report(Error, Check::MissingPatch, message);
+        }
+        if let Some((p, step_dir, colormap)) = fields_set {
+            let message = format!(
+                "patch {} has stepdir {} and colormap {}, which the engine ignores",
+                p, step_dir, colormap
+            );
+            report(Info, Check::PatchFields, message);
+        }
+    }
+
+    problems
+}
+
+/// Check that a sprite has all frames up to the last one, and that every
+/// rotating frame has all its rotations, as R_InitSpriteDefs requires
+pub fn check_sprite(def: &SpriteDef) -> Vec<Problem> {
+    let mut problems = vec![];
+    let sprite = String::from_utf8_lossy(&def.sprite()).into_owned();
+
+    for f in 0..def.len() as u8 {
+        let subject = format!("{}{}", sprite, (b'A' + f) as char);
+        let frame = match def.frame(f) {
+            Some(frame) => frame,
+            None => {
+                let message = "no lumps for the frame, though later frames exist".to_string();
+                problems.push(Problem::new(
+                    Severity::Error,
+                    Check::MissingFrame,
+                    &subject,
+                    message,
+                ));
+                continue;
+            }
+        };
+
+        let missing: Vec<String> = frame
+            .rotations()
+            .enumerate()
+            .filter(|(_, rotation)| rotation.is_none())
+            .map(|(i, _)| frame.rotation_number(i).to_string())
+            .collect();
+        if !missing.is_empty() {
+            let message = format!("missing rotations {}", missing.join(", "));
+            problems.push(Problem::new(
+                Severity::Error,
+                Check::MissingRotation,
+                &subject,
+                message,
+            ));
+        }
+    }
+
+    problems
+}
+
+/// Check all the graphics of the WADs: the sprites, the patches in the
+/// patch namespace or PNAMES, the flats and the textures. Overridden lumps
+/// are not checked, as the engine does not use them.
+pub fn check_wad(index: &ResourceIndex) -> Vec<Problem> {
+    let mut problems = vec![];
+
+    let mut patches = BTreeSet::new();
+    patches.extend(index.indices(Namespace::Sprites));
+    patches.extend(index.indices(Namespace::Patches));
+    if let Some(pnames) = index
+        .by_id(b"PNAMES")
+        .filter(|x| validate_pnames(x).is_ok())
+    {
+        for pname in parse_pnames(pnames) {
+            patches.extend(index.find_index(pname, PATCH_NAMESPACES));
+        }
+    }
+    let flats = index.indices(Namespace::Flats);
+
+    for i in patches.into_iter().chain(flats) {
+        if index.is_overridden(i) {
+            continue;
+        }
+        let name = index.id(i).to_string();
+        let data = match index.entry(i) {
+            Ok(entry) => entry.lump,
+            Err(err) => {
+                let message = err.to_string();
+                problems.push(Problem::new(
+                    Severity::Error,
+                    Check::InvalidEntry,
+                    &name,
+                    message,
+                ));
+                continue;
+            }
+        };
+        if data.starts_with(PNG_SIGNATURE) {
+            continue;
+        }
+
+        if index.namespace(i) == Some(Namespace::Flats) {
+            problems.extend(check_flat(&name, data));
+        } else {
+            problems.extend(check_patch(&name, data));
+        }
+    }
+
+    for &lump in TEXTURE_LUMPS.iter() {
+        if let Some(Err(err)) = index.by_id(lump).map(validate_texture_directory) {
+            let name = EntryId::from_bytes(lump).to_string();
+            let message = format!("{}, so its textures are left out", err);
+            problems.push(Problem::new(
+                Severity::Error,
+                Check::MalformedTextureLump,
+                &name,
+                message,
+            ));
+        }
+    }
+    problems.extend(check_textures(index, &TextureSet::from_index(index)));
+
+    for def in sprite_defs(index).values() {
+        problems.extend(check_sprite(def));
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{pnames, test_wad, texture_dir};
+
+    const SPRITE: &[u8] = include_bytes!("trooa1.sprite");
+
+    fn checks(problems: &[Problem]) -> Vec<(Severity, Check)> {
+        problems.iter().map(|x| (x.severity, x.check)).collect()
+    }
+
+    #[test]
+    fn valid_patch() {
+        assert_eq!(check_patch("TROOA1", SPRITE), vec![]);
+    }
+
+    #[test]
+    fn malformed_patches() {
+        assert_eq!(
+            checks(&check_patch("X", &SPRITE[..4])),
+            [(Severity::Error, Check::MalformedPatch)]
+        );
+        assert_eq!(
+            checks(&check_patch("X", &SPRITE[..20])),
+            [(Severity::Error, Check::MalformedPatch)]
+        );
+
+        let truncated = check_patch("X", &SPRITE[..SPRITE.len() - 3]);
+        assert_eq!(checks(&truncated), [(Severity::Error, Check::PostOverrun)]);
+        assert!(truncated[0].message.ends_with("column 40"));
+
+        let mut bad_offset = SPRITE.to_vec();
+        bad_offset[8..12].copy_from_slice(&100000u32.to_le_bytes());
+        assert_eq!(
+            checks(&check_patch("X", &bad_offset)),
+            [(Severity::Error, Check::ColumnOffset)]
+        );
+
+        let mut short = SPRITE.to_vec();
+        short[2..4].copy_from_slice(&20u16.to_le_bytes());
+        assert_eq!(
+            checks(&check_patch("X", &short)),
+            [(Severity::Warning, Check::PostOverrun)]
+        );
+    }
+
+    #[test]
+    fn flat_size() {
+        assert_eq!(check_flat("F", &[0; FLAT_SIZE]), None);
+        assert_eq!(
+            check_flat("F", &[0; 4000]).map(|x| x.severity),
+            Some(Severity::Error)
+        );
+        assert_eq!(
+            check_flat("F", &[0; 16384]).map(|x| x.severity),
+            Some(Severity::Warning)
+        );
+    }
+
+    #[test]
+    fn malformed_texture_lumps() {
+        let textures = texture_dir(&[("WALL", 64, 128, &[(0, 0, 0)])]);
+        let names = pnames(&["WALL"]);
+        let wad = test_wad(&[
+            ("TEXTURE1", &textures[..textures.len() - 4]),
+            ("PNAMES", &names[..6]),
+        ]);
+        let problems = check_wad(&ResourceIndex::new(wad.as_slice()));
+        assert_eq!(
+            checks(&problems),
+            [(Severity::Error, Check::MalformedTextureLump)]
+        );
+        assert_eq!(problems[0].subject, "TEXTURE1");
+
+        let wad = test_wad(&[("TEXTURE1", &textures), ("PNAMES", &names[..6])]);
+        let problems = check_wad(&ResourceIndex::new(wad.as_slice()));
+        assert_eq!(
+            checks(&problems),
+            [(Severity::Error, Check::MalformedTextureLump)]
+        );
+        assert_eq!(problems[0].subject, "PNAMES");
+    }
+
+    #[test]
+    fn sprite_frames() {
+        let mut def = SpriteDef::new(*b"TROO");
+        def.install(b"TROOA0\0\0");
+        def.install(b"TROOC1\0\0");
+        def.install(b"TROOC2C8");
+        def.install(b"TROOC5\0\0");
+
+        let problems = check_sprite(&def);
+        assert_eq!(
+            checks(&problems),
+            [
+                (Severity::Error, Check::MissingFrame),
+                (Severity::Error, Check::MissingRotation)
+            ]
+        );
+        assert_eq!(problems[0].subject, "TROOB");
+        assert_eq!(problems[1].subject, "TROOC");
+        assert_eq!(problems[1].message, "missing rotations 3, 4, 6, 7");
+    }
+}
@@ -0,0 +1,667 @@
+use std::fmt;
+
+use crate::{Sequence, ThingInfo, FF_FRAMEMASK, MAX_SPRITE_FRAMES, MF_NOTDMATCH};
+
+/// A problem found while applying a DeHackEd patch. Patches are applied
+/// leniently, like the engines do, so problems do not stop the rest of the
+/// patch from being applied.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DehackedWarning {
+    /// 1-based line number in the patch
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DehackedWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Block {
+    None,
+    Thing(usize),
+    Frame(usize),
+    Pointer(usize),
+    Sprite(usize),
+    Ignored,
+    BexSprites,
+    BexCodePointers,
+}
+
+/// The Thing block fields that name the first state of a sequence
+fn thing_sequence_field(key: &str) -> Option<Sequence> {
+    match key {
+        "initial frame" => Some(Sequence::Spawn),
+        "first moving frame" => Some(Sequence::See),
+        "injury frame" => Some(Sequence::Pain),
+        "close attack frame" => Some(Sequence::Melee),
+        "far attack frame" => Some(Sequence::Missile),
+        "death frame" => Some(Sequence::Death),
+        "exploding frame" => Some(Sequence::XDeath),
+        "respawn frame" => Some(Sequence::Raise),
+        _ => None,
+    }
+}
+
+/// Thing fields that do not affect graphics
+const IGNORED_THING_FIELDS: &[&str] = &[
+    "hit points",
+    "reaction time",
+    "pain chance",
+    "speed",
+    "width",
+    "height",
+    "mass",
+    "missile damage",
+    "alert sound",
+    "attack sound",
+    "pain sound",
+    "death sound",
+    "action sound",
+];
+
+/// The offset that Sprite blocks give for the name of the first sprite, for
+/// the executable of each `Doom version` of the preamble. The names follow
+/// 8 bytes apart, in the order of the sprite numbers. The offsets are the
+/// ones DeHackEd writes, as used by the engines that read Sprite blocks.
+fn sprite_names_offset(doom_version: i64) -> Option<i64> {
+    match doom_version {
+        16 | 17 | 20 => Some(129044 + 22044),
+        19 => Some(129284 + 22044),
+        21 => Some(129380 + 22044),
+        _ => None,
+    }
+}
+
+/// Doom 1.9, which patches without a known version are assumed to be for
+const DEFAULT_DOOM_VERSION: i64 = 19;
+
+fn parse_sprite_name(name: &str) -> Option<[u8; 4]> {
+    let name = name.trim().to_ascii_uppercase();
+    if name.len() != 4 || !name.is_ascii() {
+        return None;
+    }
+    let mut sprite = [0; 4];
+    sprite.copy_from_slice(name.as_bytes());
+    Some(sprite)
+}
+
+/// Parse a block header like `Frame 442`, `Text 4 6` or
+/// `Pointer 50 (Frame 100)`, giving the lowercase keyword and the numbers.
+/// A trailing number in parentheses is included, while other text in
+/// parentheses, like the names in `Thing 12 (Imp)`, is not.
+fn parse_header(line: &str) -> Option<(String, Vec<i64>)> {
+    let (head, comment) = match line.find('(') {
+        Some(start) => (&line[..start], Some(&line[start + 1..])),
+        None => (line, None),
+    };
+
+    let mut words = head.split_whitespace();
+    let keyword = words.next()?.to_ascii_lowercase();
+    let mut numbers = words
+        .map(|x| x.parse().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    if numbers.is_empty() {
+        return None;
+    }
+
+    let trailing = comment
+        .and_then(|x| x.trim_end_matches(')').split_whitespace().last())
+        .and_then(|x| x.parse::<i64>().ok());
+    numbers.extend(trailing);
+
+    Some((keyword, numbers))
+}
+
+struct Patcher<'a> {
+    info: &'a mut ThingInfo,
+    original_actions: Vec<Option<&'static str>>,
+    original_sprite_names: Vec<[u8; 4]>,
+    doom_version: i64,
+    warnings: Vec<DehackedWarning>,
+    line: usize,
+}
+
+impl<'a> Patcher<'a> {
+    fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(DehackedWarning {
+            line: self.line,
+            message: message.into(),
+        });
+    }
+
+    fn state_index(&mut self, state: i64) -> Option<usize> {
+        if state >= 0 && (state as usize) < self.info.states.len() {
+            Some(state as usize)
+        } else {
+            self.warn(format!("Frame {} is out of range", state));
+            None
+        }
+    }
+
+    fn begin_block(&mut self, line: &str) -> Option<Block> {
+        let (keyword, numbers) = parse_header(line)?;
+        let block = match keyword.as_str() {
+            "thing" => {
+                let thing = numbers[0];
+                if thing >= 1 && (thing as usize) <= self.info.mobjinfo.len() {
+                    Block::Thing(thing as usize - 1)
+                } else {
+                    self.warn(format!("Thing {} is out of range", thing));
+                    Block::Ignored
+                }
+            }
+            "frame" => match self.state_index(numbers[0]) {
+                Some(state) => Block::Frame(state),
+                None => Block::Ignored,
+            },
+            "pointer" => {
+                // Pointer blocks are numbered by code pointer, but also
+                // name the frame they apply to in parentheses
+                match numbers.get(1) {
+                    Some(&frame) => match self.state_index(frame) {
+                        Some(state) => Block::Pointer(state),
+                        None => Block::Ignored,
+                    },
+                    None => {
+                        self.warn("Pointer block without a frame number");
+                        Block::Ignored
+                    }
+                }
+            }
+            "sprite" => {
+                let sprite = numbers[0];
+                if sprite >= 0 && (sprite as usize) < self.info.sprite_names.len() {
+                    Block::Sprite(sprite as usize)
+                } else {
+                    self.warn(format!("Sprite {} is out of range", sprite));
+                    Block::Ignored
+                }
+            }
+            "ammo" | "weapon" | "sound" | "cheat" | "misc" | "par" => Block::Ignored,
+            _ => return None,
+        };
+        Some(block)
+    }
+
+    fn set_thing_field(&mut self, thing: usize, key: &str, value: i64) {
+        if key == "id #" {
+            self.info.mobjinfo[thing].doomednum = value as i32;
+        } else if key == "bits" {
+            self.info.mobjinfo[thing].flags = value as u32 & MF_NOTDMATCH;
+        } else if let Some(sequence) = thing_sequence_field(key) {
+            if let Some(state) = self.state_index(value) {
+                self.info.mobjinfo[thing].states[sequence as usize] = state;
+            }
+        } else if !IGNORED_THING_FIELDS.contains(&key) {
+            self.warn(format!("Unknown Thing field {:?}", key));
+        }
+    }
+
+    /// Set the flags of a thing from names like SOLID+SHOOTABLE, of which
+    /// only NOTDMATCH is kept
+    fn set_thing_flag_names(&mut self, thing: usize, names: &str) {
+        let not_deathmatch = names
+            .split(|c: char| c == '+' || c == '|' || c == ',' || c.is_whitespace())
+            .any(|name| name.eq_ignore_ascii_case("NOTDMATCH"));
+        self.info.mobjinfo[thing].flags = if not_deathmatch { MF_NOTDMATCH } else { 0 };
+    }
+
+    fn set_frame_field(&mut self, state: usize, key: &str, value: i64) {
+        match key {
+            "sprite number" => {
+                if value >= 0 && (value as usize) < self.info.sprite_names.len() {
+                    self.info.states[state].sprite = value as usize;
+                } else {
+                    self.warn(format!("Sprite number {} is out of range", value));
+                }
+            }
+            "sprite subnumber" => {
+                // Frame letters go from A to ], the last one R_InstallSpriteLump
+                // accepts
+                let frame = value as u32 & FF_FRAMEMASK;
+                if value >= 0 && value <= u32::MAX as i64 && (frame as usize) < MAX_SPRITE_FRAMES {
+                    self.info.states[state].frame = value as u32;
+                } else {
+                    self.warn(format!("Sprite subnumber {} is out of range", value));
+                }
+            }
+            "duration" => self.info.states[state].tics = value as i32,
+            "next frame" => {
+                if let Some(next) = self.state_index(value) {
+                    self.info.states[state].next = next;
+                }
+            }
+            "unknown 1" | "unknown 2" => (),
+            _ => self.warn(format!("Unknown Frame field {:?}", key)),
+        }
+    }
+
+    fn set_code_pointer(&mut self, state: usize, key: &str, value: i64) {
+        if key != "codep frame" {
+            self.warn(format!("Unknown Pointer field {:?}", key));
+            return;
+        }
+        if let Some(source) = self.state_index(value) {
+            self.info.states[state].action = self.original_actions[source];
+        }
+    }
+
+    fn set_doom_version(&mut self, value: i64) {
+        if sprite_names_offset(value).is_some() {
+            self.doom_version = value;
+        } else {
+            self.warn(format!("Unknown Doom version {}, assuming 1.9", value));
+            self.doom_version = DEFAULT_DOOM_VERSION;
+        }
+    }
+
+    /// Point a sprite at the name of another, given by its offset in the
+    /// data of the executable
+    fn set_sprite_field(&mut self, sprite: usize, key: &str, value: i64) {
+        if key != "offset" {
+            self.warn(format!("Unknown Sprite field {:?}", key));
+            return;
+        }
+        let start = sprite_names_offset(self.doom_version).unwrap();
+        let name = Some(value - start)
+            .filter(|&x| x >= 0 && x % 8 == 0)
+            .and_then(|x| self.original_sprite_names.get(x as usize / 8).copied());
+        match name {
+            Some(name) => self.info.sprite_names[sprite] = name,
+            None => self.warn(format!("Offset {} is not a sprite name", value)),
+        }
+    }
+
+    fn rename_sprite(&mut self, old: &str, new: &str) {
+        let index = match old.trim().parse::<usize>() {
+            Ok(index) => Some(index).filter(|&x| x < self.info.sprite_names.len()),
+            Err(_) => parse_sprite_name(old)
+                .and_then(|old| self.info.sprite_names.iter().position(|&x| x == old)),
+        };
+        match (index, parse_sprite_name(new)) {
+            (Some(index), Some(new)) => self.info.sprite_names[index] = new,
+            (None, _) => self.warn(format!("Unknown sprite {:?}", old.trim())),
+            (_, None) => self.warn(format!("Invalid sprite name {:?}", new.trim())),
+        }
+    }
+
+    fn bex_code_pointer(&mut self, key: &str, value: &str) {
+        let state = match key.strip_prefix("frame ").map(|x| x.trim().parse::<i64>()) {
+            Some(Ok(state)) => state,
+            _ => {
+                self.warn(format!("Expected FRAME <number>, found {:?}", key));
+                return;
+            }
+        };
+        let state = match self.state_index(state) {
+            Some(state) => state,
+            None => return,
+        };
+
+        if value.eq_ignore_ascii_case("null") {
+            self.info.states[state].action = None;
+            return;
+        }
+
+        let name = format!("A_{}", value.trim_start_matches("A_"));
+        let action = self
+            .original_actions
+            .iter()
+            .flatten()
+            .find(|x| x.eq_ignore_ascii_case(&name));
+        match action {
+            Some(&action) => self.info.states[state].action = Some(action),
+            None => self.warn(format!("Unknown code pointer {:?}", value)),
+        }
+    }
+
+    fn text_replacement(&mut self, old: &str, new: &str) {
+        // Of the strings in the executable, only sprite names are relevant
+        // for graphics. Engines only replace sprite names with names of
+        // the same length.
+        if old.len() != 4 || new.len() != 4 {
+            return;
+        }
+        if let (Some(old), Some(new)) = (parse_sprite_name(old), parse_sprite_name(new)) {
+            if let Some(index) = self.info.sprite_names.iter().position(|&x| x == old) {
+                self.info.sprite_names[index] = new;
+            }
+        }
+    }
+}
+
+/// Split off the first `count` characters of `text`, not counting carriage
+/// returns, as Text blocks count characters that way
+fn take_chars(text: &str, count: usize) -> Option<(String, &str)> {
+    let mut taken = String::new();
+    let mut chars = text.char_indices();
+    let mut taken_count = 0;
+    while taken_count < count {
+        let (_, c) = chars.next()?;
+        if c != '\r' {
+            taken.push(c);
+            taken_count += 1;
+        }
+    }
+    let rest = chars.next().map(|(i, _)| &text[i..]).unwrap_or("");
+    Some((taken, rest))
+}
+
+impl ThingInfo {
+    /// Apply a DeHackEd patch, as found in .deh files and DEHACKED lumps.
+    /// Thing, Frame, Pointer, Sprite and Text blocks are supported, along
+    /// with the [SPRITES] and [CODEPTR] sections of Boom's BEX format. The
+    /// offsets of Sprite blocks are looked up in the sprite name table of
+    /// the executable the preamble gives the `Doom version` of. Of the thing
+    /// bits, only NOTDMATCH is kept. Other changes do not affect graphics
+    /// and are skipped.
+    pub fn apply_dehacked(&mut self, patch: &[u8]) -> Vec<DehackedWarning> {
+        let patch = String::from_utf8_lossy(patch);
+
+        let mut patcher = Patcher {
+            original_actions: self.states.iter().map(|x| x.action).collect(),
+            original_sprite_names: self.sprite_names.clone(),
+            doom_version: DEFAULT_DOOM_VERSION,
+            info: self,
+            warnings: vec![],
+            line: 0,
+        };
+
+        // Line numbers are counted from the start of the patch rather than
+        // by the lines read, as a Text block can end in the middle of a line
+        let (mut line_number, mut counted) = (1, 0);
+        let mut line_at = |rest: &str| {
+            let offset = patch.len() - rest.len();
+            line_number += patch[counted..offset].matches('\n').count();
+            counted = offset;
+            line_number
+        };
+
+        let mut block = Block::None;
+        let mut rest: &str = &patch;
+        while !rest.is_empty() {
+            patcher.line = line_at(rest);
+            let (line, tail) = match rest.find('\n') {
+                Some(end) => (&rest[..end], &rest[end + 1..]),
+                None => (rest, ""),
+            };
+            rest = tail;
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                block = match line[1..line.len() - 1].trim().to_ascii_uppercase().as_str() {
+                    "SPRITES" => Block::BexSprites,
+                    "CODEPTR" => Block::BexCodePointers,
+                    _ => Block::Ignored,
+                };
+                continue;
+            }
+
+            if let Some((keyword, numbers)) = parse_header(line) {
+                if keyword == "text" {
+                    let old_len = numbers[0].max(0) as usize;
+                    let new_len = numbers.get(1).copied().unwrap_or(0).max(0) as usize;
+                    match take_chars(rest, old_len + new_len) {
+                        Some((text, tail)) => {
+                            let split = text
+                                .char_indices()
+                                .nth(old_len)
+                                .map(|(i, _)| i)
+                                .unwrap_or(text.len());
+                            let (old, new) = text.split_at(split);
+                            patcher.text_replacement(old, new);
+                            rest = tail;
+                        }
+                        None => {
+                            patcher.warn("Text block runs past the end of the patch");
+                            rest = "";
+                        }
+                    }
+                    block = Block::None;
+                    continue;
+                }
+            }
+
+            if !line.contains('=') {
+                if let Some(new_block) = patcher.begin_block(line) {
+                    block = new_block;
+                } else if !line
+                    .to_ascii_lowercase()
+                    .starts_with("patch file for dehacked")
+                {
+                    patcher.warn(format!("Unexpected line {:?}", line));
+                }
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap().trim().to_ascii_lowercase();
+            let value = parts.next().unwrap().trim();
+
+            match block {
+                Block::BexSprites => patcher.rename_sprite(&key, value),
+                Block::BexCodePointers => patcher.bex_code_pointer(&key, value),
+                Block::Ignored => (),
+                Block::None => {
+                    // The preamble has the version of the executable, which
+                    // gives the meaning of Sprite offsets, and the version
+                    // of the patch format, which changes nothing here
+                    if key == "doom version" {
+                        match value.parse::<i64>() {
+                            Ok(version) => patcher.set_doom_version(version),
+                            Err(_) => patcher.warn(format!("Expected a number, found {:?}", value)),
+                        }
+                    }
+                }
+                Block::Thing(_) | Block::Frame(_) | Block::Pointer(_) | Block::Sprite(_) => {
+                    let value = match value.parse::<i64>() {
+                        Ok(value) => value,
+                        Err(_) => {
+                            // Bits may be given as flag names, like in BEX
+                            match block {
+                                Block::Thing(thing) if key == "bits" => {
+                                    patcher.set_thing_flag_names(thing, value)
+                                }
+                                _ => patcher.warn(format!("Expected a number, found {:?}", value)),
+                            }
+                            continue;
+                        }
+                    };
+                    match block {
+                        Block::Thing(thing) => patcher.set_thing_field(thing, &key, value),
+                        Block::Frame(state) => patcher.set_frame_field(state, &key, value),
+                        Block::Pointer(state) => patcher.set_code_pointer(state, &key, value),
+                        Block::Sprite(sprite) => patcher.set_sprite_field(sprite, &key, value),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+
+        patcher.warnings
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const S_TROO_STND: usize = 442;
+    const MT_TROOP: usize = 11;
+
+    #[test]
+    fn frame_and_thing_blocks() {
+        let mut info = ThingInfo::doom();
+        let warnings = info.apply_dehacked(
+            b"Patch File for DeHackEd v3.0\n\
+              # A comment\n\
+              Doom version = 21\n\
+              Patch format = 6\n\
+              \n\
+              Thing 12 (Imp)\n\
+              ID # = 3100\n\
+              Hit points = 200\n\
+              Bits = SOLID+SHOOTABLE\n\
+              Initial frame = 100\n\
+              \n\
+              Frame 442\r\n\
+              Sprite number = 1\r\n\
+              Sprite subnumber = 32770\r\n\
+              Duration = 5\r\n\
+              Next frame = 0\r\n",
+        );
+        assert_eq!(warnings, vec![]);
+
+        assert_eq!(info.mobjinfo[MT_TROOP].doomednum, 3100);
+        assert_eq!(info.mobjinfo[MT_TROOP].state(Sequence::Spawn), 100);
+
+        let state = &info.states[S_TROO_STND];
+        assert_eq!(state.sprite, 1);
+        assert_eq!(state.frame_number(), 2);
+        assert!(state.is_fullbright());
+        assert_eq!(state.tics, 5);
+        assert_eq!(state.next, 0);
+    }
+
+    #[test]
+    fn deathmatch_bits() {
+        let mut info = ThingInfo::doom();
+        let key = info.by_name("MISC4").unwrap();
+        assert_eq!(info.mobjinfo[key].flags, MF_NOTDMATCH);
+
+        let patch = format!(
+            "Thing {}\nBits = 4\n\nThing 12\nBits = SOLID+NOTDMATCH\n",
+            key + 1
+        );
+        assert_eq!(info.apply_dehacked(patch.as_bytes()), vec![]);
+        assert_eq!(info.mobjinfo[key].flags, 0);
+        assert_eq!(info.mobjinfo[MT_TROOP].flags, MF_NOTDMATCH);
+    }
+
+    #[test]
+    fn frame_letter_range() {
+        let mut info = ThingInfo::doom();
+        let warnings = info.apply_dehacked(
+            b"Frame 442
+              Sprite subnumber = 28
+              Frame 443
+              Sprite subnumber = 32797
+",
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 4);
+        assert_eq!(info.states[S_TROO_STND].frame_number(), 28);
+        assert_eq!(info.states[S_TROO_STND + 1].frame_number(), 1);
+    }
+
+    #[test]
+    fn code_pointers() {
+        let mut info = ThingInfo::doom();
+        let warnings = info.apply_dehacked(
+            b"Pointer 0 (Frame 1)\n\
+              Codep Frame = 442\n\
+              \n\
+              [CODEPTR]\n\
+              FRAME 2 = Chase\n\
+              FRAME 3 = NULL\n\
+              FRAME 4 = NoSuchPointer\n",
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 7);
+
+        assert_eq!(info.states[1].action, Some("A_Look"));
+        assert_eq!(info.states[2].action, Some("A_Chase"));
+        assert_eq!(info.states[3].action, None);
+        assert_eq!(info.states[4].action, Some("A_Raise"));
+    }
+
+    #[test]
+    fn sprite_renames() {
+        let mut info = ThingInfo::doom();
+        let warnings = info.apply_dehacked(
+            b"Text 4 4\n\
+              TROOIMPXThing 1\n\
+              ID # = 7777\n\
+              \n\
+              [SPRITES]\n\
+              POSS = ZOMB\n",
+        );
+        assert_eq!(warnings, vec![]);
+        assert_eq!(&info.sprite_names[0], b"IMPX");
+        assert_eq!(&info.sprite_names[29], b"ZOMB");
+        assert_eq!(info.mobjinfo[0].doomednum, 7777);
+    }
+
+    #[test]
+    fn sprite_blocks() {
+        let mut info = ThingInfo::doom();
+        let warnings = info.apply_dehacked(
+            b"Patch File for DeHackEd v3.0\n\
+              Doom version = 21\n\
+              Patch format = 6\n\
+              \n\
+              Sprite 1\n\
+              Offset = 151424\n\
+              \n\
+              Sprite 0\n\
+              Offset = 151440\n\
+              \n\
+              Sprite 2\n\
+              Offset = 151432\n\
+              \n\
+              Sprite 3\n\
+              Offset = 151428\n",
+        );
+        assert_eq!(&info.sprite_names[0], b"PUNG");
+        assert_eq!(&info.sprite_names[1], b"TROO");
+        // Offsets name the sprites of the executable, not the renamed ones
+        assert_eq!(&info.sprite_names[2], b"SHTG");
+        let lines: Vec<_> = warnings.iter().map(|x| x.line).collect();
+        assert_eq!(lines, vec![15]);
+
+        let mut info = ThingInfo::doom();
+        let warnings = info.apply_dehacked(b"Sprite 1\nOffset = 151328\n");
+        assert_eq!(warnings, vec![]);
+        assert_eq!(&info.sprite_names[1], b"TROO");
+    }
+
+    #[test]
+    fn lines_after_text() {
+        let mut info = ThingInfo::doom();
+        // The replacement ends on the line of the next header
+        let warnings = info.apply_dehacked(
+            b"# Comment\r\n\
+              Text 4 4\r\n\
+              TROOIMPXThing 1\r\n\
+              Bogus = 1\r\n\
+              Sprite 3\r\n\
+              Offset = 1\r\n",
+        );
+        let lines: Vec<_> = warnings.iter().map(|x| x.line).collect();
+        assert_eq!(lines, vec![4, 6]);
+        assert_eq!(&info.sprite_names[0], b"IMPX");
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut info = ThingInfo::doom();
+        let original = info.clone();
+        let warnings = info.apply_dehacked(
+            b"Frame 5000\n\
+              Duration = 1\n\
+              Thing 1000\n\
+              Frame 10\n\
+              Sprite number = 500\n",
+        );
+        let lines: Vec<_> = warnings.iter().map(|x| x.line).collect();
+        assert_eq!(lines, vec![1, 3, 5]);
+        assert_eq!(info, original);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{validate_patch, Namespace};
+
+/// The signature at the start of every PNG file
+pub const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// The size of a flat, 64x64 pixels
+pub const FLAT_SIZE: usize = 64 * 64;
+
+/// The dimensions of a raw full screen graphic, as (height, width)
+pub const SCREEN_DIM: (usize, usize) = (200, 320);
+
+/// The formats a graphic lump may be stored in
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GraphicFormat {
+    /// The column-based format of sprites, patches and other graphics
+    Patch,
+    /// 64x64 raw pixels
+    Flat,
+    /// 320x200 raw pixels, as used for some full screen graphics in
+    /// Heretic and Hexen
+    RawScreen,
+    /// A PNG file, as supported by source ports
+    Png,
+}
+
+impl GraphicFormat {
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphicFormat::Patch => "patch",
+            GraphicFormat::Flat => "flat",
+            GraphicFormat::RawScreen => "raw screen",
+            GraphicFormat::Png => "PNG",
+        }
+    }
+}
+
+/// Work out which format a lump is in, from its contents and the namespace
+/// it was found in. Lumps in the flat namespace can only be flats, while
+/// elsewhere a lump that is a valid patch is taken to be one, before
+/// falling back on the size of the raw formats. Returns None for lumps
+/// that are not graphics.
+pub fn detect_graphic(data: &[u8], namespace: Option<Namespace>) -> Option<GraphicFormat> {
+    if data.starts_with(PNG_SIGNATURE) {
+        return Some(GraphicFormat::Png);
+    }
+
+    match namespace {
+        None => None,
+        Some(Namespace::Flats) => Some(GraphicFormat::Flat).filter(|_| data.len() == FLAT_SIZE),
+        Some(_) => {
+            if validate_patch(data).is_ok() {
+                Some(GraphicFormat::Patch)
+            } else if data.len() == FLAT_SIZE {
+                Some(GraphicFormat::Flat)
+            } else if data.len() == SCREEN_DIM.0 * SCREEN_DIM.1 {
+                Some(GraphicFormat::RawScreen)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SPRITE: &[u8] = include_bytes!("trooa1.sprite");
+
+    #[test]
+    fn detect_patch() {
+        assert_eq!(
+            detect_graphic(SPRITE, Some(Namespace::Sprites)),
+            Some(GraphicFormat::Patch)
+        );
+        assert_eq!(
+            detect_graphic(SPRITE, Some(Namespace::Global)),
+            Some(GraphicFormat::Patch)
+        );
+        assert_eq!(detect_graphic(SPRITE, Some(Namespace::Flats)), None);
+    }
+
+    #[test]
+    fn detect_raw() {
+        let flat = [0; FLAT_SIZE];
+        assert_eq!(
+            detect_graphic(&flat, Some(Namespace::Flats)),
+            Some(GraphicFormat::Flat)
+        );
+        assert_eq!(
+            detect_graphic(&flat, Some(Namespace::Global)),
+            Some(GraphicFormat::Flat)
+        );
+
+        let screen = vec![0; 64000];
+        assert_eq!(
+            detect_graphic(&screen, Some(Namespace::Global)),
+            Some(GraphicFormat::RawScreen)
+        );
+    }
+
+    #[test]
+    fn detect_other() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(&[0; 100]);
+        assert_eq!(
+            detect_graphic(&png, Some(Namespace::Sprites)),
+            Some(GraphicFormat::Png)
+        );
+        assert_eq!(detect_graphic(b"", Some(Namespace::Global)), None);
+        assert_eq!(detect_graphic(&[1; 1000], Some(Namespace::Global)), None);
+        assert_eq!(detect_graphic(&[0; FLAT_SIZE], None), None);
+    }
+}
@@ -6,6 +6,7 @@ pub struct Flat<'a> {
 }
 
 impl<'a> Flat<'a> {
+    #[allow(mismatched_lifetime_syntaxes)]
     pub fn new(pixels: &[u8]) -> Result<Flat, ShapeError> {
         Ok(Flat {
             pixels: ArrayView2::from_shape((64, 64), pixels)?,
@@ -0,0 +1,341 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+mod doom;
+
+/// Frame flag for states that are drawn at full brightness, as in info.h
+pub const FF_FULLBRIGHT: u32 = 0x8000;
+
+/// Mask for the frame number of a state frame, as in info.h
+pub const FF_FRAMEMASK: u32 = 0x7fff;
+
+/// Thing type flag for things that are not spawned in deathmatch, as in
+/// p_mobj.h
+pub const MF_NOTDMATCH: u32 = 0x2000000;
+
+/// The state every sequence ends in when the thing is removed
+pub const S_NULL: usize = 0;
+
+type StateDef = (&'static [u8; 4], u32, i32, &'static str, usize);
+type MobjInfoDef = (&'static str, i32, [usize; 8]);
+
+/// One entry of the state table. The thing stays in the state for `tics`
+/// game tics, or forever if it is -1, before moving on to `next`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct State {
+    /// Index into the sprite name table
+    pub sprite: usize,
+    /// Frame number, where 0 is frame A, possibly combined with
+    /// FF_FULLBRIGHT. DeHackEd patches are only allowed to set frame numbers
+    /// below `MAX_SPRITE_FRAMES`, so the frame fits a frame letter.
+    pub frame: u32,
+    pub tics: i32,
+    /// The code pointer, eg A_Look
+    pub action: Option<&'static str>,
+    pub next: usize,
+}
+
+impl State {
+    pub fn frame_number(&self) -> u32 {
+        self.frame & FF_FRAMEMASK
+    }
+
+    pub fn is_fullbright(&self) -> bool {
+        self.frame & FF_FULLBRIGHT != 0
+    }
+}
+
+/// The state sequences a thing type enters in response to what happens
+/// to it, corresponding to the state fields of mobjinfo_t
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Sequence {
+    Spawn,
+    See,
+    Pain,
+    Melee,
+    Missile,
+    Death,
+    XDeath,
+    Raise,
+}
+
+impl Sequence {
+    pub const ALL: [Sequence; 8] = [
+        Sequence::Spawn,
+        Sequence::See,
+        Sequence::Pain,
+        Sequence::Melee,
+        Sequence::Missile,
+        Sequence::Death,
+        Sequence::XDeath,
+        Sequence::Raise,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sequence::Spawn => "spawn",
+            Sequence::See => "see",
+            Sequence::Pain => "pain",
+            Sequence::Melee => "melee",
+            Sequence::Missile => "missile",
+            Sequence::Death => "death",
+            Sequence::XDeath => "xdeath",
+            Sequence::Raise => "raise",
+        }
+    }
+}
+
+impl FromStr for Sequence {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Sequence, &'static str> {
+        match s {
+            "spawn" => Ok(Sequence::Spawn),
+            "see" => Ok(Sequence::See),
+            "pain" => Ok(Sequence::Pain),
+            "melee" => Ok(Sequence::Melee),
+            "missile" => Ok(Sequence::Missile),
+            "attack" => Ok(Sequence::Missile),
+            "death" => Ok(Sequence::Death),
+            "xdeath" => Ok(Sequence::XDeath),
+            "raise" => Ok(Sequence::Raise),
+            _ => Err(
+                "sequence must be one of spawn, see, pain, melee, missile/attack, \
+                 death, xdeath or raise",
+            ),
+        }
+    }
+}
+
+/// One entry of the thing type table
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MobjInfo {
+    /// The name of the thing type in the source code, eg MT_TROOP
+    pub name: &'static str,
+    /// The thing number used in maps, or -1 for things that can only be
+    /// spawned by the game
+    pub doomednum: i32,
+    /// The first state of each sequence, in the order of Sequence::ALL
+    pub states: [usize; 8],
+    /// The MF_ flags that affect graphics. Only MF_NOTDMATCH is kept, which
+    /// the keys have.
+    pub flags: u32,
+}
+
+impl MobjInfo {
+    pub fn state(&self, sequence: Sequence) -> usize {
+        self.states[sequence as usize]
+    }
+}
+
+/// One step of a state sequence, resolved to the sprite frame to draw
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SequenceFrame {
+    pub state: usize,
+    pub sprite: [u8; 4],
+    /// 0-based frame number, where 0 is frame A
+    pub frame: u8,
+    /// Duration in tics, or -1 for a frame that lasts forever
+    pub tics: i32,
+    pub fullbright: bool,
+}
+
+/// The states visited by a thing, in order
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StateSequence {
+    pub frames: Vec<SequenceFrame>,
+    /// If the sequence repeats, the index in `frames` it repeats from
+    pub loop_start: Option<usize>,
+}
+
+/// The sprite, state and thing type tables that drive the thing animations
+/// of the game, as in info.c
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ThingInfo {
+    pub sprite_names: Vec<[u8; 4]>,
+    pub states: Vec<State>,
+    pub mobjinfo: Vec<MobjInfo>,
+}
+
+impl ThingInfo {
+    /// The tables of Doom II v1.9, which is a superset of those of Doom
+    pub fn doom() -> ThingInfo {
+        let sprite_names: Vec<[u8; 4]> = doom::SPRITE_NAMES.iter().map(|&&x| x).collect();
+
+        let states = doom::STATES
+            .iter()
+            .map(|&(sprite, frame, tics, action, next)| State {
+                sprite: sprite_names.iter().position(|x| x == sprite).unwrap(),
+                frame,
+                tics,
+                action: Some(action).filter(|x| !x.is_empty()),
+                next,
+            })
+            .collect();
+
+        let mobjinfo = doom::MOBJINFO
+            .iter()
+            .map(|&(name, doomednum, states)| MobjInfo {
+                name,
+                doomednum,
+                states,
+                flags: if doom::NOT_DEATHMATCH.contains(&name) {
+                    MF_NOTDMATCH
+                } else {
+                    0
+                },
+            })
+            .collect();
+
+        ThingInfo {
+            sprite_names,
+            states,
+            mobjinfo,
+        }
+    }
+
+    /// Find a thing type by its map thing number
+    pub fn by_doomednum(&self, doomednum: i32) -> Option<usize> {
+        self.mobjinfo.iter().position(|x| x.doomednum == doomednum)
+    }
+
+    /// Find a thing type by its source code name, eg MT_TROOP. The MT_
+    /// prefix is optional and the name is case insensitive.
+    pub fn by_name(&self, name: &str) -> Option<usize> {
+        let name = name.to_ascii_uppercase();
+        let name = name.trim_start_matches("MT_");
+        self.mobjinfo
+            .iter()
+            .position(|x| x.name.trim_start_matches("MT_") == name)
+    }
+
+    fn frame(&self, state: usize) -> SequenceFrame {
+        let s = &self.states[state];
+        SequenceFrame {
+            state,
+            sprite: self.sprite_names[s.sprite],
+            frame: s.frame_number() as u8,
+            tics: s.tics,
+            fullbright: s.is_fullbright(),
+        }
+    }
+
+    fn walk(&self, start: usize, stop_at: &BTreeSet<usize>) -> StateSequence {
+        let mut frames: Vec<SequenceFrame> = vec![];
+        let mut state = start;
+        loop {
+            if state == S_NULL || (state != start && stop_at.contains(&state)) {
+                return StateSequence {
+                    frames,
+                    loop_start: None,
+                };
+            }
+            if let Some(index) = frames.iter().position(|x| x.state == state) {
+                return StateSequence {
+                    frames,
+                    loop_start: Some(index),
+                };
+            }
+
+            frames.push(self.frame(state));
+            if self.states[state].tics == -1 {
+                return StateSequence {
+                    frames,
+                    loop_start: None,
+                };
+            }
+            state = self.states[state].next;
+        }
+    }
+
+    /// Follow the states of a sequence of the given thing type until the
+    /// sequence ends, loops or, for sequences other than spawn and see,
+    /// continues into the spawn or see sequence. Like in the game, states
+    /// with a duration of 0 tics are included but are never seen.
+    pub fn sequence(&self, thing: usize, sequence: Sequence) -> StateSequence {
+        let info = &self.mobjinfo[thing];
+        let start = info.state(sequence);
+
+        let mut stop_at = BTreeSet::new();
+        if sequence != Sequence::Spawn && sequence != Sequence::See {
+            for &base in &[Sequence::Spawn, Sequence::See] {
+                let base = self.walk(info.state(base), &BTreeSet::new());
+                stop_at.extend(base.frames.iter().map(|x| x.state));
+            }
+        }
+
+        self.walk(start, &stop_at)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table_sizes() {
+        let info = ThingInfo::doom();
+        assert_eq!(info.sprite_names.len(), 138);
+        assert_eq!(info.states.len(), 967);
+        assert_eq!(info.mobjinfo.len(), 137);
+        assert!(info.states.iter().all(|x| x.next < info.states.len()));
+    }
+
+    #[test]
+    fn imp_spawn_loops() {
+        let info = ThingInfo::doom();
+        let imp = info.by_doomednum(3001).unwrap();
+        assert_eq!(info.by_name("mt_troop"), Some(imp));
+        assert_eq!(info.by_name("TROOP"), Some(imp));
+
+        let spawn = info.sequence(imp, Sequence::Spawn);
+        let frames: Vec<_> = spawn.frames.iter().map(|x| (x.frame, x.tics)).collect();
+        assert_eq!(frames, vec![(0, 10), (1, 10)]);
+        assert_eq!(spawn.loop_start, Some(0));
+        assert!(spawn.frames.iter().all(|x| &x.sprite == b"TROO"));
+    }
+
+    #[test]
+    fn imp_death_ends() {
+        let info = ThingInfo::doom();
+        let imp = info.by_doomednum(3001).unwrap();
+        let death = info.sequence(imp, Sequence::Death);
+        let frames: Vec<_> = death.frames.iter().map(|x| (x.frame, x.tics)).collect();
+        assert_eq!(frames, vec![(8, 8), (9, 8), (10, 6), (11, 6), (12, -1)]);
+        assert_eq!(death.loop_start, None);
+    }
+
+    #[test]
+    fn attack_stops_at_see_sequence() {
+        let info = ThingInfo::doom();
+        let imp = info.by_doomednum(3001).unwrap();
+        let attack = info.sequence(imp, Sequence::Missile);
+        let frames: Vec<_> = attack.frames.iter().map(|x| x.frame).collect();
+        assert_eq!(frames, vec![4, 5, 6]);
+        assert_eq!(attack.loop_start, None);
+    }
+
+    #[test]
+    fn chaingunner_attack_refires() {
+        let info = ThingInfo::doom();
+        let chaingunner = info.by_doomednum(65).unwrap();
+        let attack = info.sequence(chaingunner, Sequence::Missile);
+        let frames: Vec<_> = attack
+            .frames
+            .iter()
+            .map(|x| (x.frame, x.fullbright))
+            .collect();
+        assert_eq!(frames, vec![(4, false), (5, true), (4, true), (5, false)]);
+        assert_eq!(attack.loop_start, Some(1));
+    }
+
+    #[test]
+    fn empty_sequence() {
+        let info = ThingInfo::doom();
+        let teleport_target = info.by_doomednum(14).unwrap();
+        assert!(info
+            .sequence(teleport_target, Sequence::Spawn)
+            .frames
+            .is_empty());
+    }
+}
@@ -0,0 +1,1147 @@
+//! The thing tables of Doom II v1.9, transcribed from info.c. The order
+//! of the tables matters, as DeHackEd patches refer to entries by index.
+
+use super::{MobjInfoDef, StateDef};
+
+#[rustfmt::skip]
+pub static SPRITE_NAMES: [&[u8; 4]; 138] = [
+    b"TROO", b"SHTG", b"PUNG", b"PISG", b"PISF", b"SHTF", b"SHT2", b"CHGG",
+    b"CHGF", b"MISG", b"MISF", b"SAWG", b"PLSG", b"PLSF", b"BFGG", b"BFGF",
+    b"BLUD", b"PUFF", b"BAL1", b"BAL2", b"PLSS", b"PLSE", b"MISL", b"BFS1",
+    b"BFE1", b"BFE2", b"TFOG", b"IFOG", b"PLAY", b"POSS", b"SPOS", b"VILE",
+    b"FIRE", b"FATB", b"FBXP", b"SKEL", b"MANF", b"FATT", b"CPOS", b"SARG",
+    b"HEAD", b"BAL7", b"BOSS", b"BOS2", b"SKUL", b"SPID", b"BSPI", b"APLS",
+    b"APBX", b"CYBR", b"PAIN", b"SSWV", b"KEEN", b"BBRN", b"BOSF", b"ARM1",
+    b"ARM2", b"BAR1", b"BEXP", b"FCAN", b"BON1", b"BON2", b"BKEY", b"RKEY",
+    b"YKEY", b"BSKU", b"RSKU", b"YSKU", b"STIM", b"MEDI", b"SOUL", b"PINV",
+    b"PSTR", b"PINS", b"MEGA", b"SUIT", b"PMAP", b"PVIS", b"CLIP", b"AMMO",
+    b"ROCK", b"BROK", b"CELL", b"CELP", b"SHEL", b"SBOX", b"BPAK", b"BFUG",
+    b"MGUN", b"CSAW", b"LAUN", b"PLAS", b"SHOT", b"SGN2", b"COLU", b"SMT2",
+    b"GOR1", b"POL2", b"POL5", b"POL4", b"POL3", b"POL1", b"POL6", b"GOR2",
+    b"GOR3", b"GOR4", b"GOR5", b"SMIT", b"COL1", b"COL2", b"COL3", b"COL4",
+    b"CAND", b"CBRA", b"COL6", b"TRE1", b"TRE2", b"ELEC", b"CEYE", b"FSKU",
+    b"COL5", b"TBLU", b"TGRN", b"TRED", b"SMBT", b"SMGT", b"SMRT", b"HDB1",
+    b"HDB2", b"HDB3", b"HDB4", b"HDB5", b"HDB6", b"POB1", b"POB2", b"BRS1",
+    b"TLMP", b"TLP2",
+];
+
+/// (sprite, frame, tics, action, next state). Fullbright frames have
+/// FF_FULLBRIGHT set, as in info.c
+#[rustfmt::skip]
+pub static STATES: [StateDef; 967] = [
+    (b"TROO", 0, -1, "", 0), // 0 S_NULL
+    (b"SHTG", 4, 0, "A_Light0", 0), // 1 S_LIGHTDONE
+    (b"PUNG", 0, 1, "A_WeaponReady", 2), // 2 S_PUNCH
+    (b"PUNG", 0, 1, "A_Lower", 3), // 3 S_PUNCHDOWN
+    (b"PUNG", 0, 1, "A_Raise", 4), // 4 S_PUNCHUP
+    (b"PUNG", 1, 4, "", 6), // 5 S_PUNCH1
+    (b"PUNG", 2, 4, "A_Punch", 7), // 6 S_PUNCH2
+    (b"PUNG", 3, 5, "", 8), // 7 S_PUNCH3
+    (b"PUNG", 2, 4, "", 9), // 8 S_PUNCH4
+    (b"PUNG", 1, 5, "A_ReFire", 2), // 9 S_PUNCH5
+    (b"PISG", 0, 1, "A_WeaponReady", 10), // 10 S_PISTOL
+    (b"PISG", 0, 1, "A_Lower", 11), // 11 S_PISTOLDOWN
+    (b"PISG", 0, 1, "A_Raise", 12), // 12 S_PISTOLUP
+    (b"PISG", 0, 4, "", 14), // 13 S_PISTOL1
+    (b"PISG", 1, 6, "A_FirePistol", 15), // 14 S_PISTOL2
+    (b"PISG", 2, 4, "", 16), // 15 S_PISTOL3
+    (b"PISG", 1, 5, "A_ReFire", 10), // 16 S_PISTOL4
+    (b"PISF", 32768, 7, "A_Light1", 1), // 17 S_PISTOLFLASH
+    (b"SHTG", 0, 1, "A_WeaponReady", 18), // 18 S_SGUN
+    (b"SHTG", 0, 1, "A_Lower", 19), // 19 S_SGUNDOWN
+    (b"SHTG", 0, 1, "A_Raise", 20), // 20 S_SGUNUP
+    (b"SHTG", 0, 3, "", 22), // 21 S_SGUN1
+    (b"SHTG", 0, 7, "A_FireShotgun", 23), // 22 S_SGUN2
+    (b"SHTG", 1, 5, "", 24), // 23 S_SGUN3
+    (b"SHTG", 2, 5, "", 25), // 24 S_SGUN4
+    (b"SHTG", 3, 4, "", 26), // 25 S_SGUN5
+    (b"SHTG", 2, 5, "", 27), // 26 S_SGUN6
+    (b"SHTG", 1, 5, "", 28), // 27 S_SGUN7
+    (b"SHTG", 0, 3, "", 29), // 28 S_SGUN8
+    (b"SHTG", 0, 7, "A_ReFire", 18), // 29 S_SGUN9
+    (b"SHTF", 32768, 4, "A_Light1", 31), // 30 S_SGUNFLASH1
+    (b"SHTF", 32769, 3, "A_Light2", 1), // 31 S_SGUNFLASH2
+    (b"SHT2", 0, 1, "A_WeaponReady", 32), // 32 S_DSGUN
+    (b"SHT2", 0, 1, "A_Lower", 33), // 33 S_DSGUNDOWN
+    (b"SHT2", 0, 1, "A_Raise", 34), // 34 S_DSGUNUP
+    (b"SHT2", 0, 3, "", 36), // 35 S_DSGUN1
+    (b"SHT2", 0, 7, "A_FireShotgun2", 37), // 36 S_DSGUN2
+    (b"SHT2", 1, 7, "", 38), // 37 S_DSGUN3
+    (b"SHT2", 2, 7, "A_CheckReload", 39), // 38 S_DSGUN4
+    (b"SHT2", 3, 7, "A_OpenShotgun2", 40), // 39 S_DSGUN5
+    (b"SHT2", 4, 7, "", 41), // 40 S_DSGUN6
+    (b"SHT2", 5, 7, "A_LoadShotgun2", 42), // 41 S_DSGUN7
+    (b"SHT2", 6, 6, "", 43), // 42 S_DSGUN8
+    (b"SHT2", 7, 6, "A_CloseShotgun2", 44), // 43 S_DSGUN9
+    (b"SHT2", 0, 5, "A_ReFire", 32), // 44 S_DSGUN10
+    (b"SHT2", 1, 7, "", 46), // 45 S_DSNR1
+    (b"SHT2", 0, 3, "", 33), // 46 S_DSNR2
+    (b"SHT2", 32776, 5, "A_Light1", 48), // 47 S_DSGUNFLASH1
+    (b"SHT2", 32777, 4, "A_Light2", 1), // 48 S_DSGUNFLASH2
+    (b"CHGG", 0, 1, "A_WeaponReady", 49), // 49 S_CHAIN
+    (b"CHGG", 0, 1, "A_Lower", 50), // 50 S_CHAINDOWN
+    (b"CHGG", 0, 1, "A_Raise", 51), // 51 S_CHAINUP
+    (b"CHGG", 0, 4, "A_FireCGun", 53), // 52 S_CHAIN1
+    (b"CHGG", 1, 4, "A_FireCGun", 54), // 53 S_CHAIN2
+    (b"CHGG", 1, 0, "A_ReFire", 49), // 54 S_CHAIN3
+    (b"CHGF", 32768, 5, "A_Light1", 1), // 55 S_CHAINFLASH1
+    (b"CHGF", 32769, 5, "A_Light2", 1), // 56 S_CHAINFLASH2
+    (b"MISG", 0, 1, "A_WeaponReady", 57), // 57 S_MISSILE
+    (b"MISG", 0, 1, "A_Lower", 58), // 58 S_MISSILEDOWN
+    (b"MISG", 0, 1, "A_Raise", 59), // 59 S_MISSILEUP
+    (b"MISG", 1, 8, "A_GunFlash", 61), // 60 S_MISSILE1
+    (b"MISG", 1, 12, "A_FireMissile", 62), // 61 S_MISSILE2
+    (b"MISG", 1, 0, "A_ReFire", 57), // 62 S_MISSILE3
+    (b"MISF", 32768, 3, "A_Light1", 64), // 63 S_MISSILEFLASH1
+    (b"MISF", 32769, 4, "", 65), // 64 S_MISSILEFLASH2
+    (b"MISF", 32770, 4, "A_Light2", 66), // 65 S_MISSILEFLASH3
+    (b"MISF", 32771, 4, "A_Light2", 1), // 66 S_MISSILEFLASH4
+    (b"SAWG", 2, 4, "A_WeaponReady", 68), // 67 S_SAW
+    (b"SAWG", 3, 4, "A_WeaponReady", 67), // 68 S_SAWB
+    (b"SAWG", 2, 1, "A_Lower", 69), // 69 S_SAWDOWN
+    (b"SAWG", 2, 1, "A_Raise", 70), // 70 S_SAWUP
+    (b"SAWG", 0, 4, "A_Saw", 72), // 71 S_SAW1
+    (b"SAWG", 1, 4, "A_Saw", 73), // 72 S_SAW2
+    (b"SAWG", 1, 0, "A_ReFire", 67), // 73 S_SAW3
+    (b"PLSG", 0, 1, "A_WeaponReady", 74), // 74 S_PLASMA
+    (b"PLSG", 0, 1, "A_Lower", 75), // 75 S_PLASMADOWN
+    (b"PLSG", 0, 1, "A_Raise", 76), // 76 S_PLASMAUP
+    (b"PLSG", 0, 3, "A_FirePlasma", 78), // 77 S_PLASMA1
+    (b"PLSG", 1, 20, "A_ReFire", 74), // 78 S_PLASMA2
+    (b"PLSF", 32768, 4, "A_Light1", 1), // 79 S_PLASMAFLASH1
+    (b"PLSF", 32769, 4, "A_Light1", 1), // 80 S_PLASMAFLASH2
+    (b"BFGG", 0, 1, "A_WeaponReady", 81), // 81 S_BFG
+    (b"BFGG", 0, 1, "A_Lower", 82), // 82 S_BFGDOWN
+    (b"BFGG", 0, 1, "A_Raise", 83), // 83 S_BFGUP
+    (b"BFGG", 0, 20, "A_BFGsound", 85), // 84 S_BFG1
+    (b"BFGG", 1, 10, "A_GunFlash", 86), // 85 S_BFG2
+    (b"BFGG", 1, 10, "A_FireBFG", 87), // 86 S_BFG3
+    (b"BFGG", 1, 20, "A_ReFire", 81), // 87 S_BFG4
+    (b"BFGF", 32768, 11, "A_Light1", 89), // 88 S_BFGFLASH1
+    (b"BFGF", 32769, 6, "A_Light2", 1), // 89 S_BFGFLASH2
+    (b"BLUD", 2, 8, "", 91), // 90 S_BLOOD1
+    (b"BLUD", 1, 8, "", 92), // 91 S_BLOOD2
+    (b"BLUD", 0, 8, "", 0), // 92 S_BLOOD3
+    (b"PUFF", 32768, 4, "", 94), // 93 S_PUFF1
+    (b"PUFF", 1, 4, "", 95), // 94 S_PUFF2
+    (b"PUFF", 2, 4, "", 96), // 95 S_PUFF3
+    (b"PUFF", 3, 4, "", 0), // 96 S_PUFF4
+    (b"BAL1", 32768, 4, "", 98), // 97 S_TBALL1
+    (b"BAL1", 32769, 4, "", 97), // 98 S_TBALL2
+    (b"BAL1", 32770, 6, "", 100), // 99 S_TBALLX1
+    (b"BAL1", 32771, 6, "", 101), // 100 S_TBALLX2
+    (b"BAL1", 32772, 6, "", 0), // 101 S_TBALLX3
+    (b"BAL2", 32768, 4, "", 103), // 102 S_RBALL1
+    (b"BAL2", 32769, 4, "", 102), // 103 S_RBALL2
+    (b"BAL2", 32770, 6, "", 105), // 104 S_RBALLX1
+    (b"BAL2", 32771, 6, "", 106), // 105 S_RBALLX2
+    (b"BAL2", 32772, 6, "", 0), // 106 S_RBALLX3
+    (b"PLSS", 32768, 6, "", 108), // 107 S_PLASBALL
+    (b"PLSS", 32769, 6, "", 107), // 108 S_PLASBALL2
+    (b"PLSE", 32768, 4, "", 110), // 109 S_PLASEXP
+    (b"PLSE", 32769, 4, "", 111), // 110 S_PLASEXP2
+    (b"PLSE", 32770, 4, "", 112), // 111 S_PLASEXP3
+    (b"PLSE", 32771, 4, "", 113), // 112 S_PLASEXP4
+    (b"PLSE", 32772, 4, "", 0), // 113 S_PLASEXP5
+    (b"MISL", 32768, 1, "", 114), // 114 S_ROCKET
+    (b"BFS1", 32768, 4, "", 116), // 115 S_BFGSHOT
+    (b"BFS1", 32769, 4, "", 115), // 116 S_BFGSHOT2
+    (b"BFE1", 32768, 8, "", 118), // 117 S_BFGLAND
+    (b"BFE1", 32769, 8, "", 119), // 118 S_BFGLAND2
+    (b"BFE1", 32770, 8, "A_BFGSpray", 120), // 119 S_BFGLAND3
+    (b"BFE1", 32771, 8, "", 121), // 120 S_BFGLAND4
+    (b"BFE1", 32772, 8, "", 122), // 121 S_BFGLAND5
+    (b"BFE1", 32773, 8, "", 0), // 122 S_BFGLAND6
+    (b"BFE2", 32768, 8, "", 124), // 123 S_BFGEXP
+    (b"BFE2", 32769, 8, "", 125), // 124 S_BFGEXP2
+    (b"BFE2", 32770, 8, "", 126), // 125 S_BFGEXP3
+    (b"BFE2", 32771, 8, "", 0), // 126 S_BFGEXP4
+    (b"MISL", 32769, 8, "A_Explode", 128), // 127 S_EXPLODE1
+    (b"MISL", 32770, 6, "", 129), // 128 S_EXPLODE2
+    (b"MISL", 32771, 4, "", 0), // 129 S_EXPLODE3
+    (b"TFOG", 32768, 6, "", 131), // 130 S_TFOG
+    (b"TFOG", 32769, 6, "", 132), // 131 S_TFOG01
+    (b"TFOG", 32768, 6, "", 133), // 132 S_TFOG02
+    (b"TFOG", 32769, 6, "", 134), // 133 S_TFOG2
+    (b"TFOG", 32770, 6, "", 135), // 134 S_TFOG3
+    (b"TFOG", 32771, 6, "", 136), // 135 S_TFOG4
+    (b"TFOG", 32772, 6, "", 137), // 136 S_TFOG5
+    (b"TFOG", 32773, 6, "", 138), // 137 S_TFOG6
+    (b"TFOG", 32774, 6, "", 139), // 138 S_TFOG7
+    (b"TFOG", 32775, 6, "", 140), // 139 S_TFOG8
+    (b"TFOG", 32776, 6, "", 141), // 140 S_TFOG9
+    (b"TFOG", 32777, 6, "", 0), // 141 S_TFOG10
+    (b"IFOG", 32768, 6, "", 143), // 142 S_IFOG
+    (b"IFOG", 32769, 6, "", 144), // 143 S_IFOG01
+    (b"IFOG", 32768, 6, "", 145), // 144 S_IFOG02
+    (b"IFOG", 32769, 6, "", 146), // 145 S_IFOG2
+    (b"IFOG", 32770, 6, "", 147), // 146 S_IFOG3
+    (b"IFOG", 32771, 6, "", 148), // 147 S_IFOG4
+    (b"IFOG", 32772, 6, "", 0), // 148 S_IFOG5
+    (b"PLAY", 0, -1, "", 0), // 149 S_PLAY
+    (b"PLAY", 0, 4, "", 151), // 150 S_PLAY_RUN1
+    (b"PLAY", 1, 4, "", 152), // 151 S_PLAY_RUN2
+    (b"PLAY", 2, 4, "", 153), // 152 S_PLAY_RUN3
+    (b"PLAY", 3, 4, "", 150), // 153 S_PLAY_RUN4
+    (b"PLAY", 4, 12, "", 149), // 154 S_PLAY_ATK1
+    (b"PLAY", 32773, 6, "", 154), // 155 S_PLAY_ATK2
+    (b"PLAY", 6, 4, "", 157), // 156 S_PLAY_PAIN
+    (b"PLAY", 6, 4, "A_Pain", 149), // 157 S_PLAY_PAIN2
+    (b"PLAY", 7, 10, "", 159), // 158 S_PLAY_DIE1
+    (b"PLAY", 8, 10, "A_PlayerScream", 160), // 159 S_PLAY_DIE2
+    (b"PLAY", 9, 10, "A_Fall", 161), // 160 S_PLAY_DIE3
+    (b"PLAY", 10, 10, "", 162), // 161 S_PLAY_DIE4
+    (b"PLAY", 11, 10, "", 163), // 162 S_PLAY_DIE5
+    (b"PLAY", 12, 10, "", 164), // 163 S_PLAY_DIE6
+    (b"PLAY", 13, -1, "", 0), // 164 S_PLAY_DIE7
+    (b"PLAY", 14, 5, "", 166), // 165 S_PLAY_XDIE1
+    (b"PLAY", 15, 5, "A_XScream", 167), // 166 S_PLAY_XDIE2
+    (b"PLAY", 16, 5, "A_Fall", 168), // 167 S_PLAY_XDIE3
+    (b"PLAY", 17, 5, "", 169), // 168 S_PLAY_XDIE4
+    (b"PLAY", 18, 5, "", 170), // 169 S_PLAY_XDIE5
+    (b"PLAY", 19, 5, "", 171), // 170 S_PLAY_XDIE6
+    (b"PLAY", 20, 5, "", 172), // 171 S_PLAY_XDIE7
+    (b"PLAY", 21, 5, "", 173), // 172 S_PLAY_XDIE8
+    (b"PLAY", 22, -1, "", 0), // 173 S_PLAY_XDIE9
+    (b"POSS", 0, 10, "A_Look", 175), // 174 S_POSS_STND
+    (b"POSS", 1, 10, "A_Look", 174), // 175 S_POSS_STND2
+    (b"POSS", 0, 4, "A_Chase", 177), // 176 S_POSS_RUN1
+    (b"POSS", 0, 4, "A_Chase", 178), // 177 S_POSS_RUN2
+    (b"POSS", 1, 4, "A_Chase", 179), // 178 S_POSS_RUN3
+    (b"POSS", 1, 4, "A_Chase", 180), // 179 S_POSS_RUN4
+    (b"POSS", 2, 4, "A_Chase", 181), // 180 S_POSS_RUN5
+    (b"POSS", 2, 4, "A_Chase", 182), // 181 S_POSS_RUN6
+    (b"POSS", 3, 4, "A_Chase", 183), // 182 S_POSS_RUN7
+    (b"POSS", 3, 4, "A_Chase", 176), // 183 S_POSS_RUN8
+    (b"POSS", 4, 10, "A_FaceTarget", 185), // 184 S_POSS_ATK1
+    (b"POSS", 5, 8, "A_PosAttack", 186), // 185 S_POSS_ATK2
+    (b"POSS", 4, 8, "", 176), // 186 S_POSS_ATK3
+    (b"POSS", 6, 3, "", 188), // 187 S_POSS_PAIN
+    (b"POSS", 6, 3, "A_Pain", 176), // 188 S_POSS_PAIN2
+    (b"POSS", 7, 5, "", 190), // 189 S_POSS_DIE1
+    (b"POSS", 8, 5, "A_Scream", 191), // 190 S_POSS_DIE2
+    (b"POSS", 9, 5, "A_Fall", 192), // 191 S_POSS_DIE3
+    (b"POSS", 10, 5, "", 193), // 192 S_POSS_DIE4
+    (b"POSS", 11, -1, "", 0), // 193 S_POSS_DIE5
+    (b"POSS", 12, 5, "", 195), // 194 S_POSS_XDIE1
+    (b"POSS", 13, 5, "A_XScream", 196), // 195 S_POSS_XDIE2
+    (b"POSS", 14, 5, "A_Fall", 197), // 196 S_POSS_XDIE3
+    (b"POSS", 15, 5, "", 198), // 197 S_POSS_XDIE4
+    (b"POSS", 16, 5, "", 199), // 198 S_POSS_XDIE5
+    (b"POSS", 17, 5, "", 200), // 199 S_POSS_XDIE6
+    (b"POSS", 18, 5, "", 201), // 200 S_POSS_XDIE7
+    (b"POSS", 19, 5, "", 202), // 201 S_POSS_XDIE8
+    (b"POSS", 20, -1, "", 0), // 202 S_POSS_XDIE9
+    (b"POSS", 10, 5, "", 204), // 203 S_POSS_RAISE1
+    (b"POSS", 9, 5, "", 205), // 204 S_POSS_RAISE2
+    (b"POSS", 8, 5, "", 206), // 205 S_POSS_RAISE3
+    (b"POSS", 7, 5, "", 176), // 206 S_POSS_RAISE4
+    (b"SPOS", 0, 10, "A_Look", 208), // 207 S_SPOS_STND
+    (b"SPOS", 1, 10, "A_Look", 207), // 208 S_SPOS_STND2
+    (b"SPOS", 0, 3, "A_Chase", 210), // 209 S_SPOS_RUN1
+    (b"SPOS", 0, 3, "A_Chase", 211), // 210 S_SPOS_RUN2
+    (b"SPOS", 1, 3, "A_Chase", 212), // 211 S_SPOS_RUN3
+    (b"SPOS", 1, 3, "A_Chase", 213), // 212 S_SPOS_RUN4
+    (b"SPOS", 2, 3, "A_Chase", 214), // 213 S_SPOS_RUN5
+    (b"SPOS", 2, 3, "A_Chase", 215), // 214 S_SPOS_RUN6
+    (b"SPOS", 3, 3, "A_Chase", 216), // 215 S_SPOS_RUN7
+    (b"SPOS", 3, 3, "A_Chase", 209), // 216 S_SPOS_RUN8
+    (b"SPOS", 4, 10, "A_FaceTarget", 218), // 217 S_SPOS_ATK1
+    (b"SPOS", 32773, 10, "A_SPosAttack", 219), // 218 S_SPOS_ATK2
+    (b"SPOS", 4, 10, "", 209), // 219 S_SPOS_ATK3
+    (b"SPOS", 6, 3, "", 221), // 220 S_SPOS_PAIN
+    (b"SPOS", 6, 3, "A_Pain", 209), // 221 S_SPOS_PAIN2
+    (b"SPOS", 7, 5, "", 223), // 222 S_SPOS_DIE1
+    (b"SPOS", 8, 5, "A_Scream", 224), // 223 S_SPOS_DIE2
+    (b"SPOS", 9, 5, "A_Fall", 225), // 224 S_SPOS_DIE3
+    (b"SPOS", 10, 5, "", 226), // 225 S_SPOS_DIE4
+    (b"SPOS", 11, -1, "", 0), // 226 S_SPOS_DIE5
+    (b"SPOS", 12, 5, "", 228), // 227 S_SPOS_XDIE1
+    (b"SPOS", 13, 5, "A_XScream", 229), // 228 S_SPOS_XDIE2
+    (b"SPOS", 14, 5, "A_Fall", 230), // 229 S_SPOS_XDIE3
+    (b"SPOS", 15, 5, "", 231), // 230 S_SPOS_XDIE4
+    (b"SPOS", 16, 5, "", 232), // 231 S_SPOS_XDIE5
+    (b"SPOS", 17, 5, "", 233), // 232 S_SPOS_XDIE6
+    (b"SPOS", 18, 5, "", 234), // 233 S_SPOS_XDIE7
+    (b"SPOS", 19, 5, "", 235), // 234 S_SPOS_XDIE8
+    (b"SPOS", 20, -1, "", 0), // 235 S_SPOS_XDIE9
+    (b"SPOS", 11, 5, "", 237), // 236 S_SPOS_RAISE1
+    (b"SPOS", 10, 5, "", 238), // 237 S_SPOS_RAISE2
+    (b"SPOS", 9, 5, "", 239), // 238 S_SPOS_RAISE3
+    (b"SPOS", 8, 5, "", 240), // 239 S_SPOS_RAISE4
+    (b"SPOS", 7, 5, "", 209), // 240 S_SPOS_RAISE5
+    (b"VILE", 0, 10, "A_Look", 242), // 241 S_VILE_STND
+    (b"VILE", 1, 10, "A_Look", 241), // 242 S_VILE_STND2
+    (b"VILE", 0, 2, "A_VileChase", 244), // 243 S_VILE_RUN1
+    (b"VILE", 0, 2, "A_VileChase", 245), // 244 S_VILE_RUN2
+    (b"VILE", 1, 2, "A_VileChase", 246), // 245 S_VILE_RUN3
+    (b"VILE", 1, 2, "A_VileChase", 247), // 246 S_VILE_RUN4
+    (b"VILE", 2, 2, "A_VileChase", 248), // 247 S_VILE_RUN5
+    (b"VILE", 2, 2, "A_VileChase", 249), // 248 S_VILE_RUN6
+    (b"VILE", 3, 2, "A_VileChase", 250), // 249 S_VILE_RUN7
+    (b"VILE", 3, 2, "A_VileChase", 251), // 250 S_VILE_RUN8
+    (b"VILE", 4, 2, "A_VileChase", 252), // 251 S_VILE_RUN9
+    (b"VILE", 4, 2, "A_VileChase", 253), // 252 S_VILE_RUN10
+    (b"VILE", 5, 2, "A_VileChase", 254), // 253 S_VILE_RUN11
+    (b"VILE", 5, 2, "A_VileChase", 243), // 254 S_VILE_RUN12
+    (b"VILE", 32774, 0, "A_VileStart", 256), // 255 S_VILE_ATK1
+    (b"VILE", 32774, 10, "A_FaceTarget", 257), // 256 S_VILE_ATK2
+    (b"VILE", 32775, 8, "A_VileTarget", 258), // 257 S_VILE_ATK3
+    (b"VILE", 32776, 8, "A_FaceTarget", 259), // 258 S_VILE_ATK4
+    (b"VILE", 32777, 8, "A_FaceTarget", 260), // 259 S_VILE_ATK5
+    (b"VILE", 32778, 8, "A_FaceTarget", 261), // 260 S_VILE_ATK6
+    (b"VILE", 32779, 8, "A_FaceTarget", 262), // 261 S_VILE_ATK7
+    (b"VILE", 32780, 8, "A_FaceTarget", 263), // 262 S_VILE_ATK8
+    (b"VILE", 32781, 8, "A_FaceTarget", 264), // 263 S_VILE_ATK9
+    (b"VILE", 32782, 8, "A_VileAttack", 265), // 264 S_VILE_ATK10
+    (b"VILE", 32783, 20, "", 243), // 265 S_VILE_ATK11
+    (b"VILE", 32794, 10, "", 267), // 266 S_VILE_HEAL1
+    (b"VILE", 32795, 10, "", 268), // 267 S_VILE_HEAL2
+    (b"VILE", 32796, 10, "", 243), // 268 S_VILE_HEAL3
+    (b"VILE", 16, 5, "", 270), // 269 S_VILE_PAIN
+    (b"VILE", 16, 5, "A_Pain", 243), // 270 S_VILE_PAIN2
+    (b"VILE", 16, 7, "", 272), // 271 S_VILE_DIE1
+    (b"VILE", 17, 7, "A_Scream", 273), // 272 S_VILE_DIE2
+    (b"VILE", 18, 7, "A_Fall", 274), // 273 S_VILE_DIE3
+    (b"VILE", 19, 7, "", 275), // 274 S_VILE_DIE4
+    (b"VILE", 20, 7, "", 276), // 275 S_VILE_DIE5
+    (b"VILE", 21, 7, "", 277), // 276 S_VILE_DIE6
+    (b"VILE", 22, 7, "", 278), // 277 S_VILE_DIE7
+    (b"VILE", 23, 5, "", 279), // 278 S_VILE_DIE8
+    (b"VILE", 24, 5, "", 280), // 279 S_VILE_DIE9
+    (b"VILE", 25, -1, "", 0), // 280 S_VILE_DIE10
+    (b"FIRE", 32768, 2, "A_StartFire", 282), // 281 S_FIRE1
+    (b"FIRE", 32769, 2, "A_Fire", 283), // 282 S_FIRE2
+    (b"FIRE", 32768, 2, "A_Fire", 284), // 283 S_FIRE3
+    (b"FIRE", 32769, 2, "A_Fire", 285), // 284 S_FIRE4
+    (b"FIRE", 32770, 2, "A_FireCrackle", 286), // 285 S_FIRE5
+    (b"FIRE", 32769, 2, "A_Fire", 287), // 286 S_FIRE6
+    (b"FIRE", 32770, 2, "A_Fire", 288), // 287 S_FIRE7
+    (b"FIRE", 32769, 2, "A_Fire", 289), // 288 S_FIRE8
+    (b"FIRE", 32770, 2, "A_Fire", 290), // 289 S_FIRE9
+    (b"FIRE", 32771, 2, "A_Fire", 291), // 290 S_FIRE10
+    (b"FIRE", 32770, 2, "A_Fire", 292), // 291 S_FIRE11
+    (b"FIRE", 32771, 2, "A_Fire", 293), // 292 S_FIRE12
+    (b"FIRE", 32770, 2, "A_Fire", 294), // 293 S_FIRE13
+    (b"FIRE", 32771, 2, "A_Fire", 295), // 294 S_FIRE14
+    (b"FIRE", 32772, 2, "A_Fire", 296), // 295 S_FIRE15
+    (b"FIRE", 32771, 2, "A_Fire", 297), // 296 S_FIRE16
+    (b"FIRE", 32772, 2, "A_Fire", 298), // 297 S_FIRE17
+    (b"FIRE", 32771, 2, "A_Fire", 299), // 298 S_FIRE18
+    (b"FIRE", 32772, 2, "A_FireCrackle", 300), // 299 S_FIRE19
+    (b"FIRE", 32773, 2, "A_Fire", 301), // 300 S_FIRE20
+    (b"FIRE", 32772, 2, "A_Fire", 302), // 301 S_FIRE21
+    (b"FIRE", 32773, 2, "A_Fire", 303), // 302 S_FIRE22
+    (b"FIRE", 32772, 2, "A_Fire", 304), // 303 S_FIRE23
+    (b"FIRE", 32773, 2, "A_Fire", 305), // 304 S_FIRE24
+    (b"FIRE", 32774, 2, "A_Fire", 306), // 305 S_FIRE25
+    (b"FIRE", 32775, 2, "A_Fire", 307), // 306 S_FIRE26
+    (b"FIRE", 32774, 2, "A_Fire", 308), // 307 S_FIRE27
+    (b"FIRE", 32775, 2, "A_Fire", 309), // 308 S_FIRE28
+    (b"FIRE", 32774, 2, "A_Fire", 310), // 309 S_FIRE29
+    (b"FIRE", 32775, 2, "A_Fire", 0), // 310 S_FIRE30
+    (b"PUFF", 1, 4, "", 312), // 311 S_SMOKE1
+    (b"PUFF", 2, 4, "", 313), // 312 S_SMOKE2
+    (b"PUFF", 1, 4, "", 314), // 313 S_SMOKE3
+    (b"PUFF", 2, 4, "", 315), // 314 S_SMOKE4
+    (b"PUFF", 3, 4, "", 0), // 315 S_SMOKE5
+    (b"FATB", 32768, 2, "A_Tracer", 317), // 316 S_TRACER
+    (b"FATB", 32769, 2, "A_Tracer", 316), // 317 S_TRACER2
+    (b"FBXP", 32768, 8, "", 319), // 318 S_TRACEEXP1
+    (b"FBXP", 32769, 6, "", 320), // 319 S_TRACEEXP2
+    (b"FBXP", 32770, 4, "", 0), // 320 S_TRACEEXP3
+    (b"SKEL", 0, 10, "A_Look", 322), // 321 S_SKEL_STND
+    (b"SKEL", 1, 10, "A_Look", 321), // 322 S_SKEL_STND2
+    (b"SKEL", 0, 2, "A_Chase", 324), // 323 S_SKEL_RUN1
+    (b"SKEL", 0, 2, "A_Chase", 325), // 324 S_SKEL_RUN2
+    (b"SKEL", 1, 2, "A_Chase", 326), // 325 S_SKEL_RUN3
+    (b"SKEL", 1, 2, "A_Chase", 327), // 326 S_SKEL_RUN4
+    (b"SKEL", 2, 2, "A_Chase", 328), // 327 S_SKEL_RUN5
+    (b"SKEL", 2, 2, "A_Chase", 329), // 328 S_SKEL_RUN6
+    (b"SKEL", 3, 2, "A_Chase", 330), // 329 S_SKEL_RUN7
+    (b"SKEL", 3, 2, "A_Chase", 331), // 330 S_SKEL_RUN8
+    (b"SKEL", 4, 2, "A_Chase", 332), // 331 S_SKEL_RUN9
+    (b"SKEL", 4, 2, "A_Chase", 333), // 332 S_SKEL_RUN10
+    (b"SKEL", 5, 2, "A_Chase", 334), // 333 S_SKEL_RUN11
+    (b"SKEL", 5, 2, "A_Chase", 323), // 334 S_SKEL_RUN12
+    (b"SKEL", 6, 0, "A_FaceTarget", 336), // 335 S_SKEL_FIST1
+    (b"SKEL", 6, 6, "A_SkelWhoosh", 337), // 336 S_SKEL_FIST2
+    (b"SKEL", 7, 6, "A_FaceTarget", 338), // 337 S_SKEL_FIST3
+    (b"SKEL", 8, 6, "A_SkelFist", 323), // 338 S_SKEL_FIST4
+    (b"SKEL", 32777, 0, "A_FaceTarget", 340), // 339 S_SKEL_MISS1
+    (b"SKEL", 32777, 10, "A_FaceTarget", 341), // 340 S_SKEL_MISS2
+    (b"SKEL", 10, 10, "A_SkelMissile", 342), // 341 S_SKEL_MISS3
+    (b"SKEL", 10, 10, "A_FaceTarget", 323), // 342 S_SKEL_MISS4
+    (b"SKEL", 11, 5, "", 344), // 343 S_SKEL_PAIN
+    (b"SKEL", 11, 5, "A_Pain", 323), // 344 S_SKEL_PAIN2
+    (b"SKEL", 11, 7, "", 346), // 345 S_SKEL_DIE1
+    (b"SKEL", 12, 7, "", 347), // 346 S_SKEL_DIE2
+    (b"SKEL", 13, 7, "A_Scream", 348), // 347 S_SKEL_DIE3
+    (b"SKEL", 14, 7, "A_Fall", 349), // 348 S_SKEL_DIE4
+    (b"SKEL", 15, 7, "", 350), // 349 S_SKEL_DIE5
+    (b"SKEL", 16, -1, "", 0), // 350 S_SKEL_DIE6
+    (b"SKEL", 16, 5, "", 352), // 351 S_SKEL_RAISE1
+    (b"SKEL", 15, 5, "", 353), // 352 S_SKEL_RAISE2
+    (b"SKEL", 14, 5, "", 354), // 353 S_SKEL_RAISE3
+    (b"SKEL", 13, 5, "", 355), // 354 S_SKEL_RAISE4
+    (b"SKEL", 12, 5, "", 356), // 355 S_SKEL_RAISE5
+    (b"SKEL", 11, 5, "", 323), // 356 S_SKEL_RAISE6
+    (b"MANF", 32768, 4, "", 358), // 357 S_FATSHOT1
+    (b"MANF", 32769, 4, "", 357), // 358 S_FATSHOT2
+    (b"MISL", 32769, 8, "", 360), // 359 S_FATSHOTX1
+    (b"MISL", 32770, 6, "", 361), // 360 S_FATSHOTX2
+    (b"MISL", 32771, 4, "", 0), // 361 S_FATSHOTX3
+    (b"FATT", 0, 15, "A_Look", 363), // 362 S_FATT_STND
+    (b"FATT", 1, 15, "A_Look", 362), // 363 S_FATT_STND2
+    (b"FATT", 0, 4, "A_Chase", 365), // 364 S_FATT_RUN1
+    (b"FATT", 0, 4, "A_Chase", 366), // 365 S_FATT_RUN2
+    (b"FATT", 1, 4, "A_Chase", 367), // 366 S_FATT_RUN3
+    (b"FATT", 1, 4, "A_Chase", 368), // 367 S_FATT_RUN4
+    (b"FATT", 2, 4, "A_Chase", 369), // 368 S_FATT_RUN5
+    (b"FATT", 2, 4, "A_Chase", 370), // 369 S_FATT_RUN6
+    (b"FATT", 3, 4, "A_Chase", 371), // 370 S_FATT_RUN7
+    (b"FATT", 3, 4, "A_Chase", 372), // 371 S_FATT_RUN8
+    (b"FATT", 4, 4, "A_Chase", 373), // 372 S_FATT_RUN9
+    (b"FATT", 4, 4, "A_Chase", 374), // 373 S_FATT_RUN10
+    (b"FATT", 5, 4, "A_Chase", 375), // 374 S_FATT_RUN11
+    (b"FATT", 5, 4, "A_Chase", 364), // 375 S_FATT_RUN12
+    (b"FATT", 6, 20, "A_FatRaise", 377), // 376 S_FATT_ATK1
+    (b"FATT", 32775, 10, "A_FatAttack1", 378), // 377 S_FATT_ATK2
+    (b"FATT", 8, 5, "A_FaceTarget", 379), // 378 S_FATT_ATK3
+    (b"FATT", 6, 5, "A_FaceTarget", 380), // 379 S_FATT_ATK4
+    (b"FATT", 32775, 10, "A_FatAttack2", 381), // 380 S_FATT_ATK5
+    (b"FATT", 8, 5, "A_FaceTarget", 382), // 381 S_FATT_ATK6
+    (b"FATT", 6, 5, "A_FaceTarget", 383), // 382 S_FATT_ATK7
+    (b"FATT", 32775, 10, "A_FatAttack3", 384), // 383 S_FATT_ATK8
+    (b"FATT", 8, 5, "A_FaceTarget", 385), // 384 S_FATT_ATK9
+    (b"FATT", 6, 5, "A_FaceTarget", 364), // 385 S_FATT_ATK10
+    (b"FATT", 9, 3, "", 387), // 386 S_FATT_PAIN
+    (b"FATT", 9, 3, "A_Pain", 364), // 387 S_FATT_PAIN2
+    (b"FATT", 10, 6, "", 389), // 388 S_FATT_DIE1
+    (b"FATT", 11, 6, "A_Scream", 390), // 389 S_FATT_DIE2
+    (b"FATT", 12, 6, "A_Fall", 391), // 390 S_FATT_DIE3
+    (b"FATT", 13, 6, "", 392), // 391 S_FATT_DIE4
+    (b"FATT", 14, 6, "", 393), // 392 S_FATT_DIE5
+    (b"FATT", 15, 6, "", 394), // 393 S_FATT_DIE6
+    (b"FATT", 16, 6, "", 395), // 394 S_FATT_DIE7
+    (b"FATT", 17, 6, "", 396), // 395 S_FATT_DIE8
+    (b"FATT", 18, 6, "", 397), // 396 S_FATT_DIE9
+    (b"FATT", 19, -1, "A_BossDeath", 0), // 397 S_FATT_DIE10
+    (b"FATT", 17, 5, "", 399), // 398 S_FATT_RAISE1
+    (b"FATT", 16, 5, "", 400), // 399 S_FATT_RAISE2
+    (b"FATT", 15, 5, "", 401), // 400 S_FATT_RAISE3
+    (b"FATT", 14, 5, "", 402), // 401 S_FATT_RAISE4
+    (b"FATT", 13, 5, "", 403), // 402 S_FATT_RAISE5
+    (b"FATT", 12, 5, "", 404), // 403 S_FATT_RAISE6
+    (b"FATT", 11, 5, "", 405), // 404 S_FATT_RAISE7
+    (b"FATT", 10, 5, "", 364), // 405 S_FATT_RAISE8
+    (b"CPOS", 0, 10, "A_Look", 407), // 406 S_CPOS_STND
+    (b"CPOS", 1, 10, "A_Look", 406), // 407 S_CPOS_STND2
+    (b"CPOS", 0, 3, "A_Chase", 409), // 408 S_CPOS_RUN1
+    (b"CPOS", 0, 3, "A_Chase", 410), // 409 S_CPOS_RUN2
+    (b"CPOS", 1, 3, "A_Chase", 411), // 410 S_CPOS_RUN3
+    (b"CPOS", 1, 3, "A_Chase", 412), // 411 S_CPOS_RUN4
+    (b"CPOS", 2, 3, "A_Chase", 413), // 412 S_CPOS_RUN5
+    (b"CPOS", 2, 3, "A_Chase", 414), // 413 S_CPOS_RUN6
+    (b"CPOS", 3, 3, "A_Chase", 415), // 414 S_CPOS_RUN7
+    (b"CPOS", 3, 3, "A_Chase", 408), // 415 S_CPOS_RUN8
+    (b"CPOS", 4, 10, "A_FaceTarget", 417), // 416 S_CPOS_ATK1
+    (b"CPOS", 32773, 4, "A_CPosAttack", 418), // 417 S_CPOS_ATK2
+    (b"CPOS", 32772, 4, "A_CPosAttack", 419), // 418 S_CPOS_ATK3
+    (b"CPOS", 5, 1, "A_CPosRefire", 417), // 419 S_CPOS_ATK4
+    (b"CPOS", 6, 3, "", 421), // 420 S_CPOS_PAIN
+    (b"CPOS", 6, 3, "A_Pain", 408), // 421 S_CPOS_PAIN2
+    (b"CPOS", 7, 5, "", 423), // 422 S_CPOS_DIE1
+    (b"CPOS", 8, 5, "A_Scream", 424), // 423 S_CPOS_DIE2
+    (b"CPOS", 9, 5, "A_Fall", 425), // 424 S_CPOS_DIE3
+    (b"CPOS", 10, 5, "", 426), // 425 S_CPOS_DIE4
+    (b"CPOS", 11, 5, "", 427), // 426 S_CPOS_DIE5
+    (b"CPOS", 12, 5, "", 428), // 427 S_CPOS_DIE6
+    (b"CPOS", 13, -1, "", 0), // 428 S_CPOS_DIE7
+    (b"CPOS", 14, 5, "", 430), // 429 S_CPOS_XDIE1
+    (b"CPOS", 15, 5, "A_XScream", 431), // 430 S_CPOS_XDIE2
+    (b"CPOS", 16, 5, "A_Fall", 432), // 431 S_CPOS_XDIE3
+    (b"CPOS", 17, 5, "", 433), // 432 S_CPOS_XDIE4
+    (b"CPOS", 18, 5, "", 434), // 433 S_CPOS_XDIE5
+    (b"CPOS", 19, -1, "", 0), // 434 S_CPOS_XDIE6
+    (b"CPOS", 13, 5, "", 436), // 435 S_CPOS_RAISE1
+    (b"CPOS", 12, 5, "", 437), // 436 S_CPOS_RAISE2
+    (b"CPOS", 11, 5, "", 438), // 437 S_CPOS_RAISE3
+    (b"CPOS", 10, 5, "", 439), // 438 S_CPOS_RAISE4
+    (b"CPOS", 9, 5, "", 440), // 439 S_CPOS_RAISE5
+    (b"CPOS", 8, 5, "", 441), // 440 S_CPOS_RAISE6
+    (b"CPOS", 7, 5, "", 408), // 441 S_CPOS_RAISE7
+    (b"TROO", 0, 10, "A_Look", 443), // 442 S_TROO_STND
+    (b"TROO", 1, 10, "A_Look", 442), // 443 S_TROO_STND2
+    (b"TROO", 0, 3, "A_Chase", 445), // 444 S_TROO_RUN1
+    (b"TROO", 0, 3, "A_Chase", 446), // 445 S_TROO_RUN2
+    (b"TROO", 1, 3, "A_Chase", 447), // 446 S_TROO_RUN3
+    (b"TROO", 1, 3, "A_Chase", 448), // 447 S_TROO_RUN4
+    (b"TROO", 2, 3, "A_Chase", 449), // 448 S_TROO_RUN5
+    (b"TROO", 2, 3, "A_Chase", 450), // 449 S_TROO_RUN6
+    (b"TROO", 3, 3, "A_Chase", 451), // 450 S_TROO_RUN7
+    (b"TROO", 3, 3, "A_Chase", 444), // 451 S_TROO_RUN8
+    (b"TROO", 4, 8, "A_FaceTarget", 453), // 452 S_TROO_ATK1
+    (b"TROO", 5, 8, "A_FaceTarget", 454), // 453 S_TROO_ATK2
+    (b"TROO", 6, 6, "A_TroopAttack", 444), // 454 S_TROO_ATK3
+    (b"TROO", 7, 2, "", 456), // 455 S_TROO_PAIN
+    (b"TROO", 7, 2, "A_Pain", 444), // 456 S_TROO_PAIN2
+    (b"TROO", 8, 8, "", 458), // 457 S_TROO_DIE1
+    (b"TROO", 9, 8, "A_Scream", 459), // 458 S_TROO_DIE2
+    (b"TROO", 10, 6, "", 460), // 459 S_TROO_DIE3
+    (b"TROO", 11, 6, "A_Fall", 461), // 460 S_TROO_DIE4
+    (b"TROO", 12, -1, "", 0), // 461 S_TROO_DIE5
+    (b"TROO", 13, 5, "", 463), // 462 S_TROO_XDIE1
+    (b"TROO", 14, 5, "A_XScream", 464), // 463 S_TROO_XDIE2
+    (b"TROO", 15, 5, "", 465), // 464 S_TROO_XDIE3
+    (b"TROO", 16, 5, "A_Fall", 466), // 465 S_TROO_XDIE4
+    (b"TROO", 17, 5, "", 467), // 466 S_TROO_XDIE5
+    (b"TROO", 18, 5, "", 468), // 467 S_TROO_XDIE6
+    (b"TROO", 19, 5, "", 469), // 468 S_TROO_XDIE7
+    (b"TROO", 20, -1, "", 0), // 469 S_TROO_XDIE8
+    (b"TROO", 12, 8, "", 471), // 470 S_TROO_RAISE1
+    (b"TROO", 11, 8, "", 472), // 471 S_TROO_RAISE2
+    (b"TROO", 10, 6, "", 473), // 472 S_TROO_RAISE3
+    (b"TROO", 9, 6, "", 474), // 473 S_TROO_RAISE4
+    (b"TROO", 8, 6, "", 444), // 474 S_TROO_RAISE5
+    (b"SARG", 0, 10, "A_Look", 476), // 475 S_SARG_STND
+    (b"SARG", 1, 10, "A_Look", 475), // 476 S_SARG_STND2
+    (b"SARG", 0, 2, "A_Chase", 478), // 477 S_SARG_RUN1
+    (b"SARG", 0, 2, "A_Chase", 479), // 478 S_SARG_RUN2
+    (b"SARG", 1, 2, "A_Chase", 480), // 479 S_SARG_RUN3
+    (b"SARG", 1, 2, "A_Chase", 481), // 480 S_SARG_RUN4
+    (b"SARG", 2, 2, "A_Chase", 482), // 481 S_SARG_RUN5
+    (b"SARG", 2, 2, "A_Chase", 483), // 482 S_SARG_RUN6
+    (b"SARG", 3, 2, "A_Chase", 484), // 483 S_SARG_RUN7
+    (b"SARG", 3, 2, "A_Chase", 477), // 484 S_SARG_RUN8
+    (b"SARG", 4, 8, "A_FaceTarget", 486), // 485 S_SARG_ATK1
+    (b"SARG", 5, 8, "A_FaceTarget", 487), // 486 S_SARG_ATK2
+    (b"SARG", 6, 8, "A_SargAttack", 477), // 487 S_SARG_ATK3
+    (b"SARG", 7, 2, "", 489), // 488 S_SARG_PAIN
+    (b"SARG", 7, 2, "A_Pain", 477), // 489 S_SARG_PAIN2
+    (b"SARG", 8, 8, "", 491), // 490 S_SARG_DIE1
+    (b"SARG", 9, 8, "A_Scream", 492), // 491 S_SARG_DIE2
+    (b"SARG", 10, 4, "", 493), // 492 S_SARG_DIE3
+    (b"SARG", 11, 4, "A_Fall", 494), // 493 S_SARG_DIE4
+    (b"SARG", 12, 4, "", 495), // 494 S_SARG_DIE5
+    (b"SARG", 13, -1, "", 0), // 495 S_SARG_DIE6
+    (b"SARG", 13, 5, "", 497), // 496 S_SARG_RAISE1
+    (b"SARG", 12, 5, "", 498), // 497 S_SARG_RAISE2
+    (b"SARG", 11, 5, "", 499), // 498 S_SARG_RAISE3
+    (b"SARG", 10, 5, "", 500), // 499 S_SARG_RAISE4
+    (b"SARG", 9, 5, "", 501), // 500 S_SARG_RAISE5
+    (b"SARG", 8, 5, "", 477), // 501 S_SARG_RAISE6
+    (b"HEAD", 0, 10, "A_Look", 502), // 502 S_HEAD_STND
+    (b"HEAD", 0, 3, "A_Chase", 503), // 503 S_HEAD_RUN1
+    (b"HEAD", 1, 5, "A_FaceTarget", 505), // 504 S_HEAD_ATK1
+    (b"HEAD", 2, 5, "A_FaceTarget", 506), // 505 S_HEAD_ATK2
+    (b"HEAD", 32771, 5, "A_HeadAttack", 503), // 506 S_HEAD_ATK3
+    (b"HEAD", 4, 3, "", 508), // 507 S_HEAD_PAIN
+    (b"HEAD", 4, 3, "A_Pain", 509), // 508 S_HEAD_PAIN2
+    (b"HEAD", 5, 6, "", 503), // 509 S_HEAD_PAIN3
+    (b"HEAD", 6, 8, "", 511), // 510 S_HEAD_DIE1
+    (b"HEAD", 7, 8, "A_Scream", 512), // 511 S_HEAD_DIE2
+    (b"HEAD", 8, 8, "", 513), // 512 S_HEAD_DIE3
+    (b"HEAD", 9, 8, "", 514), // 513 S_HEAD_DIE4
+    (b"HEAD", 10, 8, "A_Fall", 515), // 514 S_HEAD_DIE5
+    (b"HEAD", 11, -1, "", 0), // 515 S_HEAD_DIE6
+    (b"HEAD", 11, 8, "", 517), // 516 S_HEAD_RAISE1
+    (b"HEAD", 10, 8, "", 518), // 517 S_HEAD_RAISE2
+    (b"HEAD", 9, 8, "", 519), // 518 S_HEAD_RAISE3
+    (b"HEAD", 8, 8, "", 520), // 519 S_HEAD_RAISE4
+    (b"HEAD", 7, 8, "", 521), // 520 S_HEAD_RAISE5
+    (b"HEAD", 6, 8, "", 503), // 521 S_HEAD_RAISE6
+    (b"BAL7", 32768, 4, "", 523), // 522 S_BRBALL1
+    (b"BAL7", 32769, 4, "", 522), // 523 S_BRBALL2
+    (b"BAL7", 32770, 6, "", 525), // 524 S_BRBALLX1
+    (b"BAL7", 32771, 6, "", 526), // 525 S_BRBALLX2
+    (b"BAL7", 32772, 6, "", 0), // 526 S_BRBALLX3
+    (b"BOSS", 0, 10, "A_Look", 528), // 527 S_BOSS_STND
+    (b"BOSS", 1, 10, "A_Look", 527), // 528 S_BOSS_STND2
+    (b"BOSS", 0, 3, "A_Chase", 530), // 529 S_BOSS_RUN1
+    (b"BOSS", 0, 3, "A_Chase", 531), // 530 S_BOSS_RUN2
+    (b"BOSS", 1, 3, "A_Chase", 532), // 531 S_BOSS_RUN3
+    (b"BOSS", 1, 3, "A_Chase", 533), // 532 S_BOSS_RUN4
+    (b"BOSS", 2, 3, "A_Chase", 534), // 533 S_BOSS_RUN5
+    (b"BOSS", 2, 3, "A_Chase", 535), // 534 S_BOSS_RUN6
+    (b"BOSS", 3, 3, "A_Chase", 536), // 535 S_BOSS_RUN7
+    (b"BOSS", 3, 3, "A_Chase", 529), // 536 S_BOSS_RUN8
+    (b"BOSS", 4, 8, "A_FaceTarget", 538), // 537 S_BOSS_ATK1
+    (b"BOSS", 5, 8, "A_FaceTarget", 539), // 538 S_BOSS_ATK2
+    (b"BOSS", 6, 8, "A_BruisAttack", 529), // 539 S_BOSS_ATK3
+    (b"BOSS", 7, 2, "", 541), // 540 S_BOSS_PAIN
+    (b"BOSS", 7, 2, "A_Pain", 529), // 541 S_BOSS_PAIN2
+    (b"BOSS", 8, 8, "", 543), // 542 S_BOSS_DIE1
+    (b"BOSS", 9, 8, "A_Scream", 544), // 543 S_BOSS_DIE2
+    (b"BOSS", 10, 8, "", 545), // 544 S_BOSS_DIE3
+    (b"BOSS", 11, 8, "A_Fall", 546), // 545 S_BOSS_DIE4
+    (b"BOSS", 12, 8, "", 547), // 546 S_BOSS_DIE5
+    (b"BOSS", 13, 8, "", 548), // 547 S_BOSS_DIE6
+    (b"BOSS", 14, -1, "A_BossDeath", 0), // 548 S_BOSS_DIE7
+    (b"BOSS", 14, 8, "", 550), // 549 S_BOSS_RAISE1
+    (b"BOSS", 13, 8, "", 551), // 550 S_BOSS_RAISE2
+    (b"BOSS", 12, 8, "", 552), // 551 S_BOSS_RAISE3
+    (b"BOSS", 11, 8, "", 553), // 552 S_BOSS_RAISE4
+    (b"BOSS", 10, 8, "", 554), // 553 S_BOSS_RAISE5
+    (b"BOSS", 9, 8, "", 555), // 554 S_BOSS_RAISE6
+    (b"BOSS", 8, 8, "", 529), // 555 S_BOSS_RAISE7
+    (b"BOS2", 0, 10, "A_Look", 557), // 556 S_BOS2_STND
+    (b"BOS2", 1, 10, "A_Look", 556), // 557 S_BOS2_STND2
+    (b"BOS2", 0, 3, "A_Chase", 559), // 558 S_BOS2_RUN1
+    (b"BOS2", 0, 3, "A_Chase", 560), // 559 S_BOS2_RUN2
+    (b"BOS2", 1, 3, "A_Chase", 561), // 560 S_BOS2_RUN3
+    (b"BOS2", 1, 3, "A_Chase", 562), // 561 S_BOS2_RUN4
+    (b"BOS2", 2, 3, "A_Chase", 563), // 562 S_BOS2_RUN5
+    (b"BOS2", 2, 3, "A_Chase", 564), // 563 S_BOS2_RUN6
+    (b"BOS2", 3, 3, "A_Chase", 565), // 564 S_BOS2_RUN7
+    (b"BOS2", 3, 3, "A_Chase", 558), // 565 S_BOS2_RUN8
+    (b"BOS2", 4, 8, "A_FaceTarget", 567), // 566 S_BOS2_ATK1
+    (b"BOS2", 5, 8, "A_FaceTarget", 568), // 567 S_BOS2_ATK2
+    (b"BOS2", 6, 8, "A_BruisAttack", 558), // 568 S_BOS2_ATK3
+    (b"BOS2", 7, 2, "", 570), // 569 S_BOS2_PAIN
+    (b"BOS2", 7, 2, "A_Pain", 558), // 570 S_BOS2_PAIN2
+    (b"BOS2", 8, 8, "", 572), // 571 S_BOS2_DIE1
+    (b"BOS2", 9, 8, "A_Scream", 573), // 572 S_BOS2_DIE2
+    (b"BOS2", 10, 8, "", 574), // 573 S_BOS2_DIE3
+    (b"BOS2", 11, 8, "A_Fall", 575), // 574 S_BOS2_DIE4
+    (b"BOS2", 12, 8, "", 576), // 575 S_BOS2_DIE5
+    (b"BOS2", 13, 8, "", 577), // 576 S_BOS2_DIE6
+    (b"BOS2", 14, -1, "", 0), // 577 S_BOS2_DIE7
+    (b"BOS2", 14, 8, "", 579), // 578 S_BOS2_RAISE1
+    (b"BOS2", 13, 8, "", 580), // 579 S_BOS2_RAISE2
+    (b"BOS2", 12, 8, "", 581), // 580 S_BOS2_RAISE3
+    (b"BOS2", 11, 8, "", 582), // 581 S_BOS2_RAISE4
+    (b"BOS2", 10, 8, "", 583), // 582 S_BOS2_RAISE5
+    (b"BOS2", 9, 8, "", 584), // 583 S_BOS2_RAISE6
+    (b"BOS2", 8, 8, "", 558), // 584 S_BOS2_RAISE7
+    (b"SKUL", 32768, 10, "A_Look", 586), // 585 S_SKULL_STND
+    (b"SKUL", 32769, 10, "A_Look", 585), // 586 S_SKULL_STND2
+    (b"SKUL", 32768, 6, "A_Chase", 588), // 587 S_SKULL_RUN1
+    (b"SKUL", 32769, 6, "A_Chase", 587), // 588 S_SKULL_RUN2
+    (b"SKUL", 32770, 10, "A_FaceTarget", 590), // 589 S_SKULL_ATK1
+    (b"SKUL", 32771, 4, "A_SkullAttack", 591), // 590 S_SKULL_ATK2
+    (b"SKUL", 32770, 4, "", 592), // 591 S_SKULL_ATK3
+    (b"SKUL", 32771, 4, "", 591), // 592 S_SKULL_ATK4
+    (b"SKUL", 32772, 3, "", 594), // 593 S_SKULL_PAIN
+    (b"SKUL", 32772, 3, "A_Pain", 587), // 594 S_SKULL_PAIN2
+    (b"SKUL", 32773, 6, "", 596), // 595 S_SKULL_DIE1
+    (b"SKUL", 32774, 6, "A_Scream", 597), // 596 S_SKULL_DIE2
+    (b"SKUL", 32775, 6, "", 598), // 597 S_SKULL_DIE3
+    (b"SKUL", 32776, 6, "A_Fall", 599), // 598 S_SKULL_DIE4
+    (b"SKUL", 9, 6, "", 600), // 599 S_SKULL_DIE5
+    (b"SKUL", 10, 6, "", 0), // 600 S_SKULL_DIE6
+    (b"SPID", 0, 10, "A_Look", 602), // 601 S_SPID_STND
+    (b"SPID", 1, 10, "A_Look", 601), // 602 S_SPID_STND2
+    (b"SPID", 0, 3, "A_Metal", 604), // 603 S_SPID_RUN1
+    (b"SPID", 0, 3, "A_Chase", 605), // 604 S_SPID_RUN2
+    (b"SPID", 1, 3, "A_Chase", 606), // 605 S_SPID_RUN3
+    (b"SPID", 1, 3, "A_Chase", 607), // 606 S_SPID_RUN4
+    (b"SPID", 2, 3, "A_Metal", 608), // 607 S_SPID_RUN5
+    (b"SPID", 2, 3, "A_Chase", 609), // 608 S_SPID_RUN6
+    (b"SPID", 3, 3, "A_Chase", 610), // 609 S_SPID_RUN7
+    (b"SPID", 3, 3, "A_Chase", 611), // 610 S_SPID_RUN8
+    (b"SPID", 4, 3, "A_Metal", 612), // 611 S_SPID_RUN9
+    (b"SPID", 4, 3, "A_Chase", 613), // 612 S_SPID_RUN10
+    (b"SPID", 5, 3, "A_Chase", 614), // 613 S_SPID_RUN11
+    (b"SPID", 5, 3, "A_Chase", 603), // 614 S_SPID_RUN12
+    (b"SPID", 32768, 20, "A_FaceTarget", 616), // 615 S_SPID_ATK1
+    (b"SPID", 32774, 4, "A_SPosAttack", 617), // 616 S_SPID_ATK2
+    (b"SPID", 32775, 4, "A_SPosAttack", 618), // 617 S_SPID_ATK3
+    (b"SPID", 32775, 1, "A_SpidRefire", 616), // 618 S_SPID_ATK4
+    (b"SPID", 8, 3, "", 620), // 619 S_SPID_PAIN
+    (b"SPID", 8, 3, "A_Pain", 603), // 620 S_SPID_PAIN2
+    (b"SPID", 9, 20, "A_Scream", 622), // 621 S_SPID_DIE1
+    (b"SPID", 10, 10, "A_Fall", 623), // 622 S_SPID_DIE2
+    (b"SPID", 11, 10, "", 624), // 623 S_SPID_DIE3
+    (b"SPID", 12, 10, "", 625), // 624 S_SPID_DIE4
+    (b"SPID", 13, 10, "", 626), // 625 S_SPID_DIE5
+    (b"SPID", 14, 10, "", 627), // 626 S_SPID_DIE6
+    (b"SPID", 15, 10, "", 628), // 627 S_SPID_DIE7
+    (b"SPID", 16, 10, "", 629), // 628 S_SPID_DIE8
+    (b"SPID", 17, 10, "", 630), // 629 S_SPID_DIE9
+    (b"SPID", 18, 30, "", 631), // 630 S_SPID_DIE10
+    (b"SPID", 18, -1, "A_BossDeath", 0), // 631 S_SPID_DIE11
+    (b"BSPI", 0, 10, "A_Look", 633), // 632 S_BSPI_STND
+    (b"BSPI", 1, 10, "A_Look", 632), // 633 S_BSPI_STND2
+    (b"BSPI", 0, 20, "", 635), // 634 S_BSPI_SIGHT
+    (b"BSPI", 0, 3, "A_BabyMetal", 636), // 635 S_BSPI_RUN1
+    (b"BSPI", 0, 3, "A_Chase", 637), // 636 S_BSPI_RUN2
+    (b"BSPI", 1, 3, "A_Chase", 638), // 637 S_BSPI_RUN3
+    (b"BSPI", 1, 3, "A_Chase", 639), // 638 S_BSPI_RUN4
+    (b"BSPI", 2, 3, "A_Chase", 640), // 639 S_BSPI_RUN5
+    (b"BSPI", 2, 3, "A_Chase", 641), // 640 S_BSPI_RUN6
+    (b"BSPI", 3, 3, "A_BabyMetal", 642), // 641 S_BSPI_RUN7
+    (b"BSPI", 3, 3, "A_Chase", 643), // 642 S_BSPI_RUN8
+    (b"BSPI", 4, 3, "A_Chase", 644), // 643 S_BSPI_RUN9
+    (b"BSPI", 4, 3, "A_Chase", 645), // 644 S_BSPI_RUN10
+    (b"BSPI", 5, 3, "A_Chase", 646), // 645 S_BSPI_RUN11
+    (b"BSPI", 5, 3, "A_Chase", 635), // 646 S_BSPI_RUN12
+    (b"BSPI", 32768, 20, "A_FaceTarget", 648), // 647 S_BSPI_ATK1
+    (b"BSPI", 32774, 4, "A_BspiAttack", 649), // 648 S_BSPI_ATK2
+    (b"BSPI", 32775, 4, "", 650), // 649 S_BSPI_ATK3
+    (b"BSPI", 32775, 1, "A_SpidRefire", 648), // 650 S_BSPI_ATK4
+    (b"BSPI", 8, 3, "", 652), // 651 S_BSPI_PAIN
+    (b"BSPI", 8, 3, "A_Pain", 635), // 652 S_BSPI_PAIN2
+    (b"BSPI", 9, 20, "A_Scream", 654), // 653 S_BSPI_DIE1
+    (b"BSPI", 10, 7, "A_Fall", 655), // 654 S_BSPI_DIE2
+    (b"BSPI", 11, 7, "", 656), // 655 S_BSPI_DIE3
+    (b"BSPI", 12, 7, "", 657), // 656 S_BSPI_DIE4
+    (b"BSPI", 13, 7, "", 658), // 657 S_BSPI_DIE5
+    (b"BSPI", 14, 7, "", 659), // 658 S_BSPI_DIE6
+    (b"BSPI", 15, -1, "A_BossDeath", 0), // 659 S_BSPI_DIE7
+    (b"BSPI", 15, 5, "", 661), // 660 S_BSPI_RAISE1
+    (b"BSPI", 14, 5, "", 662), // 661 S_BSPI_RAISE2
+    (b"BSPI", 13, 5, "", 663), // 662 S_BSPI_RAISE3
+    (b"BSPI", 12, 5, "", 664), // 663 S_BSPI_RAISE4
+    (b"BSPI", 11, 5, "", 665), // 664 S_BSPI_RAISE5
+    (b"BSPI", 10, 5, "", 666), // 665 S_BSPI_RAISE6
+    (b"BSPI", 9, 5, "", 635), // 666 S_BSPI_RAISE7
+    (b"APLS", 32768, 5, "", 668), // 667 S_ARACH_PLAZ
+    (b"APLS", 32769, 5, "", 667), // 668 S_ARACH_PLAZ2
+    (b"APBX", 32768, 5, "", 670), // 669 S_ARACH_PLEX
+    (b"APBX", 32769, 5, "", 671), // 670 S_ARACH_PLEX2
+    (b"APBX", 32770, 5, "", 672), // 671 S_ARACH_PLEX3
+    (b"APBX", 32771, 5, "", 673), // 672 S_ARACH_PLEX4
+    (b"APBX", 32772, 5, "", 0), // 673 S_ARACH_PLEX5
+    (b"CYBR", 0, 10, "A_Look", 675), // 674 S_CYBER_STND
+    (b"CYBR", 1, 10, "A_Look", 674), // 675 S_CYBER_STND2
+    (b"CYBR", 0, 3, "A_Hoof", 677), // 676 S_CYBER_RUN1
+    (b"CYBR", 0, 3, "A_Chase", 678), // 677 S_CYBER_RUN2
+    (b"CYBR", 1, 3, "A_Chase", 679), // 678 S_CYBER_RUN3
+    (b"CYBR", 1, 3, "A_Chase", 680), // 679 S_CYBER_RUN4
+    (b"CYBR", 2, 3, "A_Chase", 681), // 680 S_CYBER_RUN5
+    (b"CYBR", 2, 3, "A_Chase", 682), // 681 S_CYBER_RUN6
+    (b"CYBR", 3, 3, "A_Metal", 683), // 682 S_CYBER_RUN7
+    (b"CYBR", 3, 3, "A_Chase", 676), // 683 S_CYBER_RUN8
+    (b"CYBR", 4, 6, "A_FaceTarget", 685), // 684 S_CYBER_ATK1
+    (b"CYBR", 5, 12, "A_CyberAttack", 686), // 685 S_CYBER_ATK2
+    (b"CYBR", 4, 12, "A_FaceTarget", 687), // 686 S_CYBER_ATK3
+    (b"CYBR", 5, 12, "A_CyberAttack", 688), // 687 S_CYBER_ATK4
+    (b"CYBR", 4, 12, "A_FaceTarget", 689), // 688 S_CYBER_ATK5
+    (b"CYBR", 5, 12, "A_CyberAttack", 676), // 689 S_CYBER_ATK6
+    (b"CYBR", 6, 10, "A_Pain", 676), // 690 S_CYBER_PAIN
+    (b"CYBR", 7, 10, "", 692), // 691 S_CYBER_DIE1
+    (b"CYBR", 8, 10, "A_Scream", 693), // 692 S_CYBER_DIE2
+    (b"CYBR", 9, 10, "", 694), // 693 S_CYBER_DIE3
+    (b"CYBR", 10, 10, "", 695), // 694 S_CYBER_DIE4
+    (b"CYBR", 11, 10, "", 696), // 695 S_CYBER_DIE5
+    (b"CYBR", 12, 10, "A_Fall", 697), // 696 S_CYBER_DIE6
+    (b"CYBR", 13, 10, "", 698), // 697 S_CYBER_DIE7
+    (b"CYBR", 14, 10, "", 699), // 698 S_CYBER_DIE8
+    (b"CYBR", 15, 30, "", 700), // 699 S_CYBER_DIE9
+    (b"CYBR", 15, -1, "A_BossDeath", 0), // 700 S_CYBER_DIE10
+    (b"PAIN", 0, 10, "A_Look", 701), // 701 S_PAIN_STND
+    (b"PAIN", 0, 3, "A_Chase", 703), // 702 S_PAIN_RUN1
+    (b"PAIN", 0, 3, "A_Chase", 704), // 703 S_PAIN_RUN2
+    (b"PAIN", 1, 3, "A_Chase", 705), // 704 S_PAIN_RUN3
+    (b"PAIN", 1, 3, "A_Chase", 706), // 705 S_PAIN_RUN4
+    (b"PAIN", 2, 3, "A_Chase", 707), // 706 S_PAIN_RUN5
+    (b"PAIN", 2, 3, "A_Chase", 702), // 707 S_PAIN_RUN6
+    (b"PAIN", 3, 5, "A_FaceTarget", 709), // 708 S_PAIN_ATK1
+    (b"PAIN", 4, 5, "A_FaceTarget", 710), // 709 S_PAIN_ATK2
+    (b"PAIN", 32773, 5, "A_FaceTarget", 711), // 710 S_PAIN_ATK3
+    (b"PAIN", 32773, 0, "A_PainAttack", 702), // 711 S_PAIN_ATK4
+    (b"PAIN", 6, 6, "", 713), // 712 S_PAIN_PAIN
+    (b"PAIN", 6, 6, "A_Pain", 702), // 713 S_PAIN_PAIN2
+    (b"PAIN", 32775, 8, "", 715), // 714 S_PAIN_DIE1
+    (b"PAIN", 32776, 8, "A_Scream", 716), // 715 S_PAIN_DIE2
+    (b"PAIN", 32777, 8, "", 717), // 716 S_PAIN_DIE3
+    (b"PAIN", 32778, 8, "", 718), // 717 S_PAIN_DIE4
+    (b"PAIN", 32779, 8, "A_PainDie", 719), // 718 S_PAIN_DIE5
+    (b"PAIN", 32780, 8, "", 0), // 719 S_PAIN_DIE6
+    (b"PAIN", 12, 8, "", 721), // 720 S_PAIN_RAISE1
+    (b"PAIN", 11, 8, "", 722), // 721 S_PAIN_RAISE2
+    (b"PAIN", 10, 8, "", 723), // 722 S_PAIN_RAISE3
+    (b"PAIN", 9, 8, "", 724), // 723 S_PAIN_RAISE4
+    (b"PAIN", 8, 8, "", 725), // 724 S_PAIN_RAISE5
+    (b"PAIN", 7, 8, "", 702), // 725 S_PAIN_RAISE6
+    (b"SSWV", 0, 10, "A_Look", 727), // 726 S_SSWV_STND
+    (b"SSWV", 1, 10, "A_Look", 726), // 727 S_SSWV_STND2
+    (b"SSWV", 0, 3, "A_Chase", 729), // 728 S_SSWV_RUN1
+    (b"SSWV", 0, 3, "A_Chase", 730), // 729 S_SSWV_RUN2
+    (b"SSWV", 1, 3, "A_Chase", 731), // 730 S_SSWV_RUN3
+    (b"SSWV", 1, 3, "A_Chase", 732), // 731 S_SSWV_RUN4
+    (b"SSWV", 2, 3, "A_Chase", 733), // 732 S_SSWV_RUN5
+    (b"SSWV", 2, 3, "A_Chase", 734), // 733 S_SSWV_RUN6
+    (b"SSWV", 3, 3, "A_Chase", 735), // 734 S_SSWV_RUN7
+    (b"SSWV", 3, 3, "A_Chase", 728), // 735 S_SSWV_RUN8
+    (b"SSWV", 4, 10, "A_FaceTarget", 737), // 736 S_SSWV_ATK1
+    (b"SSWV", 5, 10, "A_FaceTarget", 738), // 737 S_SSWV_ATK2
+    (b"SSWV", 32774, 4, "A_CPosAttack", 739), // 738 S_SSWV_ATK3
+    (b"SSWV", 5, 6, "A_FaceTarget", 740), // 739 S_SSWV_ATK4
+    (b"SSWV", 32774, 4, "A_CPosAttack", 741), // 740 S_SSWV_ATK5
+    (b"SSWV", 5, 1, "A_CPosRefire", 737), // 741 S_SSWV_ATK6
+    (b"SSWV", 7, 3, "", 743), // 742 S_SSWV_PAIN
+    (b"SSWV", 7, 3, "A_Pain", 728), // 743 S_SSWV_PAIN2
+    (b"SSWV", 8, 5, "", 745), // 744 S_SSWV_DIE1
+    (b"SSWV", 9, 5, "A_Scream", 746), // 745 S_SSWV_DIE2
+    (b"SSWV", 10, 5, "A_Fall", 747), // 746 S_SSWV_DIE3
+    (b"SSWV", 11, 5, "", 748), // 747 S_SSWV_DIE4
+    (b"SSWV", 12, -1, "", 0), // 748 S_SSWV_DIE5
+    (b"SSWV", 13, 5, "", 750), // 749 S_SSWV_XDIE1
+    (b"SSWV", 14, 5, "A_XScream", 751), // 750 S_SSWV_XDIE2
+    (b"SSWV", 15, 5, "A_Fall", 752), // 751 S_SSWV_XDIE3
+    (b"SSWV", 16, 5, "", 753), // 752 S_SSWV_XDIE4
+    (b"SSWV", 17, 5, "", 754), // 753 S_SSWV_XDIE5
+    (b"SSWV", 18, 5, "", 755), // 754 S_SSWV_XDIE6
+    (b"SSWV", 19, 5, "", 756), // 755 S_SSWV_XDIE7
+    (b"SSWV", 20, 5, "", 757), // 756 S_SSWV_XDIE8
+    (b"SSWV", 21, -1, "", 0), // 757 S_SSWV_XDIE9
+    (b"SSWV", 12, 5, "", 759), // 758 S_SSWV_RAISE1
+    (b"SSWV", 11, 5, "", 760), // 759 S_SSWV_RAISE2
+    (b"SSWV", 10, 5, "", 761), // 760 S_SSWV_RAISE3
+    (b"SSWV", 9, 5, "", 762), // 761 S_SSWV_RAISE4
+    (b"SSWV", 8, 5, "", 728), // 762 S_SSWV_RAISE5
+    (b"KEEN", 0, -1, "", 763), // 763 S_KEENSTND
+    (b"KEEN", 0, 6, "", 765), // 764 S_COMMKEEN
+    (b"KEEN", 1, 6, "", 766), // 765 S_COMMKEEN2
+    (b"KEEN", 2, 6, "A_Scream", 767), // 766 S_COMMKEEN3
+    (b"KEEN", 3, 6, "", 768), // 767 S_COMMKEEN4
+    (b"KEEN", 4, 6, "", 769), // 768 S_COMMKEEN5
+    (b"KEEN", 5, 6, "", 770), // 769 S_COMMKEEN6
+    (b"KEEN", 6, 6, "", 771), // 770 S_COMMKEEN7
+    (b"KEEN", 7, 6, "", 772), // 771 S_COMMKEEN8
+    (b"KEEN", 8, 6, "", 773), // 772 S_COMMKEEN9
+    (b"KEEN", 9, 6, "", 774), // 773 S_COMMKEEN10
+    (b"KEEN", 10, 6, "A_KeenDie", 775), // 774 S_COMMKEEN11
+    (b"KEEN", 11, -1, "", 0), // 775 S_COMMKEEN12
+    (b"KEEN", 12, 4, "", 777), // 776 S_KEENPAIN
+    (b"KEEN", 12, 8, "A_Pain", 763), // 777 S_KEENPAIN2
+    (b"BBRN", 0, -1, "", 0), // 778 S_BRAIN
+    (b"BBRN", 1, 36, "A_BrainPain", 778), // 779 S_BRAIN_PAIN
+    (b"BBRN", 0, 100, "A_BrainScream", 781), // 780 S_BRAIN_DIE1
+    (b"BBRN", 0, 10, "", 782), // 781 S_BRAIN_DIE2
+    (b"BBRN", 0, 10, "", 783), // 782 S_BRAIN_DIE3
+    (b"BBRN", 0, -1, "A_BrainDie", 0), // 783 S_BRAIN_DIE4
+    (b"SSWV", 0, 10, "A_Look", 784), // 784 S_BRAINEYE
+    (b"SSWV", 0, 181, "A_BrainAwake", 786), // 785 S_BRAINEYESEE
+    (b"SSWV", 0, 150, "A_BrainSpit", 786), // 786 S_BRAINEYE1
+    (b"BOSF", 32768, 3, "A_SpawnSound", 788), // 787 S_SPAWN1
+    (b"BOSF", 32769, 3, "A_SpawnFly", 789), // 788 S_SPAWN2
+    (b"BOSF", 32770, 3, "A_SpawnFly", 790), // 789 S_SPAWN3
+    (b"BOSF", 32771, 3, "A_SpawnFly", 787), // 790 S_SPAWN4
+    (b"FIRE", 32768, 4, "A_Fire", 792), // 791 S_SPAWNFIRE1
+    (b"FIRE", 32769, 4, "A_Fire", 793), // 792 S_SPAWNFIRE2
+    (b"FIRE", 32770, 4, "A_Fire", 794), // 793 S_SPAWNFIRE3
+    (b"FIRE", 32771, 4, "A_Fire", 795), // 794 S_SPAWNFIRE4
+    (b"FIRE", 32772, 4, "A_Fire", 796), // 795 S_SPAWNFIRE5
+    (b"FIRE", 32773, 4, "A_Fire", 797), // 796 S_SPAWNFIRE6
+    (b"FIRE", 32774, 4, "A_Fire", 798), // 797 S_SPAWNFIRE7
+    (b"FIRE", 32775, 4, "A_Fire", 0), // 798 S_SPAWNFIRE8
+    (b"MISL", 32769, 10, "", 800), // 799 S_BRAINEXPLODE1
+    (b"MISL", 32770, 10, "", 801), // 800 S_BRAINEXPLODE2
+    (b"MISL", 32771, 10, "A_BrainExplode", 0), // 801 S_BRAINEXPLODE3
+    (b"ARM1", 0, 6, "", 803), // 802 S_ARM1
+    (b"ARM1", 32769, 7, "", 802), // 803 S_ARM1A
+    (b"ARM2", 0, 6, "", 805), // 804 S_ARM2
+    (b"ARM2", 32769, 6, "", 804), // 805 S_ARM2A
+    (b"BAR1", 0, 6, "", 807), // 806 S_BAR1
+    (b"BAR1", 1, 6, "", 806), // 807 S_BAR2
+    (b"BEXP", 32768, 5, "", 809), // 808 S_BEXP
+    (b"BEXP", 32769, 5, "A_Scream", 810), // 809 S_BEXP2
+    (b"BEXP", 32770, 5, "", 811), // 810 S_BEXP3
+    (b"BEXP", 32771, 10, "A_Explode", 812), // 811 S_BEXP4
+    (b"BEXP", 32772, 10, "", 0), // 812 S_BEXP5
+    (b"FCAN", 32768, 4, "", 814), // 813 S_BBAR1
+    (b"FCAN", 32769, 4, "", 815), // 814 S_BBAR2
+    (b"FCAN", 32770, 4, "", 813), // 815 S_BBAR3
+    (b"BON1", 0, 6, "", 817), // 816 S_BON1
+    (b"BON1", 1, 6, "", 818), // 817 S_BON1A
+    (b"BON1", 2, 6, "", 819), // 818 S_BON1B
+    (b"BON1", 3, 6, "", 820), // 819 S_BON1C
+    (b"BON1", 2, 6, "", 821), // 820 S_BON1D
+    (b"BON1", 1, 6, "", 816), // 821 S_BON1E
+    (b"BON2", 0, 6, "", 823), // 822 S_BON2
+    (b"BON2", 1, 6, "", 824), // 823 S_BON2A
+    (b"BON2", 2, 6, "", 825), // 824 S_BON2B
+    (b"BON2", 3, 6, "", 826), // 825 S_BON2C
+    (b"BON2", 2, 6, "", 827), // 826 S_BON2D
+    (b"BON2", 1, 6, "", 822), // 827 S_BON2E
+    (b"BKEY", 0, 10, "", 829), // 828 S_BKEY
+    (b"BKEY", 32769, 10, "", 828), // 829 S_BKEY2
+    (b"RKEY", 0, 10, "", 831), // 830 S_RKEY
+    (b"RKEY", 32769, 10, "", 830), // 831 S_RKEY2
+    (b"YKEY", 0, 10, "", 833), // 832 S_YKEY
+    (b"YKEY", 32769, 10, "", 832), // 833 S_YKEY2
+    (b"BSKU", 0, 10, "", 835), // 834 S_BSKULL
+    (b"BSKU", 32769, 10, "", 834), // 835 S_BSKULL2
+    (b"RSKU", 0, 10, "", 837), // 836 S_RSKULL
+    (b"RSKU", 32769, 10, "", 836), // 837 S_RSKULL2
+    (b"YSKU", 0, 10, "", 839), // 838 S_YSKULL
+    (b"YSKU", 32769, 10, "", 838), // 839 S_YSKULL2
+    (b"STIM", 0, -1, "", 0), // 840 S_STIM
+    (b"MEDI", 0, -1, "", 0), // 841 S_MEDI
+    (b"SOUL", 32768, 6, "", 843), // 842 S_SOUL
+    (b"SOUL", 32769, 6, "", 844), // 843 S_SOUL2
+    (b"SOUL", 32770, 6, "", 845), // 844 S_SOUL3
+    (b"SOUL", 32771, 6, "", 846), // 845 S_SOUL4
+    (b"SOUL", 32770, 6, "", 847), // 846 S_SOUL5
+    (b"SOUL", 32769, 6, "", 842), // 847 S_SOUL6
+    (b"PINV", 32768, 6, "", 849), // 848 S_PINV
+    (b"PINV", 32769, 6, "", 850), // 849 S_PINV2
+    (b"PINV", 32770, 6, "", 851), // 850 S_PINV3
+    (b"PINV", 32771, 6, "", 848), // 851 S_PINV4
+    (b"PSTR", 32768, -1, "", 0), // 852 S_PSTR
+    (b"PINS", 32768, 6, "", 854), // 853 S_PINS
+    (b"PINS", 32769, 6, "", 855), // 854 S_PINS2
+    (b"PINS", 32770, 6, "", 856), // 855 S_PINS3
+    (b"PINS", 32771, 6, "", 853), // 856 S_PINS4
+    (b"MEGA", 32768, 6, "", 858), // 857 S_MEGA
+    (b"MEGA", 32769, 6, "", 859), // 858 S_MEGA2
+    (b"MEGA", 32770, 6, "", 860), // 859 S_MEGA3
+    (b"MEGA", 32771, 6, "", 857), // 860 S_MEGA4
+    (b"SUIT", 32768, -1, "", 0), // 861 S_SUIT
+    (b"PMAP", 32768, 6, "", 863), // 862 S_PMAP
+    (b"PMAP", 32769, 6, "", 864), // 863 S_PMAP2
+    (b"PMAP", 32770, 6, "", 865), // 864 S_PMAP3
+    (b"PMAP", 32771, 6, "", 866), // 865 S_PMAP4
+    (b"PMAP", 32770, 6, "", 867), // 866 S_PMAP5
+    (b"PMAP", 32769, 6, "", 862), // 867 S_PMAP6
+    (b"PVIS", 32768, 6, "", 869), // 868 S_PVIS
+    (b"PVIS", 1, 6, "", 868), // 869 S_PVIS2
+    (b"CLIP", 0, -1, "", 0), // 870 S_CLIP
+    (b"AMMO", 0, -1, "", 0), // 871 S_AMMO
+    (b"ROCK", 0, -1, "", 0), // 872 S_ROCK
+    (b"BROK", 0, -1, "", 0), // 873 S_BROK
+    (b"CELL", 0, -1, "", 0), // 874 S_CELL
+    (b"CELP", 0, -1, "", 0), // 875 S_CELP
+    (b"SHEL", 0, -1, "", 0), // 876 S_SHEL
+    (b"SBOX", 0, -1, "", 0), // 877 S_SBOX
+    (b"BPAK", 0, -1, "", 0), // 878 S_BPAK
+    (b"BFUG", 0, -1, "", 0), // 879 S_BFUG
+    (b"MGUN", 0, -1, "", 0), // 880 S_MGUN
+    (b"CSAW", 0, -1, "", 0), // 881 S_CSAW
+    (b"LAUN", 0, -1, "", 0), // 882 S_LAUN
+    (b"PLAS", 0, -1, "", 0), // 883 S_PLAS
+    (b"SHOT", 0, -1, "", 0), // 884 S_SHOT
+    (b"SGN2", 0, -1, "", 0), // 885 S_SHOT2
+    (b"COLU", 32768, -1, "", 0), // 886 S_COLU
+    (b"SMT2", 0, -1, "", 0), // 887 S_STALAG
+    (b"GOR1", 0, 10, "", 889), // 888 S_BLOODYTWITCH
+    (b"GOR1", 1, 15, "", 890), // 889 S_BLOODYTWITCH2
+    (b"GOR1", 2, 8, "", 891), // 890 S_BLOODYTWITCH3
+    (b"GOR1", 1, 6, "", 888), // 891 S_BLOODYTWITCH4
+    (b"PLAY", 13, -1, "", 0), // 892 S_DEADTORSO
+    (b"PLAY", 18, -1, "", 0), // 893 S_DEADBOTTOM
+    (b"POL2", 0, -1, "", 0), // 894 S_HEADSONSTICK
+    (b"POL5", 0, -1, "", 0), // 895 S_GIBS
+    (b"POL4", 0, -1, "", 0), // 896 S_HEADONASTICK
+    (b"POL3", 32768, 6, "", 898), // 897 S_HEADCANDLES
+    (b"POL3", 32769, 6, "", 897), // 898 S_HEADCANDLES2
+    (b"POL1", 0, -1, "", 0), // 899 S_DEADSTICK
+    (b"POL6", 0, 6, "", 901), // 900 S_LIVESTICK
+    (b"POL6", 1, 8, "", 900), // 901 S_LIVESTICK2
+    (b"GOR2", 0, -1, "", 0), // 902 S_MEAT2
+    (b"GOR3", 0, -1, "", 0), // 903 S_MEAT3
+    (b"GOR4", 0, -1, "", 0), // 904 S_MEAT4
+    (b"GOR5", 0, -1, "", 0), // 905 S_MEAT5
+    (b"SMIT", 0, -1, "", 0), // 906 S_STALAGTITE
+    (b"COL1", 0, -1, "", 0), // 907 S_TALLGRNCOL
+    (b"COL2", 0, -1, "", 0), // 908 S_SHRTGRNCOL
+    (b"COL3", 0, -1, "", 0), // 909 S_TALLREDCOL
+    (b"COL4", 0, -1, "", 0), // 910 S_SHRTREDCOL
+    (b"CAND", 32768, -1, "", 0), // 911 S_CANDLESTIK
+    (b"CBRA", 32768, -1, "", 0), // 912 S_CANDELABRA
+    (b"COL6", 0, -1, "", 0), // 913 S_SKULLCOL
+    (b"TRE1", 0, -1, "", 0), // 914 S_TORCHTREE
+    (b"TRE2", 0, -1, "", 0), // 915 S_BIGTREE
+    (b"ELEC", 0, -1, "", 0), // 916 S_TECHPILLAR
+    (b"CEYE", 32768, 6, "", 918), // 917 S_EVILEYE
+    (b"CEYE", 32769, 6, "", 919), // 918 S_EVILEYE2
+    (b"CEYE", 32770, 6, "", 920), // 919 S_EVILEYE3
+    (b"CEYE", 32769, 6, "", 917), // 920 S_EVILEYE4
+    (b"FSKU", 32768, 6, "", 922), // 921 S_FLOATSKULL
+    (b"FSKU", 32769, 6, "", 923), // 922 S_FLOATSKULL2
+    (b"FSKU", 32770, 6, "", 921), // 923 S_FLOATSKULL3
+    (b"COL5", 0, 14, "", 925), // 924 S_HEARTCOL
+    (b"COL5", 1, 14, "", 924), // 925 S_HEARTCOL2
+    (b"TBLU", 32768, 4, "", 927), // 926 S_BLUETORCH
+    (b"TBLU", 32769, 4, "", 928), // 927 S_BLUETORCH2
+    (b"TBLU", 32770, 4, "", 929), // 928 S_BLUETORCH3
+    (b"TBLU", 32771, 4, "", 926), // 929 S_BLUETORCH4
+    (b"TGRN", 32768, 4, "", 931), // 930 S_GREENTORCH
+    (b"TGRN", 32769, 4, "", 932), // 931 S_GREENTORCH2
+    (b"TGRN", 32770, 4, "", 933), // 932 S_GREENTORCH3
+    (b"TGRN", 32771, 4, "", 930), // 933 S_GREENTORCH4
+    (b"TRED", 32768, 4, "", 935), // 934 S_REDTORCH
+    (b"TRED", 32769, 4, "", 936), // 935 S_REDTORCH2
+    (b"TRED", 32770, 4, "", 937), // 936 S_REDTORCH3
+    (b"TRED", 32771, 4, "", 934), // 937 S_REDTORCH4
+    (b"SMBT", 32768, 4, "", 939), // 938 S_BTORCHSHRT
+    (b"SMBT", 32769, 4, "", 940), // 939 S_BTORCHSHRT2
+    (b"SMBT", 32770, 4, "", 941), // 940 S_BTORCHSHRT3
+    (b"SMBT", 32771, 4, "", 938), // 941 S_BTORCHSHRT4
+    (b"SMGT", 32768, 4, "", 943), // 942 S_GTORCHSHRT
+    (b"SMGT", 32769, 4, "", 944), // 943 S_GTORCHSHRT2
+    (b"SMGT", 32770, 4, "", 945), // 944 S_GTORCHSHRT3
+    (b"SMGT", 32771, 4, "", 942), // 945 S_GTORCHSHRT4
+    (b"SMRT", 32768, 4, "", 947), // 946 S_RTORCHSHRT
+    (b"SMRT", 32769, 4, "", 948), // 947 S_RTORCHSHRT2
+    (b"SMRT", 32770, 4, "", 949), // 948 S_RTORCHSHRT3
+    (b"SMRT", 32771, 4, "", 946), // 949 S_RTORCHSHRT4
+    (b"HDB1", 0, -1, "", 0), // 950 S_HANGNOGUTS
+    (b"HDB2", 0, -1, "", 0), // 951 S_HANGBNOBRAIN
+    (b"HDB3", 0, -1, "", 0), // 952 S_HANGTLOOKDN
+    (b"HDB4", 0, -1, "", 0), // 953 S_HANGTSKULL
+    (b"HDB5", 0, -1, "", 0), // 954 S_HANGTLOOKUP
+    (b"HDB6", 0, -1, "", 0), // 955 S_HANGTNOBRAIN
+    (b"POB1", 0, -1, "", 0), // 956 S_COLONGIBS
+    (b"POB2", 0, -1, "", 0), // 957 S_SMALLPOOL
+    (b"BRS1", 0, -1, "", 0), // 958 S_BRAINSTEM
+    (b"TLMP", 32768, 4, "", 960), // 959 S_TECHLAMP
+    (b"TLMP", 32769, 4, "", 961), // 960 S_TECHLAMP2
+    (b"TLMP", 32770, 4, "", 962), // 961 S_TECHLAMP3
+    (b"TLMP", 32771, 4, "", 959), // 962 S_TECHLAMP4
+    (b"TLP2", 32768, 4, "", 964), // 963 S_TECH2LAMP
+    (b"TLP2", 32769, 4, "", 965), // 964 S_TECH2LAMP2
+    (b"TLP2", 32770, 4, "", 966), // 965 S_TECH2LAMP3
+    (b"TLP2", 32771, 4, "", 963), // 966 S_TECH2LAMP4
+];
+
+/// (name, doomednum, [spawn, see, pain, melee, missile, death, xdeath,
+/// raise] states)
+#[rustfmt::skip]
+pub static MOBJINFO: [MobjInfoDef; 137] = [
+    ("MT_PLAYER", -1, [149, 150, 156, 0, 154, 158, 165, 0]),
+    ("MT_POSSESSED", 3004, [174, 176, 187, 0, 184, 189, 194, 203]),
+    ("MT_SHOTGUY", 9, [207, 209, 220, 0, 217, 222, 227, 236]),
+    ("MT_VILE", 64, [241, 243, 269, 0, 255, 271, 0, 0]),
+    ("MT_FIRE", -1, [281, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_UNDEAD", 66, [321, 323, 343, 335, 339, 345, 0, 351]),
+    ("MT_TRACER", -1, [316, 0, 0, 0, 0, 318, 0, 0]),
+    ("MT_SMOKE", -1, [311, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_FATSO", 67, [362, 364, 386, 0, 376, 388, 0, 398]),
+    ("MT_FATSHOT", -1, [357, 0, 0, 0, 0, 359, 0, 0]),
+    ("MT_CHAINGUY", 65, [406, 408, 420, 0, 416, 422, 429, 435]),
+    ("MT_TROOP", 3001, [442, 444, 455, 452, 452, 457, 462, 470]),
+    ("MT_SERGEANT", 3002, [475, 477, 488, 485, 0, 490, 0, 496]),
+    ("MT_SHADOWS", 58, [475, 477, 488, 485, 0, 490, 0, 496]),
+    ("MT_HEAD", 3005, [502, 503, 507, 0, 504, 510, 0, 516]),
+    ("MT_BRUISER", 3003, [527, 529, 540, 537, 537, 542, 0, 549]),
+    ("MT_BRUISERSHOT", -1, [522, 0, 0, 0, 0, 524, 0, 0]),
+    ("MT_KNIGHT", 69, [556, 558, 569, 566, 566, 571, 0, 578]),
+    ("MT_SKULL", 3006, [585, 587, 593, 0, 589, 595, 0, 0]),
+    ("MT_SPIDER", 7, [601, 603, 619, 0, 615, 621, 0, 0]),
+    ("MT_BABY", 68, [632, 634, 651, 0, 647, 653, 0, 660]),
+    ("MT_CYBORG", 16, [674, 676, 690, 0, 684, 691, 0, 0]),
+    ("MT_PAIN", 71, [701, 702, 712, 0, 708, 714, 0, 720]),
+    ("MT_WOLFSS", 84, [726, 728, 742, 0, 736, 744, 749, 758]),
+    ("MT_KEEN", 72, [763, 0, 776, 0, 0, 764, 0, 0]),
+    ("MT_BOSSBRAIN", 88, [778, 0, 779, 0, 0, 780, 0, 0]),
+    ("MT_BOSSSPIT", 89, [784, 785, 0, 0, 0, 0, 0, 0]),
+    ("MT_BOSSTARGET", 87, [0, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_SPAWNSHOT", -1, [787, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_SPAWNFIRE", -1, [791, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_BARREL", 2035, [806, 0, 0, 0, 0, 808, 0, 0]),
+    ("MT_TROOPSHOT", -1, [97, 0, 0, 0, 0, 99, 0, 0]),
+    ("MT_HEADSHOT", -1, [102, 0, 0, 0, 0, 104, 0, 0]),
+    ("MT_ROCKET", -1, [114, 0, 0, 0, 0, 127, 0, 0]),
+    ("MT_PLASMA", -1, [107, 0, 0, 0, 0, 109, 0, 0]),
+    ("MT_BFG", -1, [115, 0, 0, 0, 0, 117, 0, 0]),
+    ("MT_ARACHPLAZ", -1, [667, 0, 0, 0, 0, 669, 0, 0]),
+    ("MT_PUFF", -1, [93, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_BLOOD", -1, [90, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_TFOG", -1, [130, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_IFOG", -1, [142, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_TELEPORTMAN", 14, [0, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_EXTRABFG", -1, [123, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC0", 2018, [802, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC1", 2019, [804, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC2", 2014, [816, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC3", 2015, [822, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC4", 5, [828, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC5", 13, [830, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC6", 6, [832, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC7", 39, [838, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC8", 38, [836, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC9", 40, [834, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC10", 2011, [840, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC11", 2012, [841, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC12", 2013, [842, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_INV", 2022, [848, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC13", 2023, [852, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_INS", 2024, [853, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC14", 2025, [861, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC15", 2026, [862, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC16", 2045, [868, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MEGA", 83, [857, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_CLIP", 2007, [870, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC17", 2048, [871, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC18", 2010, [872, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC19", 2046, [873, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC20", 2047, [874, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC21", 17, [875, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC22", 2008, [876, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC23", 2049, [877, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC24", 8, [878, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC25", 2006, [879, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_CHAINGUN", 2002, [880, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC26", 2005, [881, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC27", 2003, [882, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC28", 2004, [883, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_SHOTGUN", 2001, [884, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_SUPERSHOTGUN", 82, [885, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC29", 85, [959, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC30", 86, [963, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC31", 2028, [886, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC32", 30, [907, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC33", 31, [908, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC34", 32, [909, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC35", 33, [910, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC36", 37, [913, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC37", 36, [924, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC38", 41, [917, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC39", 42, [921, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC40", 43, [914, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC41", 44, [926, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC42", 45, [930, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC43", 46, [934, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC44", 55, [938, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC45", 56, [942, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC46", 57, [946, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC47", 47, [906, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC48", 48, [916, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC49", 34, [911, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC50", 35, [912, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC51", 49, [888, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC52", 50, [902, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC53", 51, [903, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC54", 52, [904, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC55", 53, [905, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC56", 59, [902, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC57", 60, [904, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC58", 61, [903, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC59", 62, [905, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC60", 63, [888, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC61", 22, [515, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC62", 15, [164, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC63", 18, [193, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC64", 21, [495, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC65", 23, [600, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC66", 20, [461, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC67", 19, [226, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC68", 10, [173, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC69", 12, [173, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC70", 28, [894, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC71", 24, [895, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC72", 27, [896, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC73", 29, [897, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC74", 25, [899, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC75", 26, [900, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC76", 54, [915, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC77", 70, [813, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC78", 73, [950, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC79", 74, [951, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC80", 75, [952, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC81", 76, [953, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC82", 77, [954, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC83", 78, [955, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC84", 79, [956, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC85", 80, [957, 0, 0, 0, 0, 0, 0, 0]),
+    ("MT_MISC86", 81, [958, 0, 0, 0, 0, 0, 0, 0]),
+];
+
+/// The thing types with MF_NOTDMATCH, which are the keys
+pub static NOT_DEATHMATCH: [&str; 6] = [
+    "MT_MISC4", "MT_MISC5", "MT_MISC6", "MT_MISC7", "MT_MISC8", "MT_MISC9",
+];
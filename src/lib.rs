@@ -1,10 +1,37 @@
+mod check;
+mod dehacked;
+mod detect;
 mod flat;
+mod info;
+mod map;
+mod namespace;
+mod quirks;
 mod sprite;
 mod sprite_canvas;
+mod sprite_def;
 mod texture;
+mod udmf;
+mod vanilla;
+mod wall;
 mod rangetools;
 
+#[cfg(test)]
+mod test_util;
+#[cfg(test)]
+mod test_wad;
+
+pub use check::*;
+pub use dehacked::*;
+pub use detect::*;
 pub use flat::*;
+pub use info::*;
+pub use map::*;
+pub use namespace::*;
+pub use quirks::*;
 pub use sprite::*;
 pub use sprite_canvas::*;
+pub use sprite_def::*;
 pub use texture::*;
+pub use udmf::*;
+pub use vanilla::*;
+pub use wall::*;
@@ -0,0 +1,971 @@
+use byteorder::{ByteOrder, LittleEndian};
+use std::convert::TryInto;
+use std::str::FromStr;
+use wad::EntryId;
+
+use crate::{Namespace, ResourceIndex, ThingInfo, UdmfError, MF_NOTDMATCH};
+
+/// The lumps that follow the marker lump of a map in the Doom format, in
+/// the order the engine expects them. Maps in the Hexen format have a
+/// BEHAVIOR lump, and possibly a SCRIPTS lump, after these.
+pub const MAP_LUMPS: [&[u8; 8]; 10] = [
+    b"THINGS\0\0",
+    b"LINEDEFS",
+    b"SIDEDEFS",
+    b"VERTEXES",
+    b"SEGS\0\0\0\0",
+    b"SSECTORS",
+    b"NODES\0\0\0",
+    b"SECTORS\0",
+    b"REJECT\0\0",
+    b"BLOCKMAP",
+];
+
+/// Blocks players and monsters
+pub const LINEDEF_BLOCKING: u16 = 0x0001;
+/// Blocks monsters only
+pub const LINEDEF_BLOCK_MONSTERS: u16 = 0x0002;
+/// The line has a sector on both sides
+pub const LINEDEF_TWO_SIDED: u16 = 0x0004;
+pub const LINEDEF_UPPER_UNPEGGED: u16 = 0x0008;
+pub const LINEDEF_LOWER_UNPEGGED: u16 = 0x0010;
+/// Drawn as a one-sided wall on the automap
+pub const LINEDEF_SECRET: u16 = 0x0020;
+/// Blocks sound propagation
+pub const LINEDEF_BLOCK_SOUND: u16 = 0x0040;
+/// Never drawn on the automap
+pub const LINEDEF_DONT_DRAW: u16 = 0x0080;
+/// Already drawn on the automap when the level starts
+pub const LINEDEF_MAPPED: u16 = 0x0100;
+
+/// The linedef special of teleporters, which the automap draws in a color
+/// of its own
+pub const SPECIAL_TELEPORT: u16 = 39;
+
+/// The linedef specials of teleporters in maps with Hexen-style specials:
+/// Teleport and Teleport_NoFog
+pub const HEXEN_SPECIALS_TELEPORT: [u16; 2] = [70, 71];
+
+/// Hexen: the thing appears in single player games
+const HEXEN_THING_SINGLE: u16 = 0x0100;
+/// Hexen: the thing appears in cooperative games
+const HEXEN_THING_COOP: u16 = 0x0200;
+/// Hexen: the thing appears in deathmatch
+const HEXEN_THING_DEATHMATCH: u16 = 0x0400;
+
+/// The thing appears on skill levels 1 and 2
+pub const THING_EASY: u16 = 0x0001;
+/// The thing appears on skill level 3
+pub const THING_NORMAL: u16 = 0x0002;
+/// The thing appears on skill levels 4 and 5
+pub const THING_HARD: u16 = 0x0004;
+/// The monster waits for the player to come into sight
+pub const THING_AMBUSH: u16 = 0x0008;
+/// The thing only appears in multiplayer games
+pub const THING_NOT_SINGLE: u16 = 0x0010;
+/// Boom: the thing does not appear in deathmatch
+pub const THING_NOT_DEATHMATCH: u16 = 0x0020;
+/// Boom: the thing does not appear in cooperative games
+pub const THING_NOT_COOP: u16 = 0x0040;
+/// When set, as some old editors do, Boom ignores its own flags
+const THING_RESERVED: u16 = 0x0100;
+
+/// The thing number of the deathmatch starts
+pub const DEATHMATCH_START: u16 = 11;
+
+/// The value of a sidedef number that means there is no sidedef
+const NO_SIDEDEF: u16 = 0xffff;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Vertex {
+    pub x: i16,
+    pub y: i16,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Linedef {
+    pub v1: u16,
+    pub v2: u16,
+    pub flags: u16,
+    pub special: u16,
+    pub tag: u16,
+    /// The arguments of the special, in the Hexen format and UDMF
+    pub args: [i32; 5],
+    /// The sidedef to the right of the line, seen from v1 towards v2
+    pub front: Option<u16>,
+    /// The sidedef to the left of the line, for two-sided lines
+    pub back: Option<u16>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Sidedef {
+    pub x_offset: i16,
+    pub y_offset: i16,
+    pub upper: [u8; 8],
+    pub lower: [u8; 8],
+    pub middle: [u8; 8],
+    pub sector: u16,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Sector {
+    pub floor_height: i16,
+    pub ceiling_height: i16,
+    pub floor_flat: [u8; 8],
+    pub ceiling_flat: [u8; 8],
+    pub light: i16,
+    pub special: u16,
+    pub tag: u16,
+}
+
+impl Sector {
+    /// The row of COLORMAP for the light level of the sector, as seen from
+    /// above. Light levels map linearly to the 32 light colormaps, like in
+    /// the map editors.
+    pub fn colormap(&self) -> usize {
+        (255 - self.light.clamp(0, 255) as usize) / 8
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Thing {
+    /// The thing ID of the Hexen format and UDMF, used by scripts
+    pub tid: i32,
+    pub x: i16,
+    pub y: i16,
+    /// The height above the floor, in the Hexen format and UDMF
+    pub z: i16,
+    /// The direction the thing faces, in degrees counterclockwise from east
+    pub angle: i16,
+    pub doomednum: u16,
+    /// The flags of the Doom format. The flags of other formats are
+    /// translated to these, as far as they go.
+    pub flags: u16,
+    /// The special run when the thing is killed or picked up, in the Hexen
+    /// format and UDMF
+    pub special: u16,
+    pub args: [i32; 5],
+}
+
+/// The kinds of game that decide which things appear in a map
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GameMode {
+    Single,
+    Coop,
+    Deathmatch,
+}
+
+impl FromStr for GameMode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<GameMode, &'static str> {
+        match s {
+            "single" | "s" => Ok(GameMode::Single),
+            "coop" | "c" => Ok(GameMode::Coop),
+            "deathmatch" | "dm" => Ok(GameMode::Deathmatch),
+            _ => Err("game mode must be 'single'/'s', 'coop'/'c' or 'deathmatch'/'dm'"),
+        }
+    }
+}
+
+impl Thing {
+    /// The player number, 1-4, for player starts
+    pub fn player_start(&self) -> Option<u8> {
+        match self.doomednum {
+            1..=4 => Some(self.doomednum as u8),
+            _ => None,
+        }
+    }
+
+    /// Whether the thing appears on the given skill level, 1-5, and in the
+    /// given kind of game, like in P_SpawnMapThing. None means any. Player
+    /// starts are taken to appear when the player is in the game, and
+    /// deathmatch starts in deathmatch. Thing types with MF_NOTDMATCH in
+    /// `info`, like the keys, do not appear in deathmatch.
+    pub fn appears(&self, skill: Option<u8>, mode: Option<GameMode>, info: &ThingInfo) -> bool {
+        if let Some(skill) = skill {
+            let bit = match skill {
+                0..=2 => THING_EASY,
+                3 => THING_NORMAL,
+                _ => THING_HARD,
+            };
+            // Player starts appear regardless of skill
+            if self.player_start().is_none() && self.flags & bit == 0 {
+                return false;
+            }
+        }
+
+        let mode = match mode {
+            Some(mode) => mode,
+            None => return true,
+        };
+        let boom_flags = self.flags & THING_RESERVED == 0;
+        match mode {
+            GameMode::Single => {
+                self.flags & THING_NOT_SINGLE == 0
+                    && self.doomednum != DEATHMATCH_START
+                    && self.player_start().is_none_or(|x| x == 1)
+            }
+            GameMode::Coop => {
+                !(boom_flags && self.flags & THING_NOT_COOP != 0)
+                    && self.doomednum != DEATHMATCH_START
+            }
+            GameMode::Deathmatch => {
+                let not_deathmatch = info
+                    .by_doomednum(self.doomednum as i32)
+                    .is_some_and(|x| info.mobjinfo[x].flags & MF_NOTDMATCH != 0);
+                !(boom_flags && self.flags & THING_NOT_DEATHMATCH != 0)
+                    && self.player_start().is_none()
+                    && !not_deathmatch
+            }
+        }
+    }
+}
+
+/// The kind of lumps a map is stored in
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MapFormat {
+    Doom,
+    Hexen,
+    /// UDMF, with the namespace given in the TEXTMAP lump, eg zdoom
+    Udmf(String),
+}
+
+impl MapFormat {
+    /// Whether linedef and thing specials are the ones of Hexen, as in the
+    /// Hexen format and most UDMF namespaces, rather than those of Doom
+    pub fn hexen_specials(&self) -> bool {
+        match self {
+            MapFormat::Doom => false,
+            MapFormat::Hexen => true,
+            MapFormat::Udmf(namespace) => {
+                !matches!(namespace.as_str(), "doom" | "heretic" | "strife")
+            }
+        }
+    }
+}
+
+/// What went wrong when loading a map
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MapError {
+    Invalid(&'static str),
+    Udmf(UdmfError),
+}
+
+impl std::fmt::Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MapError::Invalid(message) => write!(f, "{}", message),
+            MapError::Udmf(err) => write!(f, "TEXTMAP {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<&'static str> for MapError {
+    fn from(message: &'static str) -> MapError {
+        MapError::Invalid(message)
+    }
+}
+
+impl From<UdmfError> for MapError {
+    fn from(err: UdmfError) -> MapError {
+        MapError::Udmf(err)
+    }
+}
+
+fn i16_at(data: &[u8], offset: usize) -> i16 {
+    LittleEndian::read_i16(&data[offset..offset + 2])
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+    LittleEndian::read_u16(&data[offset..offset + 2])
+}
+
+fn name_at(data: &[u8], offset: usize) -> [u8; 8] {
+    data[offset..offset + 8].try_into().unwrap()
+}
+
+fn side_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16_at(data, offset)).filter(|&x| x != NO_SIDEDEF)
+}
+
+/// Parse a VERTEXES lump. Trailing bytes that do not make up a whole
+/// record are ignored, like the engine does.
+pub fn parse_vertexes(data: &[u8]) -> Vec<Vertex> {
+    data.chunks_exact(4)
+        .map(|x| Vertex {
+            x: i16_at(x, 0),
+            y: i16_at(x, 2),
+        })
+        .collect()
+}
+
+/// Parse a LINEDEFS lump in the Doom format
+pub fn parse_linedefs(data: &[u8]) -> Vec<Linedef> {
+    data.chunks_exact(14)
+        .map(|x| Linedef {
+            v1: u16_at(x, 0),
+            v2: u16_at(x, 2),
+            flags: u16_at(x, 4),
+            special: u16_at(x, 6),
+            tag: u16_at(x, 8),
+            args: [0; 5],
+            front: side_at(x, 10),
+            back: side_at(x, 12),
+        })
+        .collect()
+}
+
+fn args_at(data: &[u8], offset: usize) -> [i32; 5] {
+    let mut args = [0; 5];
+    for (arg, &x) in args.iter_mut().zip(&data[offset..offset + 5]) {
+        *arg = x as i32;
+    }
+    args
+}
+
+/// Parse a LINEDEFS lump in the Hexen format, where the special has
+/// arguments instead of a tag
+pub fn parse_hexen_linedefs(data: &[u8]) -> Vec<Linedef> {
+    data.chunks_exact(16)
+        .map(|x| Linedef {
+            v1: u16_at(x, 0),
+            v2: u16_at(x, 2),
+            flags: u16_at(x, 4),
+            special: x[6] as u16,
+            tag: 0,
+            args: args_at(x, 7),
+            front: side_at(x, 12),
+            back: side_at(x, 14),
+        })
+        .collect()
+}
+
+/// Parse a SIDEDEFS lump
+pub fn parse_sidedefs(data: &[u8]) -> Vec<Sidedef> {
+    data.chunks_exact(30)
+        .map(|x| Sidedef {
+            x_offset: i16_at(x, 0),
+            y_offset: i16_at(x, 2),
+            upper: name_at(x, 4),
+            lower: name_at(x, 12),
+            middle: name_at(x, 20),
+            sector: u16_at(x, 28),
+        })
+        .collect()
+}
+
+/// Parse a SECTORS lump
+pub fn parse_sectors(data: &[u8]) -> Vec<Sector> {
+    data.chunks_exact(26)
+        .map(|x| Sector {
+            floor_height: i16_at(x, 0),
+            ceiling_height: i16_at(x, 2),
+            floor_flat: name_at(x, 4),
+            ceiling_flat: name_at(x, 12),
+            light: i16_at(x, 20),
+            special: u16_at(x, 22),
+            tag: u16_at(x, 24),
+        })
+        .collect()
+}
+
+/// Parse a THINGS lump in the Doom format
+pub fn parse_things(data: &[u8]) -> Vec<Thing> {
+    data.chunks_exact(10)
+        .map(|x| Thing {
+            tid: 0,
+            x: i16_at(x, 0),
+            y: i16_at(x, 2),
+            z: 0,
+            angle: i16_at(x, 4),
+            doomednum: u16_at(x, 6),
+            flags: u16_at(x, 8),
+            special: 0,
+            args: [0; 5],
+        })
+        .collect()
+}
+
+/// Translate the flags of a thing in the Hexen format to those of the Doom
+/// format. Hexen has flags for the kinds of game a thing appears in, where
+/// Doom and Boom have flags for where it does not. The flags for the
+/// player classes of Hexen are dropped.
+fn hexen_thing_flags(flags: u16) -> u16 {
+    let mut doom_flags = flags & (THING_EASY | THING_NORMAL | THING_HARD | THING_AMBUSH);
+    if flags & HEXEN_THING_SINGLE == 0 {
+        doom_flags |= THING_NOT_SINGLE;
+    }
+    if flags & HEXEN_THING_COOP == 0 {
+        doom_flags |= THING_NOT_COOP;
+    }
+    if flags & HEXEN_THING_DEATHMATCH == 0 {
+        doom_flags |= THING_NOT_DEATHMATCH;
+    }
+    doom_flags
+}
+
+/// Parse a THINGS lump in the Hexen format, which adds a thing ID, a
+/// height and a special with arguments
+pub fn parse_hexen_things(data: &[u8]) -> Vec<Thing> {
+    data.chunks_exact(20)
+        .map(|x| Thing {
+            tid: i16_at(x, 0) as i32,
+            x: i16_at(x, 2),
+            y: i16_at(x, 4),
+            z: i16_at(x, 6),
+            angle: i16_at(x, 8),
+            doomednum: u16_at(x, 10),
+            flags: hexen_thing_flags(u16_at(x, 12)),
+            special: x[14] as u16,
+            args: args_at(x, 15),
+        })
+        .collect()
+}
+
+/// How the vanilla automap draws a line when all of the map is revealed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AutomapLine {
+    /// One-sided walls
+    Wall,
+    /// Lines with the secret flag, which the automap draws like walls
+    Secret,
+    Teleporter,
+    /// Two-sided lines between sectors with different floor heights
+    FloorChange,
+    /// Two-sided lines between sectors with different ceiling heights, but
+    /// the same floor height, like the sides of most doors
+    CeilingChange,
+    /// Two-sided lines without any height change
+    TwoSided,
+}
+
+impl AutomapLine {
+    /// The palette index the automap draws the line with. As the automap
+    /// draws secret lines like walls, they are instead given the color of
+    /// the Boom automap, so they stand out.
+    pub fn color(self) -> u8 {
+        match self {
+            AutomapLine::Wall => 176,
+            AutomapLine::Secret => 252,
+            AutomapLine::Teleporter => 184,
+            AutomapLine::FloorChange => 64,
+            AutomapLine::CeilingChange => 231,
+            AutomapLine::TwoSided => 96,
+        }
+    }
+}
+
+/// The names of the maps in the WADs, in load order: the global lumps that are
+/// followed by the lumps of a map in the same WAD. Maps replaced by a later
+/// WAD are listed once.
+pub fn map_names(index: &ResourceIndex) -> Vec<String> {
+    let mut names = vec![];
+    for i in 0..index.len().saturating_sub(1) {
+        let next = index.id(i + 1);
+        let next = next.as_bytes();
+        if index.namespace(i) == Some(Namespace::Global)
+            && index.wad_of(i + 1) == index.wad_of(i)
+            && (next == MAP_LUMPS[0] || next == b"TEXTMAP\0")
+        {
+            let name = index.id(i).to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// The geometry and things of a map. Maps in the Hexen format and UDMF are
+/// loaded into the same structures as those in the Doom format. References
+/// between the parts have been checked, so they can be followed without
+/// panicking.
+pub struct Map {
+    pub name: String,
+    pub format: MapFormat,
+    pub vertexes: Vec<Vertex>,
+    pub linedefs: Vec<Linedef>,
+    pub sidedefs: Vec<Sidedef>,
+    pub sectors: Vec<Sector>,
+    pub things: Vec<Thing>,
+}
+
+impl Map {
+    /// Parse a map in the Doom format from its lumps
+    pub fn new(
+        name: String,
+        vertexes: &[u8],
+        linedefs: &[u8],
+        sidedefs: &[u8],
+        sectors: &[u8],
+        things: &[u8],
+    ) -> Result<Map, &'static str> {
+        Map {
+            name,
+            format: MapFormat::Doom,
+            vertexes: parse_vertexes(vertexes),
+            linedefs: parse_linedefs(linedefs),
+            sidedefs: parse_sidedefs(sidedefs),
+            sectors: parse_sectors(sectors),
+            things: parse_things(things),
+        }
+        .validate()
+    }
+
+    /// Parse a map in the Hexen format from its lumps
+    pub fn hexen(
+        name: String,
+        vertexes: &[u8],
+        linedefs: &[u8],
+        sidedefs: &[u8],
+        sectors: &[u8],
+        things: &[u8],
+    ) -> Result<Map, &'static str> {
+        Map {
+            name,
+            format: MapFormat::Hexen,
+            vertexes: parse_vertexes(vertexes),
+            linedefs: parse_hexen_linedefs(linedefs),
+            sidedefs: parse_sidedefs(sidedefs),
+            sectors: parse_sectors(sectors),
+            things: parse_hexen_things(things),
+        }
+        .validate()
+    }
+
+    /// Check the references between the parts of the map
+    pub(crate) fn validate(self) -> Result<Map, &'static str> {
+        let map = self;
+        let vertex_count = map.vertexes.len();
+        let sidedef_count = map.sidedefs.len();
+        for linedef in &map.linedefs {
+            if linedef.v1 as usize >= vertex_count || linedef.v2 as usize >= vertex_count {
+                return Err("a linedef refers to a missing vertex");
+            }
+            if linedef.front.is_none() {
+                return Err("a linedef has no front sidedef");
+            }
+            let sides = linedef.front.iter().chain(linedef.back.iter());
+            if sides.copied().any(|x| x as usize >= sidedef_count) {
+                return Err("a linedef refers to a missing sidedef");
+            }
+        }
+        if map
+            .sidedefs
+            .iter()
+            .any(|x| x.sector as usize >= map.sectors.len())
+        {
+            return Err("a sidedef refers to a missing sector");
+        }
+
+        Ok(map)
+    }
+
+    /// Load a map by the name of its marker lump, like E1M1 or MAP01. As in
+    /// the engine, the last map of that name wins, and its lumps are the
+    /// ones that follow the marker. The format is detected from the lumps:
+    /// a TEXTMAP lump for UDMF, or a BEHAVIOR lump for the Hexen format.
+    pub fn from_index(index: &ResourceIndex, name: &str) -> Result<Map, MapError> {
+        let id = EntryId::from_str(name).ok_or("invalid map name")?;
+        let marker = index
+            .find_index(EntryId::from_bytes(id.as_bytes()), &[Namespace::Global])
+            .ok_or("map not found")?;
+        let name = id.to_string();
+
+        let in_map = |i: usize| i < index.len() && index.wad_of(i) == index.wad_of(marker);
+        if in_map(marker + 1) && index.id(marker + 1).as_bytes() == b"TEXTMAP\0" {
+            let textmap = index
+                .entry(marker + 1)
+                .map_err(|_| "invalid TEXTMAP lump")?;
+            // Editors write names and comments in legacy encodings, so those
+            // bytes are replaced rather than rejecting the map
+            let text = String::from_utf8_lossy(textmap.lump);
+            return Ok(Map::from_udmf(name, &text)?);
+        }
+
+        let lumps: Vec<usize> = (marker + 1..)
+            .take_while(|&i| in_map(i))
+            .take_while(|&i| {
+                let id = index.id(i);
+                let id = id.as_bytes();
+                MAP_LUMPS.contains(&id) || id == b"BEHAVIOR" || id == b"SCRIPTS\0"
+            })
+            .collect();
+        let find = |lump_name: &[u8; 8]| {
+            lumps
+                .iter()
+                .copied()
+                .find(|&i| index.id(i).as_bytes() == lump_name)
+        };
+        let lump = |lump_name: &[u8; 8]| -> Result<&[u8], &'static str> {
+            find(lump_name)
+                .and_then(|i| index.entry(i).ok())
+                .map(|x| x.lump)
+                .ok_or("the map is missing a lump")
+        };
+
+        let parse = match find(b"BEHAVIOR") {
+            Some(_) => Map::hexen,
+            None => Map::new,
+        };
+        Ok(parse(
+            name,
+            lump(b"VERTEXES")?,
+            lump(b"LINEDEFS")?,
+            lump(b"SIDEDEFS")?,
+            lump(b"SECTORS\0")?,
+            lump(b"THINGS\0\0")?,
+        )?)
+    }
+
+    /// The names of the middle textures of two-sided lines, which the
+    /// engine draws as masked mid-textures
+    pub fn masked_mid_textures(&self) -> impl Iterator<Item = [u8; 8]> + '_ {
+        self.linedefs
+            .iter()
+            .filter(|x| x.back.is_some())
+            .flat_map(move |x| x.front.iter().chain(x.back.iter()))
+            .map(move |&side| self.sidedefs[side as usize].middle)
+    }
+
+    pub fn vertex(&self, index: u16) -> Vertex {
+        self.vertexes[index as usize]
+    }
+
+    /// The sector on the front side of a linedef
+    pub fn front_sector(&self, linedef: &Linedef) -> &Sector {
+        let side = &self.sidedefs[linedef.front.unwrap() as usize];
+        &self.sectors[side.sector as usize]
+    }
+
+    /// The sector on the back side of a linedef, if it has one
+    pub fn back_sector(&self, linedef: &Linedef) -> Option<&Sector> {
+        let side = &self.sidedefs[linedef.back? as usize];
+        Some(&self.sectors[side.sector as usize])
+    }
+
+    /// The bounding box of the vertexes as ((min x, min y), (max x, max y))
+    pub fn bounds(&self) -> Option<((i16, i16), (i16, i16))> {
+        let first = self.vertexes.first()?;
+        Some(self.vertexes.iter().fold(
+            ((first.x, first.y), (first.x, first.y)),
+            |((x0, y0), (x1, y1)), v| ((x0.min(v.x), y0.min(v.y)), (x1.max(v.x), y1.max(v.y))),
+        ))
+    }
+
+    /// The outline of each sector, as loops of vertex numbers that end with
+    /// their first vertex. A sector with holes, or several disjoint parts,
+    /// has several loops. Lines that face the wrong way, as happens in
+    /// broken maps, are followed all the same, and lines that cannot be
+    /// closed into a loop end up in open chains. Either way, the even-odd
+    /// rule over the edges of the chains gives the area of the sector.
+    pub fn sector_polygons(&self) -> Vec<Vec<Vec<u16>>> {
+        // The edges of each sector, directed so the sector is on the right,
+        // like the front side of a linedef
+        let mut edges = vec![vec![]; self.sectors.len()];
+        for linedef in &self.linedefs {
+            let front = self.sidedefs[linedef.front.unwrap() as usize].sector;
+            edges[front as usize].push((linedef.v1, linedef.v2));
+            if let Some(back) = linedef.back {
+                let back = self.sidedefs[back as usize].sector;
+                edges[back as usize].push((linedef.v2, linedef.v1));
+            }
+        }
+
+        edges
+            .into_iter()
+            .map(|mut edges| {
+                let mut loops = vec![];
+                while let Some((start, mut end)) = edges.pop() {
+                    let mut vertexes = vec![start, end];
+                    while end != start {
+                        let forward = edges.iter().position(|&(v1, _)| v1 == end);
+                        let next = forward.or_else(|| edges.iter().position(|&(_, v2)| v2 == end));
+                        match next {
+                            Some(next) => {
+                                let (v1, v2) = edges.swap_remove(next);
+                                end = if v1 == end { v2 } else { v1 };
+                                vertexes.push(end);
+                            }
+                            None => break,
+                        }
+                    }
+                    loops.push(vertexes);
+                }
+                loops
+            })
+            .collect()
+    }
+
+    /// Classify a linedef the way AM_drawWalls does with the full map
+    /// revealed
+    pub fn automap_line(&self, linedef: &Linedef) -> AutomapLine {
+        let back = match self.back_sector(linedef) {
+            Some(back) => back,
+            None => return AutomapLine::Wall,
+        };
+        let front = self.front_sector(linedef);
+
+        let teleport = if self.format.hexen_specials() {
+            HEXEN_SPECIALS_TELEPORT.contains(&linedef.special)
+        } else {
+            linedef.special == SPECIAL_TELEPORT
+        };
+        if teleport {
+            AutomapLine::Teleporter
+        } else if linedef.flags & LINEDEF_SECRET != 0 {
+            AutomapLine::Secret
+        } else if front.floor_height != back.floor_height {
+            AutomapLine::FloorChange
+        } else if front.ceiling_height != back.ceiling_height {
+            AutomapLine::CeilingChange
+        } else {
+            AutomapLine::TwoSided
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sector(floor: i16, ceiling: i16) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(&floor.to_le_bytes());
+        data.extend(&ceiling.to_le_bytes());
+        data.extend(b"FLOOR0_1CEIL1_1\0");
+        data.extend(&160i16.to_le_bytes());
+        data.extend(&[0; 4]);
+        data
+    }
+
+    fn sidedef(sector: u16) -> Vec<u8> {
+        let mut data = vec![0; 28];
+        data.extend(&sector.to_le_bytes());
+        data
+    }
+
+    fn linedef(v1: u16, v2: u16, flags: u16, special: u16, front: u16, back: u16) -> Vec<u8> {
+        [v1, v2, flags, special, 0, front, back]
+            .iter()
+            .flat_map(|x| x.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    fn test_map(linedefs: &[Vec<u8>]) -> Result<Map, &'static str> {
+        let vertexes: Vec<u8> = [(0i16, 0i16), (64, 0), (64, 128), (-32, 128)]
+            .iter()
+            .flat_map(|(x, y)| [x.to_le_bytes(), y.to_le_bytes()].concat())
+            .collect();
+        let sidedefs = [sidedef(0), sidedef(1), sidedef(2)].concat();
+        let sectors = [sector(0, 128), sector(0, 0), sector(24, 128)].concat();
+        Map::new(
+            "E1M1".into(),
+            &vertexes,
+            &linedefs.concat(),
+            &sidedefs,
+            &sectors,
+            &[],
+        )
+    }
+
+    #[test]
+    fn masked_mid_textures() {
+        let mut sidedefs = vec![];
+        let sides = [(0, b"-\0\0\0\0\0\0\0"), (1, b"MIDGRATE"), (0, b"STARTAN3")];
+        for &(sector, middle) in sides.iter() {
+            let mut side = sidedef(sector);
+            side[20..28].copy_from_slice(middle);
+            sidedefs.extend(side);
+        }
+        let vertexes = [0; 8];
+        let linedefs = [
+            linedef(0, 1, LINEDEF_TWO_SIDED, 0, 0, 1),
+            linedef(1, 0, 1, 0, 2, 0xffff),
+        ];
+        let sectors = [sector(0, 128), sector(0, 128)].concat();
+        let map = Map::new(
+            "E1M1".into(),
+            &vertexes,
+            &linedefs.concat(),
+            &sidedefs,
+            &sectors,
+            &[],
+        )
+        .unwrap();
+
+        let names: Vec<_> = map.masked_mid_textures().collect();
+        assert_eq!(names, [*b"-\0\0\0\0\0\0\0", *b"MIDGRATE"]);
+    }
+
+    #[test]
+    fn parse_records() {
+        let data = [16, 0, 0xf0, 0xff, 90, 0, 0xb9, 0x0b, 7, 0, 0xff];
+        let things = parse_things(&data);
+        assert_eq!(
+            things,
+            [Thing {
+                tid: 0,
+                x: 16,
+                y: -16,
+                z: 0,
+                angle: 90,
+                doomednum: 3001,
+                flags: 7,
+                special: 0,
+                args: [0; 5],
+            }]
+        );
+
+        let linedefs = parse_linedefs(&linedef(0, 1, 1, 0, 0, 0xffff));
+        assert_eq!(linedefs[0].front, Some(0));
+        assert_eq!(linedefs[0].back, None);
+    }
+
+    #[test]
+    fn automap_lines() {
+        let map = test_map(&[
+            linedef(0, 1, LINEDEF_BLOCKING, 0, 0, 0xffff),
+            linedef(1, 2, LINEDEF_TWO_SIDED, 0, 0, 1),
+            linedef(2, 3, LINEDEF_TWO_SIDED, 0, 0, 2),
+            linedef(3, 0, LINEDEF_TWO_SIDED | LINEDEF_SECRET, 0, 0, 1),
+            linedef(0, 2, LINEDEF_TWO_SIDED, SPECIAL_TELEPORT, 0, 2),
+            linedef(1, 3, LINEDEF_TWO_SIDED, 0, 0, 0),
+        ])
+        .unwrap();
+
+        let kinds: Vec<_> = map.linedefs.iter().map(|x| map.automap_line(x)).collect();
+        assert_eq!(
+            kinds,
+            [
+                AutomapLine::Wall,
+                AutomapLine::CeilingChange,
+                AutomapLine::FloorChange,
+                AutomapLine::Secret,
+                AutomapLine::Teleporter,
+                AutomapLine::TwoSided,
+            ]
+        );
+        assert_eq!(map.bounds(), Some(((-32, 0), (64, 128))));
+    }
+
+    #[test]
+    fn polygons() {
+        // A square sector 0 with sector 2 in the corner at vertex 2,
+        // separated by the line from 1 to 3
+        let map = test_map(&[
+            linedef(0, 1, LINEDEF_BLOCKING, 0, 0, 0xffff),
+            linedef(1, 3, LINEDEF_TWO_SIDED, 0, 0, 2),
+            linedef(3, 0, LINEDEF_BLOCKING, 0, 0, 0xffff),
+            linedef(1, 2, LINEDEF_BLOCKING, 0, 2, 0xffff),
+            linedef(2, 3, LINEDEF_BLOCKING, 0, 2, 0xffff),
+        ])
+        .unwrap();
+
+        let polygons = map.sector_polygons();
+        assert_eq!(polygons.len(), 3);
+        assert_eq!(polygons[0].len(), 1);
+        let closed_loop = |vertexes: &[u16]| {
+            assert_eq!(vertexes.first(), vertexes.last());
+            let mut sorted = vertexes[1..].to_vec();
+            sorted.sort();
+            sorted
+        };
+        assert_eq!(closed_loop(&polygons[0][0]), [0, 1, 3]);
+        assert_eq!(polygons[1], Vec::<Vec<u16>>::new());
+        assert_eq!(closed_loop(&polygons[2][0]), [1, 2, 3]);
+
+        // A line facing the wrong way is still part of the loop
+        let map = test_map(&[
+            linedef(0, 1, LINEDEF_BLOCKING, 0, 0, 0xffff),
+            linedef(2, 1, LINEDEF_BLOCKING, 0, 0, 0xffff),
+            linedef(2, 0, LINEDEF_BLOCKING, 0, 0, 0xffff),
+        ])
+        .unwrap();
+        let polygons = map.sector_polygons();
+        assert_eq!(polygons[0].len(), 1);
+        assert_eq!(closed_loop(&polygons[0][0]), [0, 1, 2]);
+    }
+
+    #[test]
+    fn thing_filters() {
+        let thing = |doomednum, flags| Thing {
+            tid: 0,
+            x: 0,
+            y: 0,
+            z: 0,
+            angle: 0,
+            doomednum,
+            flags,
+            special: 0,
+            args: [0; 5],
+        };
+        let info = ThingInfo::doom();
+        let easy_only = thing(3001, THING_EASY);
+        assert!(easy_only.appears(Some(1), None, &info));
+        assert!(!easy_only.appears(Some(3), None, &info));
+        assert!(easy_only.appears(None, Some(GameMode::Single), &info));
+
+        let multiplayer = thing(2001, THING_HARD | THING_NOT_SINGLE);
+        assert!(!multiplayer.appears(Some(4), Some(GameMode::Single), &info));
+        assert!(multiplayer.appears(Some(4), Some(GameMode::Coop), &info));
+
+        let not_dm = thing(2001, THING_HARD | THING_NOT_DEATHMATCH);
+        assert!(!not_dm.appears(None, Some(GameMode::Deathmatch), &info));
+        assert!(thing(2001, THING_NOT_DEATHMATCH | THING_RESERVED).appears(
+            None,
+            Some(GameMode::Deathmatch),
+            &info
+        ));
+
+        assert!(thing(1, 0).appears(Some(4), Some(GameMode::Single), &info));
+        assert!(!thing(2, 7).appears(None, Some(GameMode::Single), &info));
+        assert!(thing(2, 7).appears(None, Some(GameMode::Coop), &info));
+        assert!(!thing(2, 7).appears(None, Some(GameMode::Deathmatch), &info));
+        assert!(thing(DEATHMATCH_START, 7).appears(None, Some(GameMode::Deathmatch), &info));
+        assert!(!thing(DEATHMATCH_START, 7).appears(None, Some(GameMode::Coop), &info));
+
+        // Keys are left out of deathmatch by their thing type
+        assert!(thing(5, 7).appears(None, Some(GameMode::Coop), &info));
+        assert!(!thing(5, 7).appears(None, Some(GameMode::Deathmatch), &info));
+    }
+
+    #[test]
+    fn hexen_records() {
+        let data = [
+            7, 0, 16, 0, 0xf0, 0xff, 8, 0, 90, 0, 0xb9, 0x0b, 0x03, 0x05, 80, 1, 2, 3, 4, 5,
+        ];
+        let things = parse_hexen_things(&data);
+        let info = ThingInfo::doom();
+        assert_eq!(things[0].tid, 7);
+        assert_eq!((things[0].x, things[0].y, things[0].z), (16, -16, 8));
+        assert_eq!(things[0].doomednum, 3001);
+        assert_eq!(things[0].special, 80);
+        assert_eq!(things[0].args, [1, 2, 3, 4, 5]);
+        // Single player and deathmatch, but not coop
+        assert!(things[0].appears(Some(1), Some(GameMode::Single), &info));
+        assert!(!things[0].appears(None, Some(GameMode::Coop), &info));
+        assert!(things[0].appears(None, Some(GameMode::Deathmatch), &info));
+
+        let mut data = data;
+        data[..2].copy_from_slice(&[0xff, 0xff]);
+        assert_eq!(parse_hexen_things(&data)[0].tid, -1);
+
+        let data = [0, 0, 1, 0, 4, 0, 70, 1, 2, 0, 0, 0, 0, 0, 1, 0];
+        let linedefs = parse_hexen_linedefs(&data);
+        assert_eq!(linedefs[0].special, 70);
+        assert_eq!(linedefs[0].args, [1, 2, 0, 0, 0]);
+        assert_eq!(linedefs[0].front, Some(0));
+        assert_eq!(linedefs[0].back, Some(1));
+    }
+
+    #[test]
+    fn bad_references() {
+        assert!(test_map(&[linedef(0, 4, 0, 0, 0, 0xffff)]).is_err());
+        assert!(test_map(&[linedef(0, 1, 0, 0, 3, 0xffff)]).is_err());
+        assert!(test_map(&[linedef(0, 1, 0, 0, 0xffff, 0)]).is_err());
+    }
+}
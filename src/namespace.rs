@@ -0,0 +1,363 @@
+use std::ops::Range;
+
+use wad::EntryId;
+
+/// The namespaces set up by marker lumps in a WAD. Lumps outside of any
+/// markers are in the global namespace.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
+pub enum Namespace {
+    Global,
+    /// Between S_START and S_END, or SS_START and SS_END
+    Sprites,
+    /// Between F_START and F_END, or FF_START and FF_END
+    Flats,
+    /// Between P_START and P_END, or PP_START and PP_END
+    Patches,
+}
+
+impl Namespace {
+    pub fn name(self) -> &'static str {
+        match self {
+            Namespace::Global => "global",
+            Namespace::Sprites => "sprites",
+            Namespace::Flats => "flats",
+            Namespace::Patches => "patches",
+        }
+    }
+}
+
+/// Where to look for a graphic in patch format when it is used as a patch
+/// of a texture. Vanilla Doom looks up patches regardless of markers, but
+/// a lump in the patch namespace is preferred over an unrelated lump of the
+/// same name.
+pub const PATCH_NAMESPACES: &[Namespace] =
+    &[Namespace::Patches, Namespace::Global, Namespace::Sprites];
+
+/// Where to look for a sprite lump. WADs without sprite markers have their
+/// sprites in the global namespace.
+pub const SPRITE_NAMESPACES: &[Namespace] = &[Namespace::Sprites, Namespace::Global];
+
+/// Where to look for a flat. WADs without flat markers have their flats in
+/// the global namespace.
+pub const FLAT_NAMESPACES: &[Namespace] = &[Namespace::Flats, Namespace::Global];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Marker {
+    Start(Namespace),
+    End(Namespace),
+    /// The F1_START/F1_END style markers inside the flat and patch
+    /// namespaces of the original IWADs, which do not change the namespace
+    Nested,
+}
+
+fn marker(id: &EntryId) -> Option<Marker> {
+    use Namespace::*;
+
+    let bytes = id.as_bytes();
+    let len = bytes.iter().position(|&x| x == 0).unwrap_or(bytes.len());
+    match &bytes[..len] {
+        b"S_START" | b"SS_START" => Some(Marker::Start(Sprites)),
+        b"S_END" | b"SS_END" => Some(Marker::End(Sprites)),
+        b"F_START" | b"FF_START" => Some(Marker::Start(Flats)),
+        b"F_END" | b"FF_END" => Some(Marker::End(Flats)),
+        b"P_START" | b"PP_START" => Some(Marker::Start(Patches)),
+        b"P_END" | b"PP_END" => Some(Marker::End(Patches)),
+        b"F1_START" | b"F2_START" | b"F3_START" | b"F1_END" | b"F2_END" | b"F3_END"
+        | b"P1_START" | b"P2_START" | b"P3_START" | b"P1_END" | b"P2_END" | b"P3_END" => {
+            Some(Marker::Nested)
+        }
+        _ => None,
+    }
+}
+
+/// Assign each lump to a namespace, or None for the marker lumps
+/// themselves. Mismatched markers, like FF_START with F_END, are accepted,
+/// as PWADs commonly use them.
+fn classify(ids: impl Iterator<Item = EntryId>) -> Vec<Option<Namespace>> {
+    let mut current = Namespace::Global;
+
+    ids.map(|id| match marker(&id) {
+        Some(Marker::Start(namespace)) => {
+            current = namespace;
+            None
+        }
+        Some(Marker::End(namespace)) => {
+            if current == namespace {
+                current = Namespace::Global;
+            }
+            None
+        }
+        Some(Marker::Nested) => None,
+        None => Some(current),
+    })
+    .collect()
+}
+
+/// One lump of a `ResourceIndex`
+struct Lump {
+    /// Which WAD the lump is in, in load order
+    wad: usize,
+    /// The index of the lump in the directory of its WAD
+    index: usize,
+    id: [u8; 8],
+    namespace: Option<Namespace>,
+    /// Whether a later lump of the same name in the same namespace takes
+    /// precedence over this one
+    overridden: bool,
+}
+
+/// An index of the lumps of one or more WADs, loaded in order like an IWAD
+/// followed by PWADs, with the namespace each lump belongs to. Lumps are
+/// numbered in load order across all the WADs.
+///
+/// Markers only apply within the WAD they are in, but the lumps of a
+/// namespace are looked up across all WADs, like the merged sprite and flat
+/// lists of Boom. Like in the engines, later WADs override earlier ones.
+pub struct ResourceIndex<'a> {
+    wads: Vec<wad::WadSlice<'a>>,
+    /// The range of lump numbers of each WAD
+    wad_ranges: Vec<Range<usize>>,
+    lumps: Vec<Lump>,
+}
+
+impl<'a> ResourceIndex<'a> {
+    pub fn new(wad: wad::WadSlice<'a>) -> ResourceIndex<'a> {
+        ResourceIndex::from_wads(vec![wad])
+    }
+
+    /// Index the given WADs, in load order
+    pub fn from_wads(wads: Vec<wad::WadSlice<'a>>) -> ResourceIndex<'a> {
+        let mut wad_ranges = vec![];
+        let mut lumps = vec![];
+
+        for (wad_index, wad) in wads.iter().enumerate() {
+            let start = lumps.len();
+            let namespaces = classify(wad.id_iter());
+            lumps.extend(wad.id_iter().zip(namespaces).enumerate().map(
+                |(index, (id, namespace))| Lump {
+                    wad: wad_index,
+                    index,
+                    id: *id.as_bytes(),
+                    namespace,
+                    overridden: false,
+                },
+            ));
+            wad_ranges.push(start..lumps.len());
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for lump in lumps.iter_mut().rev() {
+            if lump.namespace.is_some() {
+                lump.overridden = !seen.insert((lump.namespace, lump.id));
+            }
+        }
+
+        ResourceIndex {
+            wads,
+            wad_ranges,
+            lumps,
+        }
+    }
+
+    /// The number of lumps in all the WADs
+    pub fn len(&self) -> usize {
+        self.lumps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lumps.is_empty()
+    }
+
+    /// The number of WADs
+    pub fn wad_count(&self) -> usize {
+        self.wads.len()
+    }
+
+    /// Which WAD the lump at the given index is in, in load order
+    pub fn wad_of(&self, index: usize) -> usize {
+        self.lumps[index].wad
+    }
+
+    pub fn id(&self, index: usize) -> EntryId {
+        EntryId::from_bytes(&self.lumps[index].id)
+    }
+
+    pub fn entry(&self, index: usize) -> Result<wad::Entry<'a>, wad::Error> {
+        let lump = &self.lumps[index];
+        self.wads[lump.wad].entry(lump.index)
+    }
+
+    /// The namespace of the lump at the given index, or None for markers
+    pub fn namespace(&self, index: usize) -> Option<Namespace> {
+        self.lumps.get(index).and_then(|x| x.namespace)
+    }
+
+    /// Whether a later lump of the same name in the same namespace, in the
+    /// same or a later WAD, takes precedence over the lump at the given index
+    pub fn is_overridden(&self, index: usize) -> bool {
+        self.lumps[index].overridden
+    }
+
+    /// Whether any lumps are in the given namespace
+    pub fn has_namespace(&self, namespace: Namespace) -> bool {
+        self.lumps.iter().any(|x| x.namespace == Some(namespace))
+    }
+
+    /// The indices of the lumps in the given namespace, in load order
+    pub fn indices(&self, namespace: Namespace) -> impl Iterator<Item = usize> + '_ {
+        self.lumps
+            .iter()
+            .enumerate()
+            .filter(move |(_, x)| x.namespace == Some(namespace))
+            .map(|(index, _)| index)
+    }
+
+    /// The lumps in the given namespace, in load order
+    pub fn entries(&self, namespace: Namespace) -> impl Iterator<Item = wad::Entry<'a>> + '_ {
+        self.indices(namespace)
+            .filter_map(move |index| self.entry(index).ok())
+    }
+
+    /// Find the index of a lump by name. The last WAD that has the lump in
+    /// any of the given namespaces wins. Within that WAD, the namespaces are
+    /// tried in order, and like in the engines, the last lump of the name
+    /// in the namespace wins.
+    pub fn find_index(&self, id: impl Into<EntryId>, namespaces: &[Namespace]) -> Option<usize> {
+        let id = id.into();
+        let id = id.as_bytes();
+        self.wad_ranges.iter().rev().find_map(|range| {
+            namespaces.iter().find_map(|&namespace| {
+                range.clone().rev().find(|&i| {
+                    let lump = &self.lumps[i];
+                    &lump.id == id && lump.namespace == Some(namespace)
+                })
+            })
+        })
+    }
+
+    /// Find a lump by name, as with `find_index`
+    pub fn find(&self, id: impl Into<EntryId>, namespaces: &[Namespace]) -> Option<&'a [u8]> {
+        let index = self.find_index(id, namespaces)?;
+        Some(self.entry(index).ok()?.lump)
+    }
+
+    /// Find a lump by name regardless of namespace, the way the engines
+    /// look up lumps like PLAYPAL and COLORMAP: the last one wins
+    pub fn by_id(&self, id: impl Into<EntryId>) -> Option<&'a [u8]> {
+        let id = id.into();
+        let index = self.lumps.iter().rposition(|x| &x.id == id.as_bytes())?;
+        Some(self.entry(index).ok()?.lump)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_wad::test_wad;
+    use Namespace::*;
+
+    fn ids(names: &[&str]) -> Vec<EntryId> {
+        names
+            .iter()
+            .map(|x| EntryId::from_str(x).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn classify_markers() {
+        let names = ids(&[
+            "PLAYPAL", "S_START", "TROOA1", "S_END", "F_START", "F1_START", "FLOOR0_1", "F1_END",
+            "FF_END", "P_START", "WALL00_3", "P_END", "TITLEPIC",
+        ]);
+        assert_eq!(
+            classify(names.into_iter()),
+            vec![
+                Some(Global),
+                None,
+                Some(Sprites),
+                None,
+                None,
+                None,
+                Some(Flats),
+                None,
+                None,
+                None,
+                Some(Patches),
+                None,
+                Some(Global),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_end_is_ignored() {
+        let names = ids(&["SS_START", "A", "F_END", "B", "SS_END", "C"]);
+        assert_eq!(
+            classify(names.into_iter()),
+            vec![None, Some(Sprites), None, Some(Sprites), None, Some(Global)]
+        );
+    }
+
+    #[test]
+    fn find_by_namespace() {
+        let wad = test_wad(&[
+            ("STEP1", b"patch"),
+            ("F_START", b""),
+            ("STEP1", b"flat"),
+            ("F_END", b""),
+            ("P_START", b""),
+            ("WALL", b"wall"),
+            ("P_END", b""),
+        ]);
+        let index = ResourceIndex::new(wad.as_slice());
+
+        assert_eq!(index.find(b"STEP1", FLAT_NAMESPACES), Some(&b"flat"[..]));
+        assert_eq!(index.find(b"STEP1", PATCH_NAMESPACES), Some(&b"patch"[..]));
+        assert_eq!(index.find(b"WALL", PATCH_NAMESPACES), Some(&b"wall"[..]));
+        assert_eq!(index.find(b"WALL", FLAT_NAMESPACES), None);
+        assert_eq!(index.find(b"F_START", &[Global]), None);
+        assert!(!index.has_namespace(Sprites));
+        assert_eq!(index.indices(Flats).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn later_wads_override() {
+        let iwad = test_wad(&[
+            ("PLAYPAL", b"iwad"),
+            ("S_START", b""),
+            ("TROOA1", b"iwad"),
+            ("TROOB1", b"iwad"),
+            ("S_END", b""),
+            ("P_START", b""),
+            ("WALL", b"iwad"),
+            ("P_END", b""),
+        ]);
+        let pwad = test_wad(&[
+            ("PLAYPAL", b"pwad"),
+            ("SS_START", b""),
+            ("TROOA1", b"pwad"),
+            ("SS_END", b""),
+            ("WALL", b"pwad"),
+        ]);
+        let index = ResourceIndex::from_wads(vec![iwad.as_slice(), pwad.as_slice()]);
+
+        assert_eq!(index.by_id(b"PLAYPAL"), Some(&b"pwad"[..]));
+        assert_eq!(index.find(b"TROOA1", SPRITE_NAMESPACES), Some(&b"pwad"[..]));
+        assert_eq!(index.find(b"TROOB1", SPRITE_NAMESPACES), Some(&b"iwad"[..]));
+        assert_eq!(index.find(b"WALL", PATCH_NAMESPACES), Some(&b"pwad"[..]));
+        assert_eq!(index.indices(Sprites).count(), 3);
+        assert_eq!(
+            index.wad_of(index.find_index(b"TROOA1", &[Sprites]).unwrap()),
+            1
+        );
+    }
+
+    #[test]
+    fn last_lump_wins() {
+        let wad = test_wad(&[("STBAR", b"first"), ("STBAR", b"second")]);
+        let index = ResourceIndex::new(wad.as_slice());
+        assert_eq!(index.find(b"STBAR", &[Global]), Some(&b"second"[..]));
+        assert!(index.is_overridden(0));
+        assert!(!index.is_overridden(1));
+    }
+}
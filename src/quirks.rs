@@ -0,0 +1,233 @@
+use std::collections::BTreeSet;
+
+use wad::EntryId;
+
+use crate::{Check, Map, PatchProvider, Problem, Severity, Texture, TextureSet};
+
+/// Find the quirks of the vanilla renderer that affect a texture:
+///
+///  * Tutti-frutti: the wall drawer wraps texture columns with a power of
+///    two mask, so textures that are not a power of two tall show garbage
+///    where they repeat vertically
+///  * Medusa: columns covered by more than one patch are composited without
+///    post information, so they are drawn as garbage when the texture is
+///    used as a masked mid-texture
+///  * Negative Y clamp: when compositing, posts that start above the top of
+///    the texture are moved down to the top instead of being cut off
+///  * Non-power-of-two width: textures repeat horizontally at the largest
+///    power of two that fits in the width, so the columns beyond it are
+///    never drawn
+///
+/// Patches that are missing or malformed are left out of the Medusa check.
+pub fn texture_quirks<'a>(
+    texture: Texture,
+    patch_provider: &impl PatchProvider<'a>,
+) -> Vec<Problem> {
+    let name = EntryId::from_bytes(&texture.name()).to_string();
+    let mut problems = vec![];
+    let mut report = |check, message: String| {
+        problems.push(Problem {
+            severity: Severity::Warning,
+            check,
+            subject: name.clone(),
+            message,
+        })
+    };
+
+    let (width, height) = (texture.width(), texture.height());
+
+    if !height.is_power_of_two() {
+        report(
+            Check::TuttiFrutti,
+            format!(
+                "height {} is not a power of two, so the texture shows garbage where it \
+                 repeats vertically",
+                height
+            ),
+        );
+    }
+
+    let mut coverage = vec![0u32; width as usize];
+    for p in 0..texture.len() {
+        let patch = texture.patch(p);
+        if let Some(sprite) = patch_provider.patch(patch.patch_id) {
+            let start = (patch.origin_x as i32).max(0);
+            let end = (patch.origin_x as i32 + sprite.width() as i32).min(width as i32);
+            for x in start..end {
+                coverage[x as usize] += 1;
+            }
+        }
+    }
+    let composite = coverage.iter().filter(|&&x| x > 1).count();
+    if composite > 0 {
+        let first = coverage.iter().position(|&x| x > 1).unwrap();
+        report(
+            Check::Medusa,
+            format!(
+                "{} columns, starting with column {}, are covered by more than one patch, \
+                 which shows the Medusa effect when used as a masked mid-texture",
+                composite, first
+            ),
+        );
+    }
+
+    for p in 0..texture.len() {
+        let patch = texture.patch(p);
+        if patch.origin_y < 0 {
+            report(
+                Check::NegativeYClamp,
+                format!(
+                    "patch {} has a Y offset of {}, which is clamped to 0 when compositing",
+                    p, patch.origin_y
+                ),
+            );
+        }
+    }
+
+    if width > 0 && !width.is_power_of_two() {
+        let wrap: u16 = 1 << (15 - width.leading_zeros());
+        report(
+            Check::NonPowerOfTwoWidth,
+            format!(
+                "width {} is not a power of two, so the texture repeats after {} columns and \
+                 columns {}-{} are never drawn",
+                width,
+                wrap,
+                wrap,
+                width - 1
+            ),
+        );
+    }
+
+    problems
+}
+
+/// Find the vanilla renderer quirks of all textures that are not
+/// overridden. As the Medusa effect only shows on masked mid-textures, it is
+/// a warning for the textures used as the middle texture of a two-sided
+/// line in one of the maps, and info for the others.
+pub fn check_vanilla<'a>(
+    textures: &TextureSet<'a>,
+    patch_provider: &impl PatchProvider<'a>,
+    maps: &[Map],
+) -> Vec<Problem> {
+    let masked: BTreeSet<usize> = maps
+        .iter()
+        .flat_map(|map| map.masked_mid_textures())
+        .filter_map(|name| textures.find_index(&name))
+        .collect();
+
+    let mut problems = vec![];
+    for i in (0..textures.len()).filter(|&i| !textures.is_overridden(i)) {
+        for mut problem in texture_quirks(textures.texture(i), patch_provider) {
+            if problem.check == Check::Medusa && !masked.contains(&i) {
+                problem.severity = Severity::Info;
+            }
+            problems.push(problem);
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{texture, texture_dir, TestPatch, TestPatchProvider};
+    use crate::{Linedef, MapFormat, Sidedef, TextureDirectory};
+
+    /// A texture of copies of the test patch at the given offsets
+    fn texture_data(width: u16, height: u16, patches: &[(i16, i16)]) -> Vec<u8> {
+        let patches: Vec<TestPatch> = patches.iter().map(|&(x, y)| (x, y, 0)).collect();
+        texture("TEST", width, height, &patches)
+    }
+
+    fn quirks(width: u16, height: u16, patches: &[(i16, i16)]) -> Vec<Check> {
+        let data = texture_data(width, height, patches);
+        texture_quirks(Texture::new(&data), &TestPatchProvider)
+            .iter()
+            .map(|x| x.check)
+            .collect()
+    }
+
+    #[test]
+    fn no_quirks() {
+        assert_eq!(quirks(64, 128, &[(0, 0), (41, 0)]), []);
+    }
+
+    #[test]
+    fn each_quirk() {
+        assert_eq!(quirks(64, 72, &[(0, 0)]), [Check::TuttiFrutti]);
+        assert_eq!(quirks(64, 128, &[(0, 0), (40, 0)]), [Check::Medusa]);
+        assert_eq!(quirks(64, 128, &[(0, -8)]), [Check::NegativeYClamp]);
+        assert_eq!(quirks(96, 128, &[(0, 0)]), [Check::NonPowerOfTwoWidth]);
+    }
+
+    #[test]
+    fn wrap_width() {
+        let data = texture_data(96, 128, &[]);
+        let problems = texture_quirks(Texture::new(&data), &TestPatchProvider);
+        assert!(problems[0].message.contains("repeats after 64 columns"));
+        assert!(problems[0].message.contains("columns 64-95"));
+    }
+
+    #[test]
+    fn medusa_on_masked_mid_textures() {
+        let medusa: &[TestPatch] = &[(0, 0, 0), (40, 0, 0)];
+        let data = texture_dir(&[("MIDGRATE", 64, 128, medusa), ("STARTAN3", 64, 128, medusa)]);
+        let directory = TextureDirectory::new(&data);
+        let textures = TextureSet::new(&[directory]);
+
+        let side = |middle: &[u8; 8]| Sidedef {
+            x_offset: 0,
+            y_offset: 0,
+            upper: *b"-\0\0\0\0\0\0\0",
+            lower: *b"-\0\0\0\0\0\0\0",
+            middle: *middle,
+            sector: 0,
+        };
+        let line = |front, back| Linedef {
+            v1: 0,
+            v2: 0,
+            flags: 0,
+            special: 0,
+            tag: 0,
+            args: [0; 5],
+            front: Some(front),
+            back,
+        };
+        let map = Map {
+            name: "E1M1".into(),
+            format: MapFormat::Doom,
+            vertexes: vec![],
+            linedefs: vec![line(0, Some(1)), line(2, None)],
+            sidedefs: vec![
+                side(b"midgrate"),
+                side(b"-\0\0\0\0\0\0\0"),
+                side(b"STARTAN3"),
+            ],
+            sectors: vec![],
+            things: vec![],
+        };
+
+        let severities = |maps: &[Map]| -> Vec<_> {
+            check_vanilla(&textures, &TestPatchProvider, maps)
+                .iter()
+                .map(|x| (x.subject.clone(), x.severity))
+                .collect()
+        };
+        assert_eq!(
+            severities(&[map]),
+            [
+                ("MIDGRATE".to_string(), Severity::Warning),
+                ("STARTAN3".to_string(), Severity::Info)
+            ]
+        );
+        assert_eq!(
+            severities(&[]),
+            [
+                ("MIDGRATE".to_string(), Severity::Info),
+                ("STARTAN3".to_string(), Severity::Info)
+            ]
+        );
+    }
+}
@@ -11,6 +11,7 @@ pub struct Column<'a> {
 }
 
 impl<'a> Column<'a> {
+    #[allow(mismatched_lifetime_syntaxes)]
     fn new(data: &[u8]) -> Column {
         Column { data }
     }
@@ -38,6 +39,46 @@ impl<'a> Iterator for Column<'a> {
     }
 }
 
+/// Check that a lump is structurally a valid patch: that the header fits,
+/// every column offset points inside the lump and every column is a
+/// sequence of posts that ends with the 0xFF terminator within the lump.
+/// Lumps that pass can be drawn with `Sprite` without panicking.
+pub fn validate_patch(data: &[u8]) -> Result<(), &'static str> {
+    if data.len() < 8 {
+        return Err("too short for a patch header");
+    }
+    let width = LittleEndian::read_u16(&data[0..2]) as usize;
+    let height = LittleEndian::read_u16(&data[2..4]);
+    if width == 0 || height == 0 {
+        return Err("zero width or height");
+    }
+
+    let column_array_end = 8 + width * 4;
+    if data.len() < column_array_end {
+        return Err("column offsets extend past the end of the lump");
+    }
+
+    for column in data[8..column_array_end].chunks(4) {
+        let mut pos = LittleEndian::read_u32(column) as usize;
+        if pos < column_array_end {
+            return Err("column offset points into the header");
+        }
+        loop {
+            match data.get(pos) {
+                None => return Err("column extends past the end of the lump"),
+                Some(255) => break,
+                Some(_) => {}
+            }
+            let count = *data
+                .get(pos + 1)
+                .ok_or("column extends past the end of the lump")? as usize;
+            pos += 4 + count;
+        }
+    }
+
+    Ok(())
+}
+
 pub struct Sprite<'a> {
     width: u16,
     height: u16,
@@ -49,6 +90,7 @@ pub struct Sprite<'a> {
 }
 
 impl<'a> Sprite<'a> {
+    #[allow(mismatched_lifetime_syntaxes)]
     pub fn new(data: &[u8]) -> Sprite {
         assert!(data.len() >= 8);
         let width = LittleEndian::read_u16(&data[0..2]);
@@ -83,15 +125,22 @@ impl<'a> Sprite<'a> {
     }
 
     pub fn col(&'a self, i: u32) -> Column<'a> {
+        // Columns run until their terminating post, and may be shared or
+        // stored out of order, so the next column offset is no bound
         let start =
             LittleEndian::read_u32(&self.column_array[i as usize]) as usize - self.data_offset;
-        let end = self
-            .column_array
-            .get(i as usize + 1)
-            .map(|x| LittleEndian::read_u32(x) as usize - self.data_offset)
-            .unwrap_or(self.data.len());
 
-        Column::new(&self.data[start..end])
+        Column::new(&self.data[start..])
+    }
+
+    /// The raw bytes of the lump from the start of the given column to the
+    /// end of the lump, the way the engine sees a column when it does not
+    /// respect the post structure
+    pub fn col_bytes(&self, i: u32) -> &'a [u8] {
+        let start =
+            LittleEndian::read_u32(&self.column_array[i as usize]) as usize - self.data_offset;
+
+        &self.data[start..]
     }
 
     pub fn origin(&self) -> (i16, i16) {
@@ -123,6 +172,14 @@ impl<'a> Sprite<'a> {
 mod test {
     use super::*;
 
+    #[test]
+    fn validate() {
+        let sprite = include_bytes!("trooa1.sprite");
+        assert_eq!(validate_patch(sprite), Ok(()));
+        assert!(validate_patch(&sprite[..sprite.len() - 3]).is_err());
+        assert!(validate_patch(&[0; 4096]).is_err());
+    }
+
     #[test]
     fn dimensions() {
         let sprite = Sprite::new(include_bytes!("trooa1.sprite"));
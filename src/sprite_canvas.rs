@@ -1,10 +1,51 @@
 use crate::rangetools::*;
-use crate::Sprite;
+use crate::{Span, Sprite};
 use byteorder::{LittleEndian, WriteBytesExt};
 use ndarray::prelude::*;
 use ndarray::s;
 use std::ops::Range;
 
+/// Mirroring to apply when drawing a patch. Sprites only ever use `x`, for
+/// the mirrored rotations, while ZDoom textures can flip patches either way.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Flip {
+    pub x: bool,
+    pub y: bool,
+}
+
+impl Flip {
+    pub fn horizontal() -> Flip {
+        Flip { x: true, y: false }
+    }
+
+    /// Map a column in the drawn patch to the column in the source patch
+    pub fn map_x(&self, x: i32, width: u16) -> i32 {
+        if self.x {
+            width as i32 - 1 - x
+        } else {
+            x
+        }
+    }
+
+    /// The top of the given span in the drawn patch
+    pub fn map_span_top(&self, span: &Span, height: u16) -> i32 {
+        if self.y {
+            height as i32 - span.top as i32 - span.pixels.len() as i32
+        } else {
+            span.top as i32
+        }
+    }
+
+    /// Map an index into the drawn span to an index into the source span
+    pub fn map_span_pixel(&self, i: i32, span: &Span) -> usize {
+        if self.y {
+            span.pixels.len() - 1 - i as usize
+        } else {
+            i as usize
+        }
+    }
+}
+
 pub struct SpriteCanvas {
     pixels: Array2<u8>,
     mask: Array2<bool>,
@@ -51,6 +92,13 @@ impl SpriteCanvas {
     }
 
     pub fn draw_patch(&mut self, pos_x: i16, pos_y: i16, sprite: &Sprite) {
+        self.draw_patch_flipped(pos_x, pos_y, sprite, Flip::default());
+    }
+
+    /// Draw a patch, optionally mirrored. As in R_ProjectSprite, a flipped
+    /// patch covers the same area as an unflipped one, so the origin is not
+    /// mirrored along with the pixels.
+    pub fn draw_patch_flipped(&mut self, pos_x: i16, pos_y: i16, sprite: &Sprite, flip: Flip) {
         let (top, left) = sprite.origin();
         let origin = (left as i32, top as i32); // Flip xy
 
@@ -62,15 +110,18 @@ impl SpriteCanvas {
         let x_range = intersect(x_range, 0..self.width() as i32); // Clip to canvas
 
         for x in x_range {
-            for span in sprite.col((x - offset.0) as _) {
-                let y_offset = offset.1 + span.top as i32;
+            let src_x = flip.map_x(x - offset.0, sprite.width());
+            for span in sprite.col(src_x as _) {
+                let span_top = flip.map_span_top(&span, sprite.height());
+                let y_offset = offset.1 + span_top;
 
                 let span_range = 0..span.pixels.len() as i32;
                 let span_range = add(span_range, y_offset);
                 let span_range = intersect(span_range, 0..self.height() as i32);
 
                 for y in span_range {
-                    self.pixels[[x as usize, y as usize]] = span.pixels[(y - y_offset) as usize];
+                    let src_y = flip.map_span_pixel(y - y_offset, &span);
+                    self.pixels[[x as usize, y as usize]] = span.pixels[src_y];
                     self.mask[[x as usize, y as usize]] = true;
                 }
             }
@@ -133,6 +184,7 @@ mod test {
     use super::*;
 
     #[test]
+    #[allow(clippy::unnecessary_cast)]
     fn roundtrip() {
         let first_sprite = Sprite::new(include_bytes!("trooa1.sprite"));
         let mut canvas =
@@ -155,6 +207,38 @@ mod test {
     }
 
     #[test]
+    fn flip_x() {
+        let sprite = Sprite::new(include_bytes!("trooa1.sprite"));
+        let mut canvas = SpriteCanvas::new(sprite.width(), sprite.height());
+        canvas.draw_patch(sprite.left(), sprite.top(), &sprite);
+        let (pixels, mask) = canvas.into_planes_col_major();
+
+        let mut canvas = SpriteCanvas::new(sprite.width(), sprite.height());
+        canvas.draw_patch_flipped(sprite.left(), sprite.top(), &sprite, Flip::horizontal());
+        let (flipped_pixels, flipped_mask) = canvas.into_planes_col_major();
+
+        assert_eq!(pixels.slice(s![..;-1, ..]), flipped_pixels);
+        assert_eq!(mask.slice(s![..;-1, ..]), flipped_mask);
+    }
+
+    #[test]
+    fn flip_y() {
+        let sprite = Sprite::new(include_bytes!("trooa1.sprite"));
+        let mut canvas = SpriteCanvas::new(sprite.width(), sprite.height());
+        canvas.draw_patch(sprite.left(), sprite.top(), &sprite);
+        let (pixels, mask) = canvas.into_planes_col_major();
+
+        let mut canvas = SpriteCanvas::new(sprite.width(), sprite.height());
+        let flip = Flip { x: false, y: true };
+        canvas.draw_patch_flipped(sprite.left(), sprite.top(), &sprite, flip);
+        let (flipped_pixels, flipped_mask) = canvas.into_planes_col_major();
+
+        assert_eq!(pixels.slice(s![.., ..;-1]), flipped_pixels);
+        assert_eq!(mask.slice(s![.., ..;-1]), flipped_mask);
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
     fn transpose() {
         let sprite = Sprite::new(include_bytes!("trooa1.sprite"));
         let mut canvas = SpriteCanvas::new(sprite.width() as u16, sprite.height() as u16);
@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+
+use crate::{Namespace, ResourceIndex};
+
+/// Vanilla Doom allows frame letters from A to ], giving 29 frames
+pub const MAX_SPRITE_FRAMES: usize = 29;
+
+/// Binary angle measurement (BAM) for 45 degrees, as in tables.h
+pub const ANG45: u32 = 0x2000_0000;
+
+/// Binary angle measurement (BAM) for 180 degrees, as in tables.h
+pub const ANG180: u32 = 0x8000_0000;
+
+/// Convert an angle in degrees to a binary angle measurement (BAM), the
+/// angle representation used by the Doom engine
+pub fn degrees_to_bam(degrees: f64) -> u32 {
+    let turns = (degrees / 360.).rem_euclid(1.);
+    (turns * 4_294_967_296.) as u64 as u32
+}
+
+/// One sprite lump slot is one of up to 16 rotations. Rotations 1-8 are the
+/// vanilla ones, 9-16 are ZDoom's intermediate rotations, named 9 and A-G
+fn parse_rotation(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'A'..=b'G' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn parse_frame(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b']' => Some(c - b'A'),
+        _ => None,
+    }
+}
+
+/// The parsed form of a sprite lump name following the `NNNNFR` or
+/// `NNNNFRfr` convention. The optional second frame/rotation pair reuses
+/// the same lump, drawn mirrored.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SpriteLumpName {
+    pub sprite: [u8; 4],
+    /// 0-based frame number, where 0 is frame A
+    pub frame: u8,
+    /// 0 for a frame that looks the same from all directions, otherwise
+    /// 1-8, or 1-16 for ZDoom-style sprites
+    pub rotation: u8,
+    pub mirror: Option<(u8, u8)>,
+}
+
+impl SpriteLumpName {
+    pub fn parse(name: &[u8]) -> Option<SpriteLumpName> {
+        let len = name.iter().position(|&x| x == 0).unwrap_or(name.len());
+        if name[len..].iter().any(|&x| x != 0) {
+            return None;
+        }
+
+        let name = &name[..len];
+        if name.len() != 6 && name.len() != 8 {
+            return None;
+        }
+
+        let mirror = if name.len() == 8 {
+            Some((parse_frame(name[6])?, parse_rotation(name[7])?))
+        } else {
+            None
+        };
+
+        Some(SpriteLumpName {
+            sprite: name[0..4].try_into().unwrap(),
+            frame: parse_frame(name[4])?,
+            rotation: parse_rotation(name[5])?,
+            mirror,
+        })
+    }
+}
+
+/// A lump to draw for one rotation of a sprite frame
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SpriteRotation {
+    pub lump: [u8; 8],
+    pub flip: bool,
+}
+
+/// All rotations of one frame of a sprite. Rotations are stored in 16 slots
+/// in angular order, so vanilla rotation n occupies slot 2(n - 1) and the
+/// ZDoom intermediate rotations occupy the odd slots.
+#[derive(Debug, Default, Clone)]
+pub struct SpriteFrame {
+    rotate: bool,
+    slots: [Option<SpriteRotation>; 16],
+}
+
+fn slot_for_rotation(rotation: u8) -> Option<usize> {
+    match rotation {
+        1..=8 => Some((rotation as usize - 1) * 2),
+        9..=16 => Some((rotation as usize - 9) * 2 + 1),
+        _ => None,
+    }
+}
+
+impl SpriteFrame {
+    fn install(&mut self, rotation: u8, lump: SpriteRotation) {
+        // Like Boom and later ports, let later lumps override earlier ones
+        // instead of reporting an error
+        if rotation == 0 {
+            self.rotate = false;
+            self.slots = [Some(lump); 16];
+        } else {
+            if !self.rotate {
+                self.slots = Default::default();
+                self.rotate = true;
+            }
+            let slot = slot_for_rotation(rotation).expect("parse_rotation gives 0-16");
+            self.slots[slot] = Some(lump);
+        }
+    }
+
+    /// True if this frame has different lumps for different view angles
+    pub fn rotates(&self) -> bool {
+        self.rotate
+    }
+
+    /// True if this frame has ZDoom-style intermediate rotations
+    pub fn has_16_rotations(&self) -> bool {
+        self.rotate && self.slots.iter().skip(1).step_by(2).any(Option::is_some)
+    }
+
+    /// The number of distinct rotations: 1, 8 or 16
+    pub fn rotation_count(&self) -> usize {
+        if !self.rotate {
+            1
+        } else if self.has_16_rotations() {
+            16
+        } else {
+            8
+        }
+    }
+
+    /// Look up rotation 0, 1-8 or ZDoom's 9-16. For non-rotating frames,
+    /// all rotations give the same lump. Other rotations give None.
+    pub fn rotation(&self, rotation: u8) -> Option<&SpriteRotation> {
+        match rotation {
+            0 => self.slots[0].as_ref().filter(|_| !self.rotate),
+            _ => self.slots[slot_for_rotation(rotation)?].as_ref(),
+        }
+    }
+
+    /// All rotations in angular order, starting with the front view and
+    /// going counterclockwise
+    pub fn rotations(&self) -> impl Iterator<Item = Option<&SpriteRotation>> {
+        let step = 16 / self.rotation_count();
+        self.slots.iter().step_by(step).map(Option::as_ref)
+    }
+
+    /// The rotation number of a position in `rotations`: 1-8, 9-16 for
+    /// ZDoom's intermediate rotations, or 0 for frames that do not rotate
+    pub fn rotation_number(&self, position: usize) -> u8 {
+        if !self.rotate {
+            return 0;
+        }
+        let slot = (position * (16 / self.rotation_count())) as u8;
+        if slot.is_multiple_of(2) {
+            slot / 2 + 1
+        } else {
+            slot / 2 + 9
+        }
+    }
+
+    /// True if all rotations have a lump
+    pub fn is_complete(&self) -> bool {
+        self.rotations().all(|x| x.is_some())
+    }
+
+    /// Resolve the lump to draw the way R_ProjectSprite does. `angle` is the
+    /// angle from the viewer to the thing minus the angle the thing is
+    /// facing, so a thing seen from the front has an angle of `ANG180`.
+    pub fn for_angle(&self, angle: u32) -> Option<&SpriteRotation> {
+        let slot = if !self.rotate {
+            0
+        } else if self.has_16_rotations() {
+            let offset = (ANG45 / 2 * 9).wrapping_sub(ANG180 / 16);
+            (angle.wrapping_add(offset) >> 28) as usize
+        } else {
+            (angle.wrapping_add(ANG45 / 2 * 9) >> 29) as usize * 2
+        };
+
+        self.slots[slot].as_ref()
+    }
+}
+
+/// All frames of a sprite, as collected by R_InitSpriteDefs
+#[derive(Debug, Clone)]
+pub struct SpriteDef {
+    sprite: [u8; 4],
+    frames: Vec<SpriteFrame>,
+}
+
+impl SpriteDef {
+    pub fn new(sprite: [u8; 4]) -> SpriteDef {
+        SpriteDef {
+            sprite,
+            frames: vec![],
+        }
+    }
+
+    /// Install a lump by name. Lumps whose names do not follow the sprite
+    /// naming convention or belong to another sprite are ignored.
+    pub fn install(&mut self, lump: &[u8; 8]) {
+        let name = match SpriteLumpName::parse(lump) {
+            Some(name) if name.sprite == self.sprite => name,
+            _ => return,
+        };
+
+        let installs = std::iter::once((name.frame, name.rotation, false))
+            .chain(name.mirror.map(|(frame, rotation)| (frame, rotation, true)));
+
+        for (frame, rotation, flip) in installs {
+            let frame = frame as usize;
+            if self.frames.len() <= frame {
+                self.frames.resize_with(frame + 1, Default::default);
+            }
+            self.frames[frame].install(rotation, SpriteRotation { lump: *lump, flip });
+        }
+    }
+
+    /// Collect the given sprite from the lumps in the sprite namespace,
+    /// between S_START and S_END or SS_START and SS_END. If there are no
+    /// such markers, the entire WAD is searched.
+    pub fn from_wad(index: &ResourceIndex, sprite: [u8; 4]) -> SpriteDef {
+        let mut def = SpriteDef::new(sprite);
+        for id in sprite_lump_ids(index) {
+            def.install(&id);
+        }
+        def
+    }
+
+    pub fn sprite(&self) -> [u8; 4] {
+        self.sprite
+    }
+
+    /// The number of frames, including any gaps
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Get a frame by number, where 0 is frame A. Frames that have no
+    /// lumps are considered missing.
+    pub fn frame(&self, frame: u8) -> Option<&SpriteFrame> {
+        self.frames
+            .get(frame as usize)
+            .filter(|x| x.slots.iter().any(Option::is_some))
+    }
+
+    /// Resolve which lump to draw, and whether to mirror it, for the given
+    /// frame and view angle. See `SpriteFrame::for_angle`.
+    pub fn resolve(&self, frame: u8, angle: u32) -> Option<&SpriteRotation> {
+        self.frame(frame)?.for_angle(angle)
+    }
+}
+
+/// The names of the lumps in the sprite namespace, in load order, so lumps
+/// of later WADs are installed over those of earlier ones
+fn sprite_lump_ids(index: &ResourceIndex) -> Vec<[u8; 8]> {
+    if index.has_namespace(Namespace::Sprites) {
+        index
+            .indices(Namespace::Sprites)
+            .map(|i| *index.id(i).as_bytes())
+            .collect()
+    } else {
+        (0..index.len()).map(|i| *index.id(i).as_bytes()).collect()
+    }
+}
+
+/// Collect all sprites in the sprite namespace of the given WAD
+pub fn sprite_defs(index: &ResourceIndex) -> BTreeMap<[u8; 4], SpriteDef> {
+    let mut defs = BTreeMap::new();
+
+    for id in sprite_lump_ids(index) {
+        let name = &id;
+        if let Some(parsed) = SpriteLumpName::parse(name) {
+            defs.entry(parsed.sprite)
+                .or_insert_with(|| SpriteDef::new(parsed.sprite))
+                .install(name);
+        }
+    }
+
+    defs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn name(s: &str) -> [u8; 8] {
+        let mut buf = [0; 8];
+        buf[..s.len()].copy_from_slice(s.as_bytes());
+        buf
+    }
+
+    fn imp_a() -> SpriteDef {
+        let mut def = SpriteDef::new(*b"TROO");
+        for lump in &["TROOA1", "TROOA2A8", "TROOA3A7", "TROOA4A6", "TROOA5"] {
+            def.install(&name(lump));
+        }
+        def
+    }
+
+    #[test]
+    fn parse_single() {
+        assert_eq!(
+            SpriteLumpName::parse(&name("TROOA1")),
+            Some(SpriteLumpName {
+                sprite: *b"TROO",
+                frame: 0,
+                rotation: 1,
+                mirror: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_mirrored() {
+        assert_eq!(
+            SpriteLumpName::parse(&name("TROOA2A8")),
+            Some(SpriteLumpName {
+                sprite: *b"TROO",
+                frame: 0,
+                rotation: 2,
+                mirror: Some((0, 8)),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(SpriteLumpName::parse(&name("TROOA")), None);
+        assert_eq!(SpriteLumpName::parse(&name("TROOA1A")), None);
+        assert_eq!(SpriteLumpName::parse(&name("TROOa1")), None);
+        assert_eq!(SpriteLumpName::parse(&name("TROOAH")), None);
+        assert_eq!(SpriteLumpName::parse(b"TROOA1\0A"), None);
+    }
+
+    #[test]
+    fn install_rotations() {
+        let def = imp_a();
+        let frame = def.frame(0).unwrap();
+        assert!(frame.rotates());
+        assert!(frame.is_complete());
+        assert_eq!(frame.rotation_count(), 8);
+        assert_eq!(
+            frame.rotation(8),
+            Some(&SpriteRotation {
+                lump: name("TROOA2A8"),
+                flip: true
+            })
+        );
+        assert_eq!(def.frame(1).map(|_| ()), None);
+        assert_eq!(frame.rotation(17), None);
+    }
+
+    #[test]
+    fn resolve_like_r_projectsprite() {
+        let def = imp_a();
+        let lump = |deg| {
+            let r = def.resolve(0, degrees_to_bam(deg)).unwrap();
+            (r.lump, r.flip)
+        };
+
+        assert_eq!(lump(180.), (name("TROOA1"), false));
+        assert_eq!(lump(225.), (name("TROOA2A8"), false));
+        assert_eq!(lump(135.), (name("TROOA2A8"), true));
+        assert_eq!(lump(0.), (name("TROOA5"), false));
+        assert_eq!(lump(200.), (name("TROOA1"), false));
+    }
+
+    #[test]
+    fn non_rotating() {
+        let mut def = SpriteDef::new(*b"BAR1");
+        def.install(&name("BAR1A0"));
+        let frame = def.frame(0).unwrap();
+        assert!(!frame.rotates());
+        assert_eq!(frame.rotation_count(), 1);
+        assert_eq!(def.resolve(0, 12345).unwrap().lump, name("BAR1A0"));
+    }
+
+    #[test]
+    fn missing_rotations() {
+        let mut def = SpriteDef::new(*b"TROO");
+        def.install(&name("TROOA1"));
+        assert!(!def.frame(0).unwrap().is_complete());
+    }
+
+    #[test]
+    fn rotation_numbers() {
+        let def = imp_a();
+        let numbers: Vec<u8> = (0..8)
+            .map(|i| def.frame(0).unwrap().rotation_number(i))
+            .collect();
+        assert_eq!(numbers, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let mut def = SpriteDef::new(*b"TROO");
+        def.install(&name("TROOA1"));
+        def.install(&name("TROOA9"));
+        let frame = def.frame(0).unwrap();
+        let numbers: Vec<u8> = (0..4).map(|i| frame.rotation_number(i)).collect();
+        assert_eq!(numbers, [1, 9, 2, 10]);
+    }
+}
@@ -0,0 +1,14 @@
+//! Helpers shared by the tests of the library
+
+pub use crate::test_wad::*;
+
+use crate::{PatchProvider, Sprite};
+
+/// Gives the same 41 pixels wide sprite for every patch
+pub struct TestPatchProvider;
+
+impl<'a> PatchProvider<'a> for TestPatchProvider {
+    fn patch(&self, _id: u16) -> Option<Sprite<'a>> {
+        Some(Sprite::new(include_bytes!("trooa1.sprite")))
+    }
+}
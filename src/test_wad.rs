@@ -0,0 +1,70 @@
+//! Builders for small WADs and lumps to test on, shared by the library and
+//! the command line tool
+
+// Each crate uses only some of the builders
+#![allow(dead_code)]
+
+use wad::EntryId;
+
+pub fn test_wad(lumps: &[(&str, &[u8])]) -> wad::Wad {
+    let mut data = b"PWAD".to_vec();
+    data.extend(&(lumps.len() as u32).to_le_bytes());
+    let directory_offset = 12 + lumps.iter().map(|(_, x)| x.len()).sum::<usize>();
+    data.extend(&(directory_offset as u32).to_le_bytes());
+
+    let mut directory: Vec<u8> = vec![];
+    for (name, lump) in lumps {
+        directory.extend(&(data.len() as u32).to_le_bytes());
+        directory.extend(&(lump.len() as u32).to_le_bytes());
+        directory.extend(EntryId::from_str(name).unwrap().as_bytes());
+        data.extend(*lump);
+    }
+    data.extend(directory);
+
+    wad::parse_wad(data).unwrap()
+}
+
+pub fn pnames(names: &[&str]) -> Vec<u8> {
+    let mut data = (names.len() as u32).to_le_bytes().to_vec();
+    for name in names {
+        data.extend(EntryId::from_str(name).unwrap().as_bytes());
+    }
+    data
+}
+
+/// A patch of a texture as x and y offset and PNAMES index
+pub type TestPatch = (i16, i16, u16);
+
+/// A texture lump with the given name, width, height and patches
+pub fn texture(name: &str, width: u16, height: u16, patches: &[TestPatch]) -> Vec<u8> {
+    let mut data = EntryId::from_str(name).unwrap().as_bytes().to_vec();
+    data.extend(&[0; 4]);
+    data.extend(&width.to_le_bytes());
+    data.extend(&height.to_le_bytes());
+    data.extend(&[0; 4]);
+    data.extend(&(patches.len() as u16).to_le_bytes());
+    for (x, y, patch) in patches {
+        data.extend(&x.to_le_bytes());
+        data.extend(&y.to_le_bytes());
+        data.extend(&patch.to_le_bytes());
+        data.extend(&1u16.to_le_bytes());
+        data.extend(&0u16.to_le_bytes());
+    }
+    data
+}
+
+/// A texture directory of textures given as name, width, height and patches
+pub fn texture_dir(textures: &[(&str, u16, u16, &[TestPatch])]) -> Vec<u8> {
+    let mut offsets: Vec<u8> = vec![];
+    let mut data: Vec<u8> = vec![];
+    let start = 4 + 4 * textures.len();
+    for &(name, width, height, patches) in textures {
+        offsets.extend(&((start + data.len()) as u32).to_le_bytes());
+        data.extend(texture(name, width, height, patches));
+    }
+
+    let mut dir = (textures.len() as u32).to_le_bytes().to_vec();
+    dir.extend(offsets);
+    dir.extend(data);
+    dir
+}
@@ -1,7 +1,44 @@
 use byteorder::{ByteOrder, LittleEndian};
+use ndarray::prelude::*;
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 
-use super::{Sprite, SpriteCanvas};
+use super::{validate_patch, ResourceIndex, Sprite, SpriteCanvas, PATCH_NAMESPACES};
+
+/// Check that a TEXTUREx lump is well formed: the offset array fits in the
+/// lump, and every texture has a complete header and patch list between its
+/// offset and the next. Lumps that pass can be read with `TextureDirectory`
+/// without panicking.
+pub fn validate_texture_directory(data: &[u8]) -> Result<(), &'static str> {
+    if data.len() < 4 {
+        return Err("too short for the texture count");
+    }
+    let num_textures = LittleEndian::read_u32(&data[0..4]) as usize;
+    let offset_array_end = 4 + num_textures * 4;
+    if num_textures & 0x80000000 != 0 || data.len() < offset_array_end {
+        return Err("texture offsets extend past the end of the lump");
+    }
+
+    let offsets: Vec<usize> = data[4..offset_array_end]
+        .chunks(4)
+        .map(|x| LittleEndian::read_u32(x) as usize)
+        .collect();
+    for (i, &start) in offsets.iter().enumerate() {
+        let end = offsets.get(i + 1).copied().unwrap_or(data.len());
+        if start < offset_array_end || start > end || end > data.len() {
+            return Err("texture offset points outside of the texture data");
+        }
+        if end - start < 22 {
+            return Err("texture too short for its header");
+        }
+        let patch_count = LittleEndian::read_u16(&data[start + 20..start + 22]) as usize;
+        if end - start < 22 + patch_count * 10 {
+            return Err("texture patches extend past the end of the texture");
+        }
+    }
+
+    Ok(())
+}
 
 pub struct TextureDirectory<'a> {
     offsets: &'a [[u8; 4]],
@@ -9,6 +46,7 @@ pub struct TextureDirectory<'a> {
 }
 
 impl<'a> TextureDirectory<'a> {
+    #[allow(mismatched_lifetime_syntaxes)]
     pub fn new(data: &[u8]) -> TextureDirectory {
         let num_textures = LittleEndian::read_u32(&data[0..4]);
         assert!(num_textures & 0x80000000 == 0);
@@ -31,6 +69,7 @@ impl<'a> TextureDirectory<'a> {
         TextureDirectory { offsets, data }
     }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> u32 {
         self.offsets.len() as u32
     }
@@ -47,21 +86,28 @@ impl<'a> TextureDirectory<'a> {
     }
 }
 
+/// ZDoom flag for textures whose offsets are panned in world units rather
+/// than texture pixels, which matters for scaled textures
+pub const TEXTURE_WORLD_PANNING: u16 = 0x8000;
+
+#[derive(Clone, Copy)]
 pub struct Texture<'a> {
     name: [u8; 8],
-    // masked: bool,
+    masked: [u8; 4],
     width: u16,
     height: u16,
-    // columndirectory: u32,
-    // patch_count: u16,
+    columndirectory: u32,
     patch_data: &'a [[u8; 10]],
 }
 
 impl<'a> Texture<'a> {
+    #[allow(mismatched_lifetime_syntaxes)]
     pub fn new(data: &[u8]) -> Texture {
         let name = data[0..8].try_into().unwrap();
+        let masked = data[8..12].try_into().unwrap();
         let width = LittleEndian::read_u16(&data[12..14]);
         let height = LittleEndian::read_u16(&data[14..16]);
+        let columndirectory = LittleEndian::read_u32(&data[16..20]);
         let patch_count = LittleEndian::read_u16(&data[20..22]);
 
         let patch_data_start = 22;
@@ -81,8 +127,10 @@ impl<'a> Texture<'a> {
 
         Texture {
             name,
+            masked,
             width,
             height,
+            columndirectory,
             patch_data,
         }
     }
@@ -91,6 +139,40 @@ impl<'a> Texture<'a> {
         self.name
     }
 
+    /// The raw bytes of the masked field, which ZDoom reuses for flags and
+    /// scale. See `masked`, `flags`, `scale_x` and `scale_y`
+    pub fn masked_field(&self) -> [u8; 4] {
+        self.masked
+    }
+
+    /// The masked flag of the vanilla format, set by the original tools for
+    /// textures with transparent parts. The vanilla engine ignores it
+    pub fn masked(&self) -> bool {
+        LittleEndian::read_u32(&self.masked) != 0
+    }
+
+    /// The flags ZDoom reads from the first half of the masked field, such
+    /// as `TEXTURE_WORLD_PANNING`
+    pub fn flags(&self) -> u16 {
+        LittleEndian::read_u16(&self.masked[0..2])
+    }
+
+    pub fn world_panning(&self) -> bool {
+        self.flags() & TEXTURE_WORLD_PANNING != 0
+    }
+
+    /// The horizontal scale ZDoom reads from the third byte of the masked
+    /// field, in eighths. 0 means unscaled
+    pub fn scale_x(&self) -> u8 {
+        self.masked[2]
+    }
+
+    /// The vertical scale ZDoom reads from the fourth byte of the masked
+    /// field, in eighths. 0 means unscaled
+    pub fn scale_y(&self) -> u8 {
+        self.masked[3]
+    }
+
     pub fn width(&self) -> u16 {
         self.width
     }
@@ -99,10 +181,20 @@ impl<'a> Texture<'a> {
         self.height
     }
 
-    pub fn len(&self) -> u16 {
+    /// Unused by the engine. Always 0 in the IWADs
+    pub fn columndirectory(&self) -> u32 {
+        self.columndirectory
+    }
+
+    pub fn patch_count(&self) -> u16 {
         self.patch_data.len() as u16
     }
 
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u16 {
+        self.patch_count()
+    }
+
     pub fn patch(&self, index: u16) -> Patch {
         Patch::new(self.patch_data[index as usize])
     }
@@ -112,25 +204,37 @@ pub struct Patch {
     pub origin_x: i16,
     pub origin_y: i16,
     pub patch_id: u16,
-    // step_dir: u16,
-    // colormap: u16,
+    /// Unused by the engine. Always 1 in the IWADs
+    pub step_dir: u16,
+    /// Unused by the engine. Always 0 in the IWADs
+    pub colormap: u16,
 }
 
 impl Patch {
     pub fn new(data: [u8; 10]) -> Patch {
-        let step_dir = LittleEndian::read_u16(&data[6..8]);
-        let colormap = LittleEndian::read_u16(&data[8..10]);
-        debug_assert_eq!(step_dir, 1);
-        debug_assert_eq!(colormap, 0);
-
         Patch {
             origin_x: LittleEndian::read_i16(&data[0..2]),
             origin_y: LittleEndian::read_i16(&data[2..4]),
             patch_id: LittleEndian::read_u16(&data[4..6]),
+            step_dir: LittleEndian::read_u16(&data[6..8]),
+            colormap: LittleEndian::read_u16(&data[8..10]),
         }
     }
 }
 
+/// Check that the patch names of a PNAMES lump fit in the lump, so it can be
+/// read with `parse_pnames` without panicking
+pub fn validate_pnames(data: &[u8]) -> Result<(), &'static str> {
+    if data.len() < 4 {
+        return Err("too short for the patch count");
+    }
+    let num_patches = LittleEndian::read_u32(&data[0..4]) as usize;
+    if num_patches & 0x80000000 != 0 || data.len() < 4 + num_patches * 8 {
+        return Err("patch names extend past the end of the lump");
+    }
+    Ok(())
+}
+
 pub fn parse_pnames(data: &[u8]) -> &[[u8; 8]] {
     let num_patches = LittleEndian::read_u32(&data[0..4]);
     assert!(num_patches & 0x80000000 == 0);
@@ -153,25 +257,123 @@ pub fn parse_pnames(data: &[u8]) -> &[[u8; 8]] {
     names
 }
 
-pub trait PatchProvider<'a> {
+/// The lumps with texture definitions, in the order the engine reads them
+pub const TEXTURE_LUMPS: [&[u8; 8]; 2] = [b"TEXTURE1", b"TEXTURE2"];
+
+/// A texture name as used for lookups: upper case and padded with zeros
+fn texture_key(name: &[u8]) -> [u8; 8] {
+    let mut key = [0; 8];
+    for (k, &c) in key.iter_mut().zip(name.iter().take_while(|&&c| c != 0)) {
+        *k = c.to_ascii_uppercase();
+    }
+    key
+}
+
+/// The textures of all the texture directories as one namespace, the way
+/// the engine sees them: the textures of TEXTURE2 are numbered after those
+/// of TEXTURE1. When several textures have the same name, the first one
+/// wins, like in vanilla and Boom. ZDoom and its descendants use the last.
+pub struct TextureSet<'a> {
+    /// Each texture with the index of the directory it is defined in
+    textures: Vec<(usize, Texture<'a>)>,
+    by_name: BTreeMap<[u8; 8], usize>,
+}
+
+impl<'a> TextureSet<'a> {
+    pub fn new(directories: &[TextureDirectory<'a>]) -> TextureSet<'a> {
+        let mut textures = vec![];
+        for (d, directory) in directories.iter().enumerate() {
+            textures.extend((0..directory.len()).map(|i| (d, directory.texture(i))));
+        }
+
+        let mut by_name = BTreeMap::new();
+        for (i, (_, texture)) in textures.iter().enumerate() {
+            by_name.entry(texture_key(&texture.name())).or_insert(i);
+        }
+
+        TextureSet { textures, by_name }
+    }
+
+    /// Collect the textures of TEXTURE1 and TEXTURE2, taking each lump from
+    /// the last WAD that has it. Lumps that fail `validate_texture_directory`
+    /// are left out
+    pub fn from_index(index: &ResourceIndex<'a>) -> TextureSet<'a> {
+        let directories: Vec<_> = TEXTURE_LUMPS
+            .iter()
+            .filter_map(|&lump| index.by_id(lump))
+            .filter(|data| validate_texture_directory(data).is_ok())
+            .map(TextureDirectory::new)
+            .collect();
+        TextureSet::new(&directories)
+    }
+
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty()
+    }
+
+    pub fn texture(&self, index: usize) -> Texture<'a> {
+        self.textures[index].1
+    }
+
+    /// The index of the directory the texture at the given index is
+    /// defined in
+    pub fn directory(&self, index: usize) -> usize {
+        self.textures[index].0
+    }
+
+    /// Find the index of a texture by name, ignoring case
+    pub fn find_index(&self, name: &[u8]) -> Option<usize> {
+        self.by_name.get(&texture_key(name)).copied()
+    }
+
+    pub fn find(&self, name: &[u8]) -> Option<Texture<'a>> {
+        self.find_index(name).map(|index| self.texture(index))
+    }
+
+    /// Whether an earlier texture of the same name takes precedence over the
+    /// texture at the given index
+    pub fn is_overridden(&self, index: usize) -> bool {
+        self.find_index(&self.texture(index).name()) != Some(index)
+    }
+
+    /// The textures that are not overridden, in order
+    pub fn iter(&self) -> impl Iterator<Item = Texture<'a>> + '_ {
+        (0..self.len())
+            .filter(move |&i| !self.is_overridden(i))
+            .map(move |i| self.texture(i))
+    }
+}
+
+/// Looks up the patches of textures by their index in PNAMES. Providers are
+/// shared between threads when textures are rendered in parallel. Patches
+/// that are missing or malformed are None, so the patches that are given
+/// can be drawn without panicking.
+pub trait PatchProvider<'a>: Send + Sync {
     fn patch(&self, id: u16) -> Option<Sprite<'a>>;
 }
 
+/// Looks up patches by name when they are used. Patches are looked up in
+/// the order of `PATCH_NAMESPACES`.
 pub struct LazyPatchProvider<'a> {
-    wad: wad::WadSlice<'a>,
+    index: &'a ResourceIndex<'a>,
     pnames: &'a [[u8; 8]],
 }
 
 impl<'a> LazyPatchProvider<'a> {
-    pub fn new(wad: wad::WadSlice<'a>, pnames: &'a [[u8; 8]]) -> LazyPatchProvider<'a> {
-        LazyPatchProvider { wad, pnames }
+    pub fn new(index: &'a ResourceIndex<'a>, pnames: &'a [[u8; 8]]) -> LazyPatchProvider<'a> {
+        LazyPatchProvider { index, pnames }
     }
 }
 
 impl<'a> PatchProvider<'a> for LazyPatchProvider<'a> {
     fn patch(&self, id: u16) -> Option<Sprite<'a>> {
         let name = self.pnames.get(id as usize)?;
-        let sprite = self.wad.by_id(name)?;
+        let sprite = self.index.find(name, PATCH_NAMESPACES)?;
+        validate_patch(sprite).ok()?;
         Some(Sprite::new(sprite))
     }
 }
@@ -181,9 +383,16 @@ pub struct EagerPatchProvider<'a> {
 }
 
 impl<'a> EagerPatchProvider<'a> {
-    pub fn new(wad: wad::WadSlice<'a>, pnames: &[[u8; 8]]) -> EagerPatchProvider<'a> {
+    pub fn new(index: &ResourceIndex<'a>, pnames: &[[u8; 8]]) -> EagerPatchProvider<'a> {
         EagerPatchProvider {
-            patches: pnames.iter().map(|id| wad.by_id(id)).collect(),
+            patches: pnames
+                .iter()
+                .map(|id| {
+                    index
+                        .find(id, PATCH_NAMESPACES)
+                        .filter(|x| validate_patch(x).is_ok())
+                })
+                .collect(),
         }
     }
 }
@@ -194,10 +403,10 @@ impl<'a> PatchProvider<'a> for EagerPatchProvider<'a> {
     }
 }
 
-pub fn render_texture<'a>(texture: Texture, patch_provider: &impl PatchProvider<'a>) -> Vec<u8> {
+fn composite<'a>(texture: Texture, patch_provider: &impl PatchProvider<'a>) -> SpriteCanvas {
     let mut canvas = SpriteCanvas::new(texture.width, texture.height);
     for p in 0..texture.len() {
-        let patch = texture.patch(p as u16);
+        let patch = texture.patch(p);
         let sprite = patch_provider
             .patch(patch.patch_id)
             .expect("Missing patches not handled");
@@ -208,12 +417,30 @@ pub fn render_texture<'a>(texture: Texture, patch_provider: &impl PatchProvider<
         );
     }
 
-    canvas.make_sprite()
+    canvas
+}
+
+/// Composite a texture from its patches into patch format. The patch format
+/// cannot hold posts longer than 128 pixels or starting below row 255, so
+/// this panics for textures that need them. See `render_texture_planes`.
+pub fn render_texture<'a>(texture: Texture, patch_provider: &impl PatchProvider<'a>) -> Vec<u8> {
+    composite(texture, patch_provider).make_sprite()
+}
+
+/// Composite a texture from its patches into pixels and mask as (y, x)
+/// planes. This handles textures of any size the format allows.
+pub fn render_texture_planes<'a>(
+    texture: Texture,
+    patch_provider: &impl PatchProvider<'a>,
+) -> (Array2<u8>, Array2<bool>) {
+    composite(texture, patch_provider).into_planes_row_major()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::test_util::{pnames, test_wad, texture, TestPatch, TestPatchProvider};
+    use ndarray::s;
 
     #[test]
     fn construct_ok() {
@@ -221,6 +448,20 @@ mod test {
         assert_eq!(texture_dir.len(), 125);
     }
 
+    #[test]
+    fn validate() {
+        let texture_dir = include_bytes!("texture1.texture_dir");
+        assert_eq!(validate_texture_directory(texture_dir), Ok(()));
+        assert!(validate_texture_directory(&texture_dir[..2]).is_err());
+        assert!(validate_texture_directory(&texture_dir[..300]).is_err());
+        assert!(validate_texture_directory(&texture_dir[..texture_dir.len() - 1]).is_err());
+
+        let pnames = pnames(&["WALL00_1", "WALL00_2"]);
+        assert_eq!(validate_pnames(&pnames), Ok(()));
+        assert!(validate_pnames(&pnames[..pnames.len() - 1]).is_err());
+        assert!(validate_pnames(&[]).is_err());
+    }
+
     #[test]
     fn get_all_textures() {
         let texture_dir = TextureDirectory::new(include_bytes!("texture1.texture_dir"));
@@ -231,6 +472,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_cast)]
     fn get_all_patches() {
         let texture_dir = TextureDirectory::new(include_bytes!("texture1.texture_dir"));
 
@@ -243,6 +485,101 @@ mod test {
         }
     }
 
+    #[test]
+    fn texture_set_merges_directories() {
+        fn directory(names: &[&[u8; 8]]) -> Vec<u8> {
+            let mut data = vec![];
+            data.extend(&(names.len() as u32).to_le_bytes());
+            for i in 0..names.len() {
+                data.extend(&(4 + 4 * names.len() as u32 + 22 * i as u32).to_le_bytes());
+            }
+            for name in names {
+                data.extend(&name[..]);
+                data.extend(&[0; 14]);
+            }
+            data
+        }
+
+        let texture1 = directory(&[b"STARTAN3", b"BIGDOOR2"]);
+        let texture2 = directory(&[b"BIGDOOR2", b"SKY2\0\0\0\0"]);
+        let set = TextureSet::new(&[
+            TextureDirectory::new(&texture1),
+            TextureDirectory::new(&texture2),
+        ]);
+
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.find_index(b"startan3"), Some(0));
+        assert_eq!(set.find_index(b"BIGDOOR2"), Some(1));
+        assert_eq!(set.directory(2), 1);
+        assert_eq!(set.find_index(b"SKY2"), Some(3));
+        assert_eq!(set.find_index(b"SKY3"), None);
+        assert!(set.is_overridden(2));
+        assert!(!set.is_overridden(1));
+        assert_eq!(set.iter().count(), 3);
+    }
+
+    #[test]
+    fn render_large_texture_planes() {
+        // Tiling the patch gives posts that are too tall for the patch
+        // format, far below row 255
+        let (width, height) = (41u16, 2000u16);
+        let sprite = Sprite::new(include_bytes!("trooa1.sprite"));
+        let patches: Vec<TestPatch> = (0..height / sprite.height() + 1)
+            .map(|i| (0, (i * sprite.height()) as i16, 0))
+            .collect();
+        let data = texture("TALL", width, height, &patches);
+
+        let (pixels, mask) = render_texture_planes(Texture::new(&data), &TestPatchProvider);
+        assert_eq!(pixels.dim(), (height as usize, width as usize));
+        let bottom = height as usize - 1;
+        let y = bottom % sprite.height() as usize;
+        assert_eq!(mask.slice(s![bottom, ..]), mask.slice(s![y, ..]),);
+        assert_eq!(pixels.slice(s![bottom, ..]), pixels.slice(s![y, ..]),);
+    }
+
+    #[test]
+    fn malformed_patches_are_none() {
+        let pnames = pnames(&["TROOA1", "BROKEN"]);
+        let wad = test_wad(&[
+            ("PNAMES", &pnames),
+            ("P_START", b""),
+            ("TROOA1", include_bytes!("trooa1.sprite")),
+            ("BROKEN", b"\x10\0\x10\0"),
+            ("P_END", b""),
+        ]);
+        let index = ResourceIndex::new(wad.as_slice());
+        let pnames = parse_pnames(index.by_id(b"PNAMES").unwrap());
+
+        let lazy = LazyPatchProvider::new(&index, pnames);
+        let eager = EagerPatchProvider::new(&index, pnames);
+        assert!(lazy.patch(0).is_some() && eager.patch(0).is_some());
+        assert!(lazy.patch(1).is_none() && eager.patch(1).is_none());
+
+        // The vanilla renderer skips the patch rather than panicking
+        let data = texture("TEST", 16, 16, &[(0, 0, 1)]);
+        let (_, mask) = crate::render_texture_vanilla(Texture::new(&data), &lazy, true);
+        assert!(mask.iter().all(|&x| !x));
+    }
+
+    #[test]
+    fn zdoom_header_fields() {
+        #[rustfmt::skip]
+        let texture = Texture::new(&[
+            b'N', b'A', b'M', b'E', 0, 0, 0, 0,
+            0, 0x80, 16, 4, // flags, scale x, scale y
+            16, 0, // width
+            16, 0, // height
+            0, 0, 0, 0,
+            0, 0, // patch count
+        ]);
+
+        assert!(texture.masked());
+        assert!(texture.world_panning());
+        assert_eq!(texture.scale_x(), 16);
+        assert_eq!(texture.scale_y(), 4);
+        assert_eq!(texture.columndirectory(), 0);
+    }
+
     #[test]
     fn parse_pnames_successful() {
         let pnames = parse_pnames(include_bytes!("pnames.pnames"));
@@ -252,6 +589,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::deprecated_cfg_attr)]
     fn basic_render_texture() {
         struct TestPatchProvider;
 
@@ -293,6 +631,8 @@ mod test {
             76, 74, 73, 71, 0, 255,
         ];
 
+        assert!(!texture.masked());
+        assert_eq!(texture.patch_count(), 1);
         assert_eq!(sprite_data.len(), expected.len());
         assert!(sprite_data.iter().zip(expected.iter()).all(|(a, b)| a == b));
     }
@@ -0,0 +1,666 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::*;
+
+/// An error in a TEXTMAP lump, at the position where it was found. Errors
+/// in the contents of a block, like a missing field, are reported at the
+/// start of the block.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UdmfError {
+    /// The line of the error, starting at 1
+    pub line: usize,
+    /// The column of the error, in characters, starting at 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for UdmfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for UdmfError {}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum UdmfValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+}
+
+/// A block of assignments, like `vertex { x = 0.0; y = 64.0; }`
+#[derive(Debug, PartialEq, Clone)]
+pub struct UdmfBlock {
+    /// The kind of block, in lowercase
+    pub kind: String,
+    pub line: usize,
+    pub column: usize,
+    /// The fields of the block by their names, in lowercase
+    pub fields: BTreeMap<String, UdmfValue>,
+}
+
+/// The parsed contents of a TEXTMAP lump
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Udmf {
+    /// The assignments outside of blocks, like the namespace
+    pub globals: BTreeMap<String, UdmfValue>,
+    /// The blocks, in the order they appear
+    pub blocks: Vec<UdmfBlock>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Identifier(String),
+    Value(UdmfValue),
+    Punctuation(char),
+}
+
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(text: &'a str) -> Tokenizer<'a> {
+        Tokenizer {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> UdmfError {
+        UdmfError {
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn take_while(&mut self, mut pred: impl FnMut(char) -> bool) -> String {
+        let mut s = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !pred(c) {
+                break;
+            }
+            s.push(c);
+            self.bump();
+        }
+        s
+    }
+
+    /// Skip whitespace and comments
+    fn skip(&mut self) -> Result<(), UdmfError> {
+        loop {
+            self.take_while(char::is_whitespace);
+            if self.chars.peek() != Some(&'/') {
+                return Ok(());
+            }
+            let (line, column) = (self.line, self.column);
+            self.bump();
+            match self.bump() {
+                Some('/') => {
+                    self.take_while(|c| c != '\n');
+                }
+                Some('*') => {
+                    let mut prev = None;
+                    loop {
+                        match self.bump() {
+                            Some('/') if prev == Some('*') => break,
+                            None => {
+                                return Err(UdmfError {
+                                    line,
+                                    column,
+                                    message: "unterminated comment".into(),
+                                })
+                            }
+                            c => prev = c,
+                        }
+                    }
+                }
+                _ => {
+                    return Err(UdmfError {
+                        line,
+                        column,
+                        message: "unexpected '/'".into(),
+                    })
+                }
+            }
+        }
+    }
+
+    /// The next token with its position, or None at the end of the text
+    fn next(&mut self) -> Result<Option<(Token, usize, usize)>, UdmfError> {
+        self.skip()?;
+        let (line, column) = (self.line, self.column);
+        let c = match self.chars.peek() {
+            Some(&c) => c,
+            None => return Ok(None),
+        };
+
+        let token = if c.is_ascii_alphabetic() || c == '_' {
+            let word = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+            match word.to_ascii_lowercase().as_str() {
+                "true" => Token::Value(UdmfValue::Bool(true)),
+                "false" => Token::Value(UdmfValue::Bool(false)),
+                word => Token::Identifier(word.to_string()),
+            }
+        } else if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+            Token::Value(self.number()?)
+        } else if c == '"' {
+            Token::Value(UdmfValue::Str(self.string()?))
+        } else if "{}=;".contains(c) {
+            self.bump();
+            Token::Punctuation(c)
+        } else {
+            return Err(self.error(format!("unexpected {:?}", c)));
+        };
+        Ok(Some((token, line, column)))
+    }
+
+    fn number(&mut self) -> Result<UdmfValue, UdmfError> {
+        let (line, column) = (self.line, self.column);
+        let text = self.take_while(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+        let invalid = || UdmfError {
+            line,
+            column,
+            message: format!("invalid number {:?}", text),
+        };
+
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+        let sign = if negative { -1 } else { 1 };
+
+        let hex = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"));
+        if let Some(hex) = hex {
+            return i64::from_str_radix(hex, 16)
+                .map(|x| UdmfValue::Int(sign * x))
+                .map_err(|_| invalid());
+        }
+        if digits.contains(['.', 'e', 'E']) {
+            return text.parse().map(UdmfValue::Float).map_err(|_| invalid());
+        }
+        let radix = if digits.len() > 1 && digits.starts_with('0') {
+            8
+        } else {
+            10
+        };
+        i64::from_str_radix(digits, radix)
+            .map(|x| UdmfValue::Int(sign * x))
+            .map_err(|_| invalid())
+    }
+
+    fn string(&mut self) -> Result<String, UdmfError> {
+        let (line, column) = (self.line, self.column);
+        self.bump();
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.bump() {
+                    Some(c) => s.push(c),
+                    None => break,
+                },
+                Some(c) => s.push(c),
+                None => break,
+            }
+        }
+        Err(UdmfError {
+            line,
+            column,
+            message: "unterminated string".into(),
+        })
+    }
+}
+
+/// Parse the text of a TEXTMAP lump into its assignments and blocks,
+/// without interpreting them
+pub fn parse_udmf(text: &str) -> Result<Udmf, UdmfError> {
+    let mut tokenizer = Tokenizer::new(text);
+    let mut udmf = Udmf::default();
+
+    let expected = |tokenizer: &Tokenizer, what: &str, got: Option<(Token, usize, usize)>| match got
+    {
+        Some((_, line, column)) => UdmfError {
+            line,
+            column,
+            message: format!("expected {}", what),
+        },
+        None => tokenizer.error(format!("expected {} before the end", what)),
+    };
+
+    // Parses the value and semicolon of an assignment
+    let value = |tokenizer: &mut Tokenizer| -> Result<UdmfValue, UdmfError> {
+        let value = match tokenizer.next()? {
+            Some((Token::Value(value), _, _)) => value,
+            got => return Err(expected(tokenizer, "a value", got)),
+        };
+        match tokenizer.next()? {
+            Some((Token::Punctuation(';'), _, _)) => Ok(value),
+            got => Err(expected(tokenizer, "';'", got)),
+        }
+    };
+
+    while let Some(token) = tokenizer.next()? {
+        let (name, line, column) = match token {
+            (Token::Identifier(name), line, column) => (name.to_ascii_lowercase(), line, column),
+            got => return Err(expected(&tokenizer, "an identifier", Some(got))),
+        };
+        match tokenizer.next()? {
+            Some((Token::Punctuation('='), _, _)) => {
+                let value = value(&mut tokenizer)?;
+                udmf.globals.insert(name, value);
+            }
+            Some((Token::Punctuation('{'), _, _)) => {
+                let mut fields = BTreeMap::new();
+                loop {
+                    let field = match tokenizer.next()? {
+                        Some((Token::Punctuation('}'), _, _)) => break,
+                        Some((Token::Identifier(field), _, _)) => field.to_ascii_lowercase(),
+                        got => return Err(expected(&tokenizer, "a field or '}'", got)),
+                    };
+                    match tokenizer.next()? {
+                        Some((Token::Punctuation('='), _, _)) => (),
+                        got => return Err(expected(&tokenizer, "'='", got)),
+                    }
+                    fields.insert(field, value(&mut tokenizer)?);
+                }
+                udmf.blocks.push(UdmfBlock {
+                    kind: name,
+                    line,
+                    column,
+                    fields,
+                });
+            }
+            got => return Err(expected(&tokenizer, "'=' or '{'", got)),
+        }
+    }
+
+    Ok(udmf)
+}
+
+impl UdmfBlock {
+    fn error(&self, message: String) -> UdmfError {
+        UdmfError {
+            line: self.line,
+            column: self.column,
+            message: format!("{}: {}", self.kind, message),
+        }
+    }
+
+    fn float(&self, name: &str, default: Option<f64>) -> Result<f64, UdmfError> {
+        match self.fields.get(name) {
+            Some(&UdmfValue::Float(x)) => Ok(x),
+            Some(&UdmfValue::Int(x)) => Ok(x as f64),
+            Some(_) => Err(self.error(format!("{} must be a number", name))),
+            None => default.ok_or_else(|| self.error(format!("missing {}", name))),
+        }
+    }
+
+    fn int(&self, name: &str, default: Option<i64>) -> Result<i64, UdmfError> {
+        match self.fields.get(name) {
+            Some(&UdmfValue::Int(x)) => Ok(x),
+            Some(_) => Err(self.error(format!("{} must be an integer", name))),
+            None => default.ok_or_else(|| self.error(format!("missing {}", name))),
+        }
+    }
+
+    fn bool(&self, name: &str) -> Result<bool, UdmfError> {
+        match self.fields.get(name) {
+            Some(&UdmfValue::Bool(x)) => Ok(x),
+            Some(_) => Err(self.error(format!("{} must be true or false", name))),
+            None => Ok(false),
+        }
+    }
+
+    /// A texture or flat name, uppercased and cut or padded to the eight
+    /// bytes of a lump name
+    fn name(&self, name: &str, default: Option<&str>) -> Result<[u8; 8], UdmfError> {
+        let value = match self.fields.get(name) {
+            Some(UdmfValue::Str(x)) => x.as_str(),
+            Some(_) => return Err(self.error(format!("{} must be a string", name))),
+            None => default.ok_or_else(|| self.error(format!("missing {}", name)))?,
+        };
+        let mut bytes = [0; 8];
+        for (b, c) in bytes.iter_mut().zip(value.bytes()) {
+            *b = c.to_ascii_uppercase();
+        }
+        Ok(bytes)
+    }
+
+    /// A number that must fit the type of the field it is stored in
+    fn ranged<T: TryFrom<i64>>(&self, name: &str, value: i64) -> Result<T, UdmfError> {
+        T::try_from(value).map_err(|_| self.error(format!("{} is out of range", name)))
+    }
+
+    fn coordinate(&self, name: &str, default: Option<f64>) -> Result<i16, UdmfError> {
+        let value = self.float(name, default)?.round();
+        if value < i16::MIN as f64 || value > i16::MAX as f64 {
+            return Err(self.error(format!("{} is out of range", name)));
+        }
+        Ok(value as i16)
+    }
+
+    fn args(&self) -> Result<[i32; 5], UdmfError> {
+        let mut args = [0; 5];
+        for (i, arg) in args.iter_mut().enumerate() {
+            let name = format!("arg{}", i);
+            *arg = self.ranged(&name, self.int(&name, Some(0))?)?;
+        }
+        Ok(args)
+    }
+
+    fn flags(&self, flags: &[(&str, u16)]) -> Result<u16, UdmfError> {
+        let mut bits = 0;
+        for &(name, bit) in flags {
+            if self.bool(name)? {
+                bits |= bit;
+            }
+        }
+        Ok(bits)
+    }
+
+    fn vertex(&self) -> Result<Vertex, UdmfError> {
+        Ok(Vertex {
+            x: self.coordinate("x", None)?,
+            y: self.coordinate("y", None)?,
+        })
+    }
+
+    fn linedef(&self) -> Result<Linedef, UdmfError> {
+        let side = |name, default| -> Result<Option<u16>, UdmfError> {
+            match self.int(name, default)? {
+                -1 => Ok(None),
+                x => self.ranged(name, x).map(Some),
+            }
+        };
+        Ok(Linedef {
+            v1: self.ranged("v1", self.int("v1", None)?)?,
+            v2: self.ranged("v2", self.int("v2", None)?)?,
+            flags: self.flags(&[
+                ("blocking", LINEDEF_BLOCKING),
+                ("blockmonsters", LINEDEF_BLOCK_MONSTERS),
+                ("twosided", LINEDEF_TWO_SIDED),
+                ("dontpegtop", LINEDEF_UPPER_UNPEGGED),
+                ("dontpegbottom", LINEDEF_LOWER_UNPEGGED),
+                ("secret", LINEDEF_SECRET),
+                ("blocksound", LINEDEF_BLOCK_SOUND),
+                ("dontdraw", LINEDEF_DONT_DRAW),
+                ("mapped", LINEDEF_MAPPED),
+            ])?,
+            special: self.ranged("special", self.int("special", Some(0))?)?,
+            tag: self.ranged("id", self.int("id", Some(0))?.max(0))?,
+            args: self.args()?,
+            front: side("sidefront", None)?,
+            back: side("sideback", Some(-1))?,
+        })
+    }
+
+    fn sidedef(&self) -> Result<Sidedef, UdmfError> {
+        Ok(Sidedef {
+            x_offset: self.ranged("offsetx", self.int("offsetx", Some(0))?)?,
+            y_offset: self.ranged("offsety", self.int("offsety", Some(0))?)?,
+            upper: self.name("texturetop", Some("-"))?,
+            lower: self.name("texturebottom", Some("-"))?,
+            middle: self.name("texturemiddle", Some("-"))?,
+            sector: self.ranged("sector", self.int("sector", None)?)?,
+        })
+    }
+
+    fn sector(&self) -> Result<Sector, UdmfError> {
+        Ok(Sector {
+            floor_height: self.ranged("heightfloor", self.int("heightfloor", Some(0))?)?,
+            ceiling_height: self.ranged("heightceiling", self.int("heightceiling", Some(0))?)?,
+            floor_flat: self.name("texturefloor", None)?,
+            ceiling_flat: self.name("textureceiling", None)?,
+            light: self.ranged("lightlevel", self.int("lightlevel", Some(160))?)?,
+            special: self.ranged("special", self.int("special", Some(0))?)?,
+            tag: self.ranged("id", self.int("id", Some(0))?)?,
+        })
+    }
+
+    fn thing(&self) -> Result<Thing, UdmfError> {
+        // UDMF has a flag for each skill level, where the Doom format
+        // shares a flag between the two easiest and the two hardest
+        let mut flags = self.flags(&[
+            ("skill1", THING_EASY),
+            ("skill2", THING_EASY),
+            ("skill3", THING_NORMAL),
+            ("skill4", THING_HARD),
+            ("skill5", THING_HARD),
+            ("ambush", THING_AMBUSH),
+        ])?;
+        for &(name, bit) in &[
+            ("single", THING_NOT_SINGLE),
+            ("coop", THING_NOT_COOP),
+            ("dm", THING_NOT_DEATHMATCH),
+        ] {
+            if !self.bool(name)? {
+                flags |= bit;
+            }
+        }
+        Ok(Thing {
+            tid: self.ranged("id", self.int("id", Some(0))?)?,
+            x: self.coordinate("x", None)?,
+            y: self.coordinate("y", None)?,
+            z: self.coordinate("height", Some(0.))?,
+            angle: self.ranged("angle", self.int("angle", Some(0))?)?,
+            doomednum: self.ranged("type", self.int("type", None)?)?,
+            flags,
+            special: self.ranged("special", self.int("special", Some(0))?)?,
+            args: self.args()?,
+        })
+    }
+}
+
+impl Map {
+    /// Load a map from the text of its TEXTMAP lump. Blocks and fields
+    /// that do not matter to the map model are ignored, as are the
+    /// extensions of source ports. Texture and flat names are cut to the
+    /// eight characters of a lump name.
+    pub fn from_udmf(name: String, text: &str) -> Result<Map, UdmfError> {
+        let udmf = parse_udmf(text)?;
+
+        let namespace = match udmf.globals.get("namespace") {
+            Some(UdmfValue::Str(namespace)) => namespace.to_ascii_lowercase(),
+            _ => {
+                return Err(UdmfError {
+                    line: 1,
+                    column: 1,
+                    message: "missing namespace".into(),
+                })
+            }
+        };
+
+        let mut map = Map {
+            name,
+            format: MapFormat::Udmf(namespace),
+            vertexes: vec![],
+            linedefs: vec![],
+            sidedefs: vec![],
+            sectors: vec![],
+            things: vec![],
+        };
+        let mut linedef_blocks = vec![];
+        let mut sidedef_blocks = vec![];
+
+        for block in &udmf.blocks {
+            match block.kind.as_str() {
+                "vertex" => map.vertexes.push(block.vertex()?),
+                "linedef" => {
+                    map.linedefs.push(block.linedef()?);
+                    linedef_blocks.push(block);
+                }
+                "sidedef" => {
+                    map.sidedefs.push(block.sidedef()?);
+                    sidedef_blocks.push(block);
+                }
+                "sector" => map.sectors.push(block.sector()?),
+                "thing" => map.things.push(block.thing()?),
+                _ => (),
+            }
+        }
+
+        // Check the references here, where they can be reported at the
+        // block they are in
+        for (linedef, block) in map.linedefs.iter().zip(linedef_blocks) {
+            if [linedef.v1, linedef.v2]
+                .iter()
+                .any(|&v| v as usize >= map.vertexes.len())
+            {
+                return Err(block.error("no such vertex".into()));
+            }
+            if [linedef.front, linedef.back]
+                .iter()
+                .flatten()
+                .any(|&s| s as usize >= map.sidedefs.len())
+            {
+                return Err(block.error("no such sidedef".into()));
+            }
+        }
+        for (sidedef, block) in map.sidedefs.iter().zip(sidedef_blocks) {
+            if sidedef.sector as usize >= map.sectors.len() {
+                return Err(block.error("no such sector".into()));
+            }
+        }
+
+        map.validate().map_err(|message| UdmfError {
+            line: 1,
+            column: 1,
+            message: message.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_wad::test_wad;
+    use crate::ResourceIndex;
+
+    const TEXTMAP: &str = r#"
+// A triangle with a thing in it
+namespace = "zdoom";
+
+vertex { x = 0.0; y = 0.0; }
+vertex { x = 64.0; y = 0.0; }
+vertex { x = 0.0; y = -64.5; }
+
+linedef { v1 = 0; v2 = 1; sidefront = 0; sideback = 0; twosided = true; special = 70; arg0 = 3; }
+linedef { v1 = 1; v2 = 2; sidefront = 0; id = 7; }
+linedef { v1 = 2; v2 = 0; sidefront = 0; sideback = 0; twosided = true; secret = true; }
+
+sidedef { sector = 0; texturemiddle = "StartAn3"; offsetx = -8; }
+
+/* Fields of other ports are ignored */
+sector {
+    texturefloor = "FLOOR0_1";
+    textureceiling = "F_SKY1";
+    heightceiling = 128;
+    lightlevel = 192;
+    colorfloor = 0x8080ff;
+}
+
+thing { x = 16.0; y = -16.0; type = 3001; angle = 90; id = 5; skill1 = true; skill2 = true; single = true; }
+"#;
+
+    #[test]
+    fn parse_textmap() {
+        let map = Map::from_udmf("MAP01".into(), TEXTMAP).unwrap();
+        assert_eq!(map.format, MapFormat::Udmf("zdoom".into()));
+        assert!(map.format.hexen_specials());
+
+        assert_eq!(map.vertexes[2], Vertex { x: 0, y: -65 });
+        assert_eq!(map.linedefs[0].flags, LINEDEF_TWO_SIDED);
+        assert_eq!(map.linedefs[0].args, [3, 0, 0, 0, 0]);
+        assert_eq!(map.linedefs[0].back, Some(0));
+        assert_eq!(map.linedefs[1].back, None);
+        assert_eq!(map.linedefs[1].tag, 7);
+        assert_eq!(map.automap_line(&map.linedefs[0]), AutomapLine::Teleporter);
+        assert_eq!(map.automap_line(&map.linedefs[2]), AutomapLine::Secret);
+
+        assert_eq!(&map.sidedefs[0].middle, b"STARTAN3");
+        assert_eq!(&map.sidedefs[0].upper, b"-\0\0\0\0\0\0\0");
+        assert_eq!(map.sidedefs[0].x_offset, -8);
+        assert_eq!(map.sectors[0].ceiling_height, 128);
+        assert_eq!(map.sectors[0].light, 192);
+
+        let thing = &map.things[0];
+        let info = ThingInfo::doom();
+        assert_eq!((thing.x, thing.y, thing.tid), (16, -16, 5));
+        assert_eq!(
+            thing.flags,
+            THING_EASY | THING_NOT_COOP | THING_NOT_DEATHMATCH
+        );
+        assert!(thing.appears(Some(1), Some(GameMode::Single), &info));
+        assert!(!thing.appears(Some(3), Some(GameMode::Single), &info));
+    }
+
+    #[test]
+    fn non_utf8_textmap() {
+        let mut textmap = b"// Caf\xe9 in Latin-1".to_vec();
+        textmap.extend(TEXTMAP.as_bytes());
+        let wad = test_wad(&[("MAP01", b""), ("TEXTMAP", &textmap), ("ENDMAP", b"")]);
+        let index = ResourceIndex::new(wad.as_slice());
+        let map = Map::from_index(&index, "MAP01").unwrap();
+        assert_eq!(map.things.len(), 1);
+    }
+
+    #[test]
+    fn numbers() {
+        let udmf = parse_udmf("a = -12; b = 0x1F; c = 010; d = 1.5e2; e = +3;").unwrap();
+        assert_eq!(udmf.globals["a"], UdmfValue::Int(-12));
+        assert_eq!(udmf.globals["b"], UdmfValue::Int(31));
+        assert_eq!(udmf.globals["c"], UdmfValue::Int(8));
+        assert_eq!(udmf.globals["d"], UdmfValue::Float(150.));
+        assert_eq!(udmf.globals["e"], UdmfValue::Int(3));
+    }
+
+    #[test]
+    fn error_positions() {
+        let err = |text| Map::from_udmf("MAP01".into(), text).err().unwrap();
+
+        let e = err("namespace = \"doom\";\nvertex { x = 0.0\n  y = 1.0; }");
+        assert_eq!((e.line, e.column), (3, 3));
+        assert_eq!(e.message, "expected ';'");
+
+        let e = err("namespace = \"doom\";\n\n  vertex { y = 1.0; }");
+        assert_eq!((e.line, e.column), (3, 3));
+        assert_eq!(e.message, "vertex: missing x");
+
+        let e = err("namespace = \"doom\";\n/* open\n comment");
+        assert_eq!((e.line, e.column), (2, 1));
+
+        let e = err("namespace = \"doom\";\nsidedef { sector = 0; }");
+        assert_eq!((e.line, e.column), (2, 1));
+        assert_eq!(e.message, "sidedef: no such sector");
+        assert_eq!(e.to_string(), "line 2, column 1: sidedef: no such sector");
+
+        let e = err("vertex { x = 0; y = 0; }");
+        assert_eq!(e.message, "missing namespace");
+    }
+}
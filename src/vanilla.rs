@@ -0,0 +1,205 @@
+use ndarray::prelude::*;
+
+use crate::{PatchProvider, Texture};
+
+/// The wall drawer, R_DrawColumn, wraps the texture row with this mask
+const COLUMN_MASK: usize = 127;
+
+/// Parse the bytes starting at a post header as posts, the way
+/// R_DrawMaskedColumn does, and draw them into the column. Parsing stops at
+/// the 0xFF terminator or when the bytes run out.
+fn draw_posts(bytes: &[u8], mut pixels: ArrayViewMut1<u8>, mut mask: ArrayViewMut1<bool>) {
+    let height = pixels.len();
+    let mut pos = 0;
+    loop {
+        let (top, length) = match (bytes.get(pos), bytes.get(pos + 1)) {
+            (Some(255), _) | (None, _) | (_, None) => break,
+            (Some(&top), Some(&length)) => (top as usize, length as usize),
+        };
+        let source = bytes.get(pos + 3..).unwrap_or_default();
+        for (i, &pixel) in source.iter().take(length).enumerate() {
+            if top + i < height {
+                pixels[top + i] = pixel;
+                mask[top + i] = true;
+            }
+        }
+        pos += length + 4;
+    }
+}
+
+/// Composite a texture the way the vanilla renderer does, as seen in
+/// Chocolate Doom, rather than the way it was meant to look. Returns the
+/// pixels and the mask as (y, x) planes.
+///
+/// Like R_GenerateLookup, each column is drawn from the last patch covering
+/// it. Columns covered by a single patch are read straight from the patch,
+/// ignoring the Y offset and the post structure, so patches that are
+/// shorter than the texture, or have holes, show the bytes that follow the
+/// pixels in the lump. This is the tutti-frutti effect. Columns covered by
+/// more than one patch are composited into a solid block like
+/// R_GenerateComposite does, where posts above the top of the texture are
+/// moved down instead of being cut off, and pixels not covered by any patch
+/// are 0. Columns not covered by any patch are left transparent. As in
+/// R_DrawColumn, rows wrap after 128 pixels.
+///
+/// With `masked`, the texture is drawn as a masked mid-texture, like
+/// R_RenderMaskedSegRange does. Single-patch columns are drawn as posts,
+/// with transparency, while composited columns are parsed as posts too,
+/// which gives the Medusa effect.
+pub fn render_texture_vanilla<'a>(
+    texture: Texture,
+    patch_provider: &impl PatchProvider<'a>,
+    masked: bool,
+) -> (Array2<u8>, Array2<bool>) {
+    let width = texture.width() as usize;
+    let height = texture.height() as usize;
+
+    let patches: Vec<_> = (0..texture.len())
+        .map(|p| {
+            let patch = texture.patch(p);
+            let sprite = patch_provider.patch(patch.patch_id)?;
+            let x1 = patch.origin_x as i32;
+            let x_range =
+                x1.max(0) as usize..(x1 + sprite.width() as i32).clamp(0, width as i32) as usize;
+            Some((patch, sprite, x_range))
+        })
+        .collect();
+
+    // R_GenerateLookup: the patch each column is drawn from, and how many
+    // patches cover it
+    let mut lookup = vec![None; width];
+    let mut patch_count = vec![0; width];
+    for (p, (patch, _, x_range)) in patches
+        .iter()
+        .enumerate()
+        .filter_map(|(p, x)| Some((p, x.as_ref()?)))
+    {
+        for x in x_range.clone() {
+            lookup[x] = Some((p, (x as i32 - patch.origin_x as i32) as u32));
+            patch_count[x] += 1;
+        }
+    }
+
+    // R_GenerateComposite: composited columns are stored one after the
+    // other, as solid columns of the height of the texture. The masked
+    // drawer steps back over a post header it expects before each column,
+    // so room is made for that in front of the first one.
+    const HEADER: usize = 3;
+    let mut composite_offset = vec![None; width];
+    let mut composite_size = HEADER;
+    for x in 0..width {
+        if patch_count[x] > 1 {
+            composite_offset[x] = Some(composite_size);
+            composite_size += height;
+        }
+    }
+    let mut composite = vec![0u8; composite_size];
+    for (patch, sprite, x_range) in patches.iter().flatten() {
+        for x in x_range.clone() {
+            let offset = match composite_offset[x] {
+                Some(offset) => offset,
+                None => continue,
+            };
+            let column = &mut composite[offset..offset + height];
+            let src_x = (x as i32 - patch.origin_x as i32) as u32;
+            for span in sprite.col(src_x) {
+                // R_DrawColumnInCache clamps the position, but keeps the
+                // start of the source
+                let mut position = patch.origin_y as i32 + span.top as i32;
+                let mut count = span.pixels.len() as i32;
+                if position < 0 {
+                    count += position;
+                    position = 0;
+                }
+                count = count.min(height as i32 - position);
+                if count > 0 {
+                    let position = position as usize;
+                    column[position..position + count as usize]
+                        .copy_from_slice(&span.pixels[..count as usize]);
+                }
+            }
+        }
+    }
+
+    let mut pixels = Array2::zeros((height, width));
+    let mut mask = Array2::from_elem((height, width), false);
+    for x in 0..width {
+        let (p, src_x) = match lookup[x] {
+            Some(found) => found,
+            None => continue,
+        };
+
+        let sprite = &patches[p].as_ref().unwrap().1;
+        if masked {
+            let posts = match composite_offset[x] {
+                Some(offset) => &composite[offset - HEADER..],
+                None => sprite.col_bytes(src_x),
+            };
+            draw_posts(posts, pixels.column_mut(x), mask.column_mut(x));
+        } else {
+            // The bytes of the column, starting at the first pixel
+            let source = match composite_offset[x] {
+                Some(offset) => &composite[offset..],
+                None => sprite.col_bytes(src_x).get(HEADER..).unwrap_or_default(),
+            };
+            for y in 0..height {
+                pixels[[y, x]] = source.get(y & COLUMN_MASK).copied().unwrap_or(0);
+                mask[[y, x]] = true;
+            }
+        }
+    }
+
+    (pixels, mask)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::{texture, TestPatchProvider};
+    use crate::{render_texture_planes, Sprite};
+    use ndarray::s;
+
+    #[test]
+    fn masked_single_patch_matches_clean() {
+        // Each column is covered by one patch, and the Y offset is 0, so
+        // the masked drawer gives the same result as clean compositing
+        let data = texture("TEST", 41, 57, &[(0, 0, 0)]);
+        let texture = Texture::new(&data);
+        let vanilla = render_texture_vanilla(texture, &TestPatchProvider, true);
+        assert_eq!(vanilla, render_texture_planes(texture, &TestPatchProvider));
+    }
+
+    #[test]
+    fn solid_columns_are_opaque() {
+        let data = texture("TEST", 41, 57, &[(0, 0, 0)]);
+        let (pixels, mask) = render_texture_vanilla(Texture::new(&data), &TestPatchProvider, false);
+        assert!(mask.iter().all(|&x| x));
+
+        // Column 6 of the sprite starts with a post of 3 pixels at row 17,
+        // which is drawn from the top, followed by the bytes of the post
+        // footer and the next post header
+        let sprite = Sprite::new(include_bytes!("trooa1.sprite"));
+        let bytes = sprite.col_bytes(6);
+        assert_eq!(pixels[[0, 6]], bytes[3]);
+        assert_eq!(pixels[[3, 6]], bytes[6]);
+    }
+
+    #[test]
+    fn composited_columns_clamp_negative_y() {
+        let sprite = Sprite::new(include_bytes!("trooa1.sprite"));
+        let first_post = sprite.col(6).next().unwrap();
+
+        // Two patches on top of each other make every column composited.
+        // The first post of column 6 starts one row above the texture, and
+        // is moved down to row 0 rather than having its first pixel cut off
+        let y = -(first_post.top as i16) - 1;
+        let data = texture("TEST", 41, 57, &[(0, y, 0), (0, y, 0)]);
+        let (pixels, mask) = render_texture_vanilla(Texture::new(&data), &TestPatchProvider, false);
+        assert!(mask.iter().all(|&x| x));
+        let len = first_post.pixels.len();
+        assert_eq!(
+            pixels.slice(s![..len - 1, 6]).to_vec(),
+            &first_post.pixels[..len - 1]
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::*;
+
+/// The name of the sky flat. Upper textures between two sectors with sky
+/// ceilings are not drawn, so the sky shows through.
+pub const SKY_FLAT: &[u8; 8] = b"F_SKY1\0\0";
+
+/// The side of a linedef that a sidedef is on
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Side {
+    Front,
+    Back,
+}
+
+impl std::str::FromStr for Side {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "front" | "f" => Ok(Side::Front),
+            "back" | "b" => Ok(Side::Back),
+            _ => Err("Unrecognized side. Valid options are front/f or back/b"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WallPart {
+    Upper,
+    Middle,
+    Lower,
+}
+
+/// Where a texture is pegged vertically
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Peg {
+    /// The top of the texture is at this height
+    Top(i32),
+    /// The bottom of the texture is at this height
+    Bottom(i32),
+}
+
+/// A part of a wall with one texture, between two heights
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct WallSection {
+    pub part: WallPart,
+    pub texture: [u8; 8],
+    pub bottom: i32,
+    pub top: i32,
+    pub peg: Peg,
+    /// Whether the texture repeats vertically. The middle texture of a
+    /// two-sided line is drawn once, and is transparent where it has holes.
+    pub tiled: bool,
+}
+
+impl WallSection {
+    /// The row of the texture to draw just below the given height, for a
+    /// texture of the given height and the Y offset of the sidedef. For
+    /// tiled sections the row must be wrapped to the texture.
+    pub fn texture_row(&self, z: i32, texture_height: i32, y_offset: i16) -> i32 {
+        let top = match self.peg {
+            Peg::Top(top) => top,
+            Peg::Bottom(bottom) => bottom + texture_height,
+        };
+        top - z + y_offset as i32
+    }
+}
+
+/// A sidedef as it is drawn by the engine: the sections of its wall, from
+/// the floor to the ceiling of its sector, and how the textures are
+/// aligned on them
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Wall {
+    /// The length of the linedef, rounded to whole map units
+    pub length: i32,
+    pub bottom: i32,
+    pub top: i32,
+    pub x_offset: i16,
+    pub y_offset: i16,
+    /// The light level of the sector, with the fake contrast of the engine:
+    /// walls along the X axis are darker, and walls along the Y axis are
+    /// lighter
+    pub light: i16,
+    /// The sections with a texture, from top to bottom. A section with a
+    /// missing texture would show the hall of mirrors effect in game, and is
+    /// left out.
+    pub sections: Vec<WallSection>,
+}
+
+impl Wall {
+    /// The row of COLORMAP for the light level of the wall, like
+    /// `Sector::colormap`
+    pub fn colormap(&self) -> usize {
+        (255 - self.light.clamp(0, 255) as usize) / 8
+    }
+}
+
+fn has_texture(name: &[u8; 8]) -> bool {
+    name[0] != b'-' && name[0] != 0
+}
+
+impl Map {
+    /// Lay out the wall of a side of a linedef the way the vanilla renderer
+    /// does, with the upper and lower unpegged flags of the linedef
+    pub fn wall(&self, linedef: usize, side: Side) -> Result<Wall, &'static str> {
+        let linedef = self.linedefs.get(linedef).ok_or("no such linedef")?;
+        let (sidedef, other, v1, v2) = match side {
+            Side::Front => (linedef.front, linedef.back, linedef.v1, linedef.v2),
+            Side::Back => (linedef.back, linedef.front, linedef.v2, linedef.v1),
+        };
+        let sidedef = &self.sidedefs[sidedef.ok_or("the linedef has no such side")? as usize];
+        let front = &self.sectors[sidedef.sector as usize];
+        let back = other.map(|x| &self.sectors[self.sidedefs[x as usize].sector as usize]);
+
+        let (v1, v2) = (self.vertex(v1), self.vertex(v2));
+        let (dx, dy) = (v2.x as f64 - v1.x as f64, v2.y as f64 - v1.y as f64);
+
+        let mut light = front.light;
+        if v1.y == v2.y {
+            light -= 16;
+        } else if v1.x == v2.x {
+            light += 16;
+        }
+
+        let floor = front.floor_height as i32;
+        let ceiling = front.ceiling_height as i32;
+        let upper_unpegged = linedef.flags & LINEDEF_UPPER_UNPEGGED != 0;
+        let lower_unpegged = linedef.flags & LINEDEF_LOWER_UNPEGGED != 0;
+
+        let mut sections = vec![];
+        match back {
+            None => sections.push(WallSection {
+                part: WallPart::Middle,
+                texture: sidedef.middle,
+                bottom: floor,
+                top: ceiling,
+                peg: if lower_unpegged {
+                    Peg::Bottom(floor)
+                } else {
+                    Peg::Top(ceiling)
+                },
+                tiled: true,
+            }),
+            Some(back) => {
+                let back_floor = back.floor_height as i32;
+                let back_ceiling = back.ceiling_height as i32;
+                let sky = &front.ceiling_flat == SKY_FLAT && &back.ceiling_flat == SKY_FLAT;
+
+                if back_ceiling < ceiling && !sky {
+                    sections.push(WallSection {
+                        part: WallPart::Upper,
+                        texture: sidedef.upper,
+                        bottom: back_ceiling.max(floor),
+                        top: ceiling,
+                        peg: if upper_unpegged {
+                            Peg::Top(ceiling)
+                        } else {
+                            Peg::Bottom(back_ceiling)
+                        },
+                        tiled: true,
+                    });
+                }
+                let (low, high) = (floor.max(back_floor), ceiling.min(back_ceiling));
+                if low < high {
+                    sections.push(WallSection {
+                        part: WallPart::Middle,
+                        texture: sidedef.middle,
+                        bottom: low,
+                        top: high,
+                        peg: if lower_unpegged {
+                            Peg::Bottom(low)
+                        } else {
+                            Peg::Top(high)
+                        },
+                        tiled: false,
+                    });
+                }
+                if back_floor > floor {
+                    sections.push(WallSection {
+                        part: WallPart::Lower,
+                        texture: sidedef.lower,
+                        bottom: floor,
+                        top: back_floor.min(ceiling),
+                        peg: if lower_unpegged {
+                            Peg::Top(ceiling)
+                        } else {
+                            Peg::Top(back_floor)
+                        },
+                        tiled: true,
+                    });
+                }
+            }
+        }
+        sections.retain(|x| has_texture(&x.texture) && x.bottom < x.top);
+
+        Ok(Wall {
+            length: (dx * dx + dy * dy).sqrt().round() as i32,
+            bottom: floor,
+            top: ceiling,
+            x_offset: sidedef.x_offset,
+            y_offset: sidedef.y_offset,
+            light,
+            sections,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A room with a raised ceiling of sky in the middle, separated by a
+    // two-sided line with a grate on it, and a step up
+    const TEXTMAP: &str = r#"
+namespace = "doom";
+vertex { x = 0.0; y = 0.0; }
+vertex { x = 0.0; y = 128.0; }
+vertex { x = 96.0; y = 0.0; }
+linedef { v1 = 0; v2 = 1; sidefront = 0; sideback = 1; twosided = true; }
+linedef { v1 = 1; v2 = 2; sidefront = 2; dontpegtop = true; dontpegbottom = true; }
+sidedef { sector = 0; texturetop = "STARTAN3"; texturemiddle = "MIDGRATE";
+          texturebottom = "STEP1"; offsetx = 4; offsety = -2; }
+sidedef { sector = 1; texturetop = "STARTAN3"; }
+sidedef { sector = 0; texturemiddle = "STARTAN3"; }
+sector { heightfloor = 0; heightceiling = 128; texturefloor = "FLOOR0_1";
+         textureceiling = "F_SKY1"; lightlevel = 160; }
+sector { heightfloor = 24; heightceiling = 200; texturefloor = "FLOOR0_1";
+         textureceiling = "F_SKY1"; lightlevel = 160; }
+"#;
+
+    #[test]
+    fn two_sided() {
+        let map = Map::from_udmf("MAP01".into(), TEXTMAP).unwrap();
+
+        let wall = map.wall(0, Side::Front).unwrap();
+        assert_eq!((wall.length, wall.bottom, wall.top), (128, 0, 128));
+        assert_eq!((wall.x_offset, wall.y_offset), (4, -2));
+        // Along the Y axis
+        assert_eq!(wall.light, 176);
+        // No upper: the back ceiling is higher
+        assert_eq!(
+            wall.sections,
+            [
+                WallSection {
+                    part: WallPart::Middle,
+                    texture: *b"MIDGRATE",
+                    bottom: 24,
+                    top: 128,
+                    peg: Peg::Top(128),
+                    tiled: false,
+                },
+                WallSection {
+                    part: WallPart::Lower,
+                    texture: *b"STEP1\0\0\0",
+                    bottom: 0,
+                    top: 24,
+                    peg: Peg::Top(24),
+                    tiled: true,
+                },
+            ]
+        );
+        assert_eq!(wall.sections[1].texture_row(24, 16, wall.y_offset), -2);
+
+        // The upper of the back side is under the sky hack, and the
+        // middle and lower have no texture
+        let wall = map.wall(0, Side::Back).unwrap();
+        assert_eq!((wall.bottom, wall.top), (24, 200));
+        assert!(wall.sections.is_empty());
+    }
+
+    #[test]
+    fn one_sided() {
+        let map = Map::from_udmf("MAP01".into(), TEXTMAP).unwrap();
+        let wall = map.wall(1, Side::Front).unwrap();
+        assert_eq!(wall.length, 160);
+        assert_eq!(wall.light, 160);
+        assert_eq!(wall.sections.len(), 1);
+        let section = wall.sections[0];
+        assert_eq!(section.peg, Peg::Bottom(0));
+        // The bottom of a 72 high texture is at the floor
+        assert_eq!(section.texture_row(128, 72, 0), -56);
+        assert_eq!(section.texture_row(1, 72, 0), 71);
+
+        assert!(map.wall(1, Side::Back).is_err());
+        assert!(map.wall(2, Side::Front).is_err());
+    }
+}